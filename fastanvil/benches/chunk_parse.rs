@@ -1,5 +1,5 @@
 use criterion::{black_box, criterion_group, criterion_main, Criterion};
-use fastanvil::JavaChunk;
+use fastanvil::{CurrentJavaChunk, JavaChunk, LosslessJavaChunk, Region};
 
 const CHUNK_RAW: &[u8] = include_bytes!("../resources/chunk.nbt");
 
@@ -12,5 +12,32 @@ pub fn fastnbt_benchmark(c: &mut Criterion) {
     });
 }
 
-criterion_group!(benches, fastnbt_benchmark);
+pub fn post18_benchmark(c: &mut Criterion) {
+    let file = std::fs::File::open("./resources/1.19.4.mca").unwrap();
+    let mut region = Region::from_stream(file).unwrap();
+    let data = &region.read_chunk(0, 0).unwrap().unwrap();
+
+    c.bench_function("chunk_1.19.4", |b| {
+        b.iter(|| {
+            let chunk = JavaChunk::from_bytes(data).unwrap();
+            black_box(chunk);
+        });
+    });
+
+    c.bench_function("current_chunk_1.19.4", |b| {
+        b.iter(|| {
+            let chunk: CurrentJavaChunk = fastnbt::from_bytes(data).unwrap();
+            black_box(chunk);
+        });
+    });
+
+    c.bench_function("lossless_chunk_1.19.4", |b| {
+        b.iter(|| {
+            let chunk: LosslessJavaChunk = fastnbt::from_bytes(data).unwrap();
+            black_box(chunk);
+        });
+    });
+}
+
+criterion_group!(benches, fastnbt_benchmark, post18_benchmark);
 criterion_main!(benches);
@@ -1,6 +1,6 @@
 use criterion::{black_box, criterion_group, criterion_main, Criterion};
 
-use fastanvil::{complete, Region};
+use fastanvil::{complete, CurrentJavaChunk, Region};
 
 pub fn create_complete_chunk_by_current(c: &mut Criterion) {
     c.bench_function("chunk", |b| {
@@ -16,5 +16,21 @@ pub fn create_complete_chunk_by_current(c: &mut Criterion) {
     });
 }
 
-criterion_group!(benches, create_complete_chunk_by_current);
+pub fn count_non_air_blocks(c: &mut Criterion) {
+    c.bench_function("iter_blocks", |b| {
+        let file = std::fs::File::open("./resources/1.19.4.mca").unwrap();
+
+        let mut region = Region::from_stream(file).unwrap();
+        let data = &region.read_chunk(0, 0).unwrap().unwrap();
+        let chunk: CurrentJavaChunk = fastnbt::from_bytes(data).unwrap();
+
+        b.iter(|| black_box(chunk.iter_blocks().count()));
+    });
+}
+
+criterion_group!(
+    benches,
+    create_complete_chunk_by_current,
+    count_non_air_blocks
+);
 criterion_main!(benches);
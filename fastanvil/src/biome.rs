@@ -1,7 +1,10 @@
 //! functionality relating to Minecraft biomes.
 
+use std::fmt;
+use std::str::FromStr;
+
 use num_enum::{IntoPrimitive, TryFromPrimitive};
-use serde::{Deserialize, Deserializer};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
 // Values from https://minecraft.wiki/w/Java_Edition_data_value#Biomes
 #[derive(TryFromPrimitive, IntoPrimitive, Debug, Clone, Copy, Eq, PartialEq)]
@@ -111,85 +114,171 @@ pub enum Biome {
     WoodedBadlands,
     MangroveSwamp,
     DeepDark,
+    CherryGrove,
+    PaleGarden,
     Unknown,
 }
 
+/// Generates conversions between biomes and their modern (1.18+) namespaced
+/// names. Biomes that only existed before 1.18 do not have a name.
+macro_rules! biome_names {
+    ($($name:literal => $biome:ident),* $(,)?) => {
+        impl Biome {
+            fn from_path(path: &str) -> Self {
+                match path {
+                    $($name => Biome::$biome,)*
+                    _ => Biome::Unknown,
+                }
+            }
+
+            /// The name of the biome as stored in 1.18+ chunks, without the
+            /// `minecraft:` namespace. None for [`Biome::Unknown`] and biomes
+            /// that were removed before 1.18.
+            pub fn name(self) -> Option<&'static str> {
+                match self {
+                    $(Biome::$biome => Some($name),)*
+                    _ => None,
+                }
+            }
+        }
+    };
+}
+
+biome_names! {
+    "badlands" => Badlands,
+    "bamboo_jungle" => BambooJungle,
+    "basalt_deltas" => BasaltDeltas,
+    "beach" => Beach,
+    "birch_forest" => BirchForest,
+    "cold_ocean" => ColdOcean,
+    "crimson_forest" => CrimsonForest,
+    "dark_forest" => DarkForest,
+    "deep_cold_ocean" => DeepColdOcean,
+    "deep_frozen_ocean" => DeepFrozenOcean,
+    "deep_lukewarm_ocean" => DeepLukewarmOcean,
+    "deep_ocean" => DeepOcean,
+    "desert" => Desert,
+    "dripstone_caves" => DripstoneCaves,
+    "end_barrens" => EndBarrens,
+    "end_highlands" => EndHighlands,
+    "end_midlands" => EndMidlands,
+    "eroded_badlands" => ErodedBadlands,
+    "flower_forest" => FlowerForest,
+    "forest" => Forest,
+    "frozen_ocean" => FrozenOcean,
+    "frozen_peaks" => FrozenPeaks,
+    "frozen_river" => FrozenRiver,
+    "grove" => Grove,
+    "ice_spikes" => IceSpikes,
+    "jagged_peaks" => JaggedPeaks,
+    "jungle" => Jungle,
+    "lukewarm_ocean" => LukewarmOcean,
+    "lush_caves" => LushCaves,
+    "meadow" => Meadow,
+    "mushroom_fields" => MushroomFields,
+    "nether_wastes" => NetherWastes,
+    "ocean" => Ocean,
+    "old_growth_birch_forest" => OldGrowthBirchForest,
+    "old_growth_pine_taiga" => OldGrowthPineTaiga,
+    "old_growth_spruce_taiga" => OldGrowthSpruceTaiga,
+    "plains" => Plains,
+    "river" => River,
+    "savanna" => Savanna,
+    "savanna_plateau" => SavannaPlateau,
+    "small_end_islands" => SmallEndIslands,
+    "snowy_beach" => SnowyBeach,
+    "snowy_plains" => SnowyPlains,
+    "snowy_slopes" => SnowySlopes,
+    "snowy_taiga" => SnowyTaiga,
+    "soul_sand_valley" => SoulSandValley,
+    "sparse_jungle" => SparseJungle,
+    "stony_peaks" => StonyPeaks,
+    "stony_shore" => StonyShore,
+    "sunflower_plains" => SunflowerPlains,
+    "swamp" => Swamp,
+    "taiga" => Taiga,
+    "the_end" => TheEnd,
+    "the_void" => TheVoid,
+    "warm_ocean" => WarmOcean,
+    "warped_forest" => WarpedForest,
+    "windswept_forest" => WindsweptForest,
+    "windswept_gravelly_hills" => WindsweptGravellyHills,
+    "windswept_hills" => WindsweptHills,
+    "windswept_savanna" => WindsweptSavanna,
+    "wooded_badlands" => WoodedBadlands,
+    "mangrove_swamp" => MangroveSwamp,
+    "deep_dark" => DeepDark,
+    "cherry_grove" => CherryGrove,
+    "pale_garden" => PaleGarden,
+}
+
 impl<'de> Deserialize<'de> for Biome {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
         D: Deserializer<'de>,
     {
         let s: &str = Deserialize::deserialize(deserializer)?;
-        use Biome::*;
+        Ok(Biome::from_name(s))
+    }
+}
 
-        match s.strip_prefix("minecraft:") {
-            Some(name) => Ok(match name {
-                "badlands" => Badlands,
-                "bamboo_jungle" => BambooJungle,
-                "basalt_deltas" => BasaltDeltas,
-                "beach" => Beach,
-                "birch_forest" => BirchForest,
-                "cold_ocean" => ColdOcean,
-                "crimson_forest" => CrimsonForest,
-                "dark_forest" => DarkForest,
-                "deep_cold_ocean" => DeepColdOcean,
-                "deep_frozen_ocean" => DeepFrozenOcean,
-                "deep_lukewarm_ocean" => DeepLukewarmOcean,
-                "deep_ocean" => DeepOcean,
-                "desert" => Desert,
-                "dripstone_caves" => DripstoneCaves,
-                "end_barrens" => EndBarrens,
-                "end_highlands" => EndHighlands,
-                "end_midlands" => EndMidlands,
-                "eroded_badlands" => ErodedBadlands,
-                "flower_forest" => FlowerForest,
-                "forest" => Forest,
-                "frozen_ocean" => FrozenOcean,
-                "frozen_peaks" => FrozenPeaks,
-                "frozen_river" => FrozenRiver,
-                "grove" => Grove,
-                "ice_spikes" => IceSpikes,
-                "jagged_peaks" => JaggedPeaks,
-                "jungle" => Jungle,
-                "lukewarm_ocean" => LukewarmOcean,
-                "lush_caves" => LushCaves,
-                "meadow" => Meadow,
-                "mushroom_fields" => MushroomFields,
-                "nether_wastes" => NetherWastes,
-                "ocean" => Ocean,
-                "old_growth_birch_forest" => OldGrowthBirchForest,
-                "old_growth_pine_taiga" => OldGrowthPineTaiga,
-                "old_growth_spruce_taiga" => OldGrowthSpruceTaiga,
-                "plains" => Plains,
-                "river" => River,
-                "savanna" => Savanna,
-                "savanna_plateau" => SavannaPlateau,
-                "small_end_islands" => SmallEndIslands,
-                "snowy_beach" => SnowyBeach,
-                "snowy_plains" => SnowyPlains,
-                "snowy_slopes" => SnowySlopes,
-                "snowy_taiga" => SnowyTaiga,
-                "soul_sand_valley" => SoulSandValley,
-                "sparse_jungle" => SparseJungle,
-                "stony_peaks" => StonyPeaks,
-                "stony_shore" => StonyShore,
-                "sunflower_plains" => SunflowerPlains,
-                "swamp" => Swamp,
-                "taiga" => Taiga,
-                "the_end" => TheEnd,
-                "the_void" => TheVoid,
-                "warm_ocean" => WarmOcean,
-                "warped_forest" => WarpedForest,
-                "windswept_forest" => WindsweptForest,
-                "windswept_gravelly_hills" => WindsweptGravellyHills,
-                "windswept_hills" => WindsweptHills,
-                "windswept_savanna" => WindsweptSavanna,
-                "wooded_badlands" => WoodedBadlands,
-                "mangrove_swamp" => MangroveSwamp,
-                "deep_dark" => DeepDark,
-                _ => Unknown,
-            }),
-            None => Ok(Unknown),
+impl Biome {
+    /// Get the biome from its namespaced id as stored in 1.18+ chunks, eg
+    /// `minecraft:plains`. Unrecognised ids give [`Biome::Unknown`].
+    pub fn from_name(name: &str) -> Self {
+        match name.strip_prefix("minecraft:") {
+            Some(path) => Biome::from_path(path),
+            None => Biome::Unknown,
+        }
+    }
+}
+
+/// Error from parsing a [`Biome`] from an unrecognised id.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseBiomeError(String);
+
+impl fmt::Display for ParseBiomeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unknown biome id: {}", self.0)
+    }
+}
+
+impl std::error::Error for ParseBiomeError {}
+
+/// Parses a namespaced id like [`Biome::from_name`], but fails rather than
+/// giving [`Biome::Unknown`].
+impl FromStr for Biome {
+    type Err = ParseBiomeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match Biome::from_name(s) {
+            Biome::Unknown => Err(ParseBiomeError(s.to_owned())),
+            biome => Ok(biome),
+        }
+    }
+}
+
+/// Displays the namespaced id, eg `minecraft:plains`. Biomes without a
+/// [name][`Biome::name`] display their variant name instead.
+impl fmt::Display for Biome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.name() {
+            Some(name) => write!(f, "minecraft:{name}"),
+            None => write!(f, "{self:?}"),
+        }
+    }
+}
+
+impl Serialize for Biome {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self.name() {
+            Some(_) => serializer.collect_str(self),
+            None => Err(serde::ser::Error::custom(format!(
+                "biome {self:?} has no known name to serialize"
+            ))),
         }
     }
 }
@@ -201,6 +290,9 @@ pub struct Climate {
 
 impl Biome {
     // Values from https://github.com/erich666/Mineways/blob/master/Win/biomes.cpp
+    // with biomes that still exist updated to match current vanilla.
+    /// The temperature and rainfall (downfall) of the biome, used to sample
+    /// the grass and foliage colourmaps.
     pub fn climate(self) -> Climate {
         let climate = |t, r| Climate {
             temperature: t,
@@ -245,8 +337,8 @@ impl Biome {
             GiantTreeTaiga => climate(0.3, 0.8),
             GiantTreeTaigaHills => climate(0.3, 0.8),
             WoodedMountains => climate(0.2, 0.3),
-            Savanna => climate(1.2, 0.0),
-            SavannaPlateau => climate(1.0, 0.0),
+            Savanna => climate(2.0, 0.0),
+            SavannaPlateau => climate(2.0, 0.0),
             Badlands => climate(2.0, 0.0),
             WoodedBadlandsPlateau => climate(2.0, 0.0),
             BadlandsPlateau => climate(2.0, 0.0),
@@ -254,13 +346,13 @@ impl Biome {
             EndMidlands => climate(0.5, 0.5),
             EndHighlands => climate(0.5, 0.5),
             EndBarrens => climate(0.5, 0.5),
-            WarmOcean => climate(0.8, 0.5),
-            LukewarmOcean => climate(0.8, 0.5),
-            ColdOcean => climate(0.8, 0.5),
-            DeepWarmOcean => climate(0.8, 0.5),
-            DeepLukewarmOcean => climate(0.8, 0.5),
-            DeepColdOcean => climate(0.8, 0.5),
-            DeepFrozenOcean => climate(0.8, 0.5),
+            WarmOcean => climate(0.5, 0.5),
+            LukewarmOcean => climate(0.5, 0.5),
+            ColdOcean => climate(0.5, 0.5),
+            DeepWarmOcean => climate(0.5, 0.5),
+            DeepLukewarmOcean => climate(0.5, 0.5),
+            DeepColdOcean => climate(0.5, 0.5),
+            DeepFrozenOcean => climate(0.5, 0.5),
             TheVoid => climate(0.5, 0.5),
             SunflowerPlains => climate(0.8, 0.4),
             DesertLakes => climate(2.0, 0.0),
@@ -307,11 +399,23 @@ impl Biome {
             WindsweptForest => climate(0.2, 0.3),
             WindsweptGravellyHills => climate(0.2, 0.3),
             WindsweptHills => climate(0.2, 0.3),
-            WindsweptSavanna => climate(1.1, 0.0),
+            WindsweptSavanna => climate(2.0, 0.0),
             WoodedBadlands => climate(2.0, 0.0),
             MangroveSwamp => climate(0.8, 0.9),
-            DeepDark => climate(0.8, 0.5),
+            DeepDark => climate(0.8, 0.4),
+            CherryGrove => climate(0.5, 0.8),
+            PaleGarden => climate(0.7, 0.8),
             Unknown => climate(0.0, 0.0),
         }
     }
+
+    /// The temperature of the biome. See [`climate`][`Biome::climate`].
+    pub fn temperature(self) -> f32 {
+        self.climate().temperature as f32
+    }
+
+    /// The downfall (rainfall) of the biome. See [`climate`][`Biome::climate`].
+    pub fn downfall(self) -> f32 {
+        self.climate().rainfall as f32
+    }
 }
@@ -5,6 +5,7 @@ use fastnbt::LongArray;
 use serde::Deserialize;
 
 // Various data versions for the anvil format
+const SNAPSHOT_20W17A: i32 = 2529;
 const V1_17_0: i32 = 2724;
 const V1_17_1: i32 = 2730;
 const SNAPSHOT_21W44A: i32 = 2845;
@@ -13,10 +14,38 @@ const SNAPSHOT_21W44A: i32 = 2845;
 /// allocating memory for them when they might not be needed. This object by
 /// default just retains a reference to the data in the input, and `unpack_into`
 /// can be used to get the unpacked version when needed.
+///
+/// ```
+/// # use fastanvil::PackedBits;
+/// # use fastnbt::LongArray;
+/// // Two 5-bit values, 1 and 2, in the padded 1.16+ layout.
+/// let bits = PackedBits(LongArray::new(vec![1 | 2 << 5]));
+/// let mut buf = [0u16; 2];
+/// bits.unpack_into(5, true, &mut buf);
+/// assert_eq!(buf, [1, 2]);
+/// ```
 #[derive(Deserialize, Debug)]
 pub struct PackedBits(pub LongArray);
 
 impl PackedBits {
+    /// Unpack values of `bits_per_item` bits into `buf`, filling it from the
+    /// start of the data.
+    ///
+    /// If `padded` is true the data is in the 1.16+ layout, where values never
+    /// straddle two longs and any unused high bits of each long are padding.
+    /// Otherwise the data is in the tightly packed layout used before 1.16,
+    /// where a value can continue into the next long.
+    ///
+    /// Unpacking stops early if the data runs out, leaving the rest of `buf`
+    /// untouched.
+    pub fn unpack_into(&self, bits_per_item: usize, padded: bool, buf: &mut [u16]) {
+        if padded {
+            self.unpack_1_16(bits_per_item, buf)
+        } else {
+            self.unpack_1_15(bits_per_item, buf)
+        }
+    }
+
     pub fn unpack_blockstates(&self, bits_per_item: usize, buf: &mut [u16]) {
         let bpi = match self.0.len() {
             256 => 4,
@@ -34,18 +63,16 @@ impl PackedBits {
     fn unpack_1_16(&self, bits_per_item: usize, buf: &mut [u16]) {
         let data = &*self.0;
 
-        let mut buf_i = 0;
         let values_per_64bits = 64 / bits_per_item;
+        let mut out = buf.iter_mut();
 
         for datum in data {
             let datum = *datum as u64;
             for i in 0..values_per_64bits {
-                let v = datum.get_bits(i * bits_per_item..(i + 1) * bits_per_item);
-                buf[buf_i] = v as u16;
-                buf_i += 1;
-                if buf_i >= buf.len() {
-                    break;
-                }
+                let Some(val) = out.next() else {
+                    return;
+                };
+                *val = datum.get_bits(i * bits_per_item..(i + 1) * bits_per_item) as u16;
             }
         }
     }
@@ -60,6 +87,9 @@ impl PackedBits {
         for (i, val) in buf.iter_mut().enumerate() {
             let begin = i * bits_per_item;
             let end = begin + bits_per_item;
+            if end > v.len() * 64 {
+                break;
+            }
             *val = v.get_bits(begin..end) as u16;
         }
     }
@@ -144,6 +174,56 @@ pub fn expand_heightmap(data: &[i64], y_min: isize, data_version: i32) -> Vec<i1
     }
 }
 
+/// Get a single height from heightmap data, without expanding the rest. This
+/// agrees with [`expand_heightmap`], but returns `None` for data it does not
+/// understand rather than panicking.
+pub(crate) fn heightmap_at(
+    data: &[i64],
+    index: usize,
+    y_min: isize,
+    data_version: i32,
+) -> Option<i16> {
+    if index >= 256 {
+        return None;
+    }
+
+    match data_version {
+        V1_17_0 | V1_17_1 | SNAPSHOT_21W44A.. => {
+            let bits = match data.len() {
+                43 => 10,
+                37 => 9,
+                _ => return None,
+            };
+            let h = packed_1_16_at(data, index, bits)?;
+
+            // Reinterpret as signed and offset by min y.
+            Some((h as isize + y_min) as i16)
+        }
+        _ => match data.len() {
+            37 => packed_1_16_at(data, index, 9).map(|h| h as i16),
+            36 => {
+                // Values can straddle two longs, so read up to two.
+                let (begin, bits) = (index * 9, 9);
+                let (long, offset) = (begin / 64, begin % 64);
+                let mut v = (*data.get(long)? as u64) >> offset;
+                if offset + bits > 64 {
+                    v |= (*data.get(long + 1)? as u64) << (64 - offset);
+                }
+                Some(v.get_bits(0..bits) as i16)
+            }
+            _ => None,
+        },
+    }
+}
+
+/// Get a single value from bit-packed data in the 1.16 format.
+fn packed_1_16_at(data: &[i64], index: usize, bits: usize) -> Option<u16> {
+    let values_per_64bits = 64 / bits;
+    let datum = *data.get(index / values_per_64bits)? as u64;
+    let i = index % values_per_64bits;
+    Some(datum.get_bits(i * bits..(i + 1) * bits) as u16)
+}
+
 /// Expand generic bit-packed data in the 1.16 format, ie with padding bits.
 pub fn expand_generic_1_16(data: &[i64], bits: usize) -> Vec<u16> {
     let values_per_64bits = 64 / bits;
@@ -177,6 +257,13 @@ pub fn expand_generic_1_15(data: &[i64], bits: usize) -> Vec<u16> {
     result
 }
 
+/// Whether blockstates from a chunk of the given data version use the padded
+/// layout, where values never straddle two longs. This changed in 20w17a, a
+/// snapshot for 1.16.
+pub(crate) fn padded_blockstates(data_version: i32) -> bool {
+    data_version >= SNAPSHOT_20W17A
+}
+
 /// Get the number of bits that will be used in `Blockstates` per block.
 ///
 /// See `anvil::expand_blockstates` for more information.
@@ -364,4 +451,48 @@ mod tests {
         packed.unpack_blockstates(9, buf.as_mut_slice());
         assert_eq!(&expected[..], &buf[..]);
     }
+
+    #[test]
+    fn unpack_into_padded() {
+        // 5 bits per item gives 12 values per long, with 4 bits of padding.
+        let mut first = 0u64;
+        for i in 0..12 {
+            first |= (i as u64) << (i * 5);
+        }
+        first |= 0b1111 << 60; // padding, should be ignored.
+        let packed = PackedBits(LongArray::new(vec![first as i64, 31]));
+
+        let mut buf = [0u16; 13];
+        packed.unpack_into(5, true, &mut buf);
+        assert_eq!(buf, [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 31]);
+    }
+
+    #[test]
+    fn unpack_into_tightly_packed() {
+        // 5 bits per item, the 13th value straddles the first and second long.
+        let mut data = [0u64; 2];
+        for i in 0..14 {
+            data.set_bits(i * 5..(i + 1) * 5, i as u64 + 1);
+        }
+        let packed = PackedBits(LongArray::new(data.iter().map(|d| *d as i64).collect()));
+
+        let mut buf = [0u16; 14];
+        packed.unpack_into(5, false, &mut buf);
+        assert_eq!(buf, [1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14]);
+    }
+
+    #[test]
+    fn unpack_into_stops_when_data_runs_out() {
+        let packed = PackedBits(LongArray::new(vec![-1]));
+
+        let mut buf = [7u16; 20];
+        packed.unpack_into(4, true, &mut buf);
+        assert_eq!(buf[..16], [15; 16]);
+        assert_eq!(buf[16..], [7; 4]);
+
+        let mut buf = [7u16; 20];
+        packed.unpack_into(5, false, &mut buf);
+        assert_eq!(buf[..12], [31; 12]);
+        assert_eq!(buf[12..], [7; 8]);
+    }
 }
@@ -94,7 +94,12 @@ impl From<pre18::JavaChunk> for Chunk {
 
         Chunk {
             status: java_chunk.status(),
-            sections: (java_chunk.level.sections.unwrap(), biomes).into(),
+            sections: (
+                java_chunk.level.sections.unwrap(),
+                biomes,
+                java_chunk.data_version,
+            )
+                .into(),
             heightmap,
         }
     }
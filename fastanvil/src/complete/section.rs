@@ -59,20 +59,33 @@ impl Section {
 
 impl From<java::Section> for Section {
     fn from(current_section: java::Section) -> Self {
-        let blocks = current_section
-            .block_states
-            .try_iter_indices()
-            .map(|block_iter| block_iter.map(|index| index as u16).collect());
+        // With a single entry palette every block is palette[0], so there is
+        // nothing to unpack even if data is present.
+        let blocks = match current_section.block_states.palette().len() {
+            1 => None,
+            _ => current_section
+                .block_states
+                .try_iter_indices()
+                .map(|block_iter| block_iter.map(|index| index as u16).collect()),
+        };
 
-        let biomes = current_section
-            .biomes
-            .try_iter_indices()
-            .map(|biome_iter| biome_iter.map(|index| index as u8).collect());
+        let biomes = match current_section.biomes.palette().len() {
+            1 => None,
+            _ => current_section
+                .biomes
+                .try_iter_indices()
+                .map(|biome_iter| biome_iter.map(|index| index as u8).collect()),
+        };
 
         let mut sec = Section {
             block_palette: Vec::from(current_section.block_states.palette()),
             blocks,
-            biome_palette: Vec::from(current_section.biomes.palette()),
+            biome_palette: current_section
+                .biomes
+                .palette()
+                .iter()
+                .map(|id| Biome::from_name(id))
+                .collect(),
             biomes,
         };
 
@@ -90,8 +103,10 @@ impl From<java::Section> for Section {
     }
 }
 
-impl From<(Pre18Section, &[Biome])> for Section {
-    fn from((current_section, current_biomes): (Pre18Section, &[Biome])) -> Self {
+impl From<(Pre18Section, &[Biome], i32)> for Section {
+    fn from(
+        (current_section, current_biomes, data_version): (Pre18Section, &[Biome], i32),
+    ) -> Self {
         let blocks;
         let block_pallet;
 
@@ -105,7 +120,7 @@ impl From<(Pre18Section, &[Biome])> for Section {
 
                 blocks = Some(
                     block_states
-                        .iter_indices(block_pallet.len())
+                        .iter_indices(block_pallet.len(), data_version)
                         .map(|index| index as u16)
                         .collect(),
                 );
@@ -68,9 +68,13 @@ impl From<java::SectionTower<java::Section>> for SectionTower {
     }
 }
 
-impl From<(java::SectionTower<Pre18Section>, Vec<Biome>)> for SectionTower {
+impl From<(java::SectionTower<Pre18Section>, Vec<Biome>, i32)> for SectionTower {
     fn from(
-        (current_tower, current_biomes): (java::SectionTower<Pre18Section>, Vec<Biome>),
+        (current_tower, current_biomes, data_version): (
+            java::SectionTower<Pre18Section>,
+            Vec<Biome>,
+            i32,
+        ),
     ) -> Self {
         let mut tower = SectionTower {
             sections: vec![],
@@ -92,6 +96,7 @@ impl From<(java::SectionTower<Pre18Section>, Vec<Biome>)> for SectionTower {
                 (
                     section,
                     &current_biomes[((index - 1) * BIOME_COUNT)..(index * BIOME_COUNT)],
+                    data_version,
                 )
                     .into(),
             );
@@ -1,8 +1,10 @@
+use std::collections::{hash_map::Entry, HashMap};
 use std::io::{Read, Seek, Write};
+use std::marker::PhantomData;
 use std::{error::Error, fmt::Display, ops::Range};
 
-use crate::Region;
-use crate::{biome::Biome, Block};
+use crate::{biome::Biome, Block, HeightmapKind};
+use crate::{JavaChunk, Region};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub struct RCoord(pub isize);
@@ -16,12 +18,80 @@ pub enum HeightMode {
     Calculate, // calculate height maps manually, much slower.
 }
 
+/// How far through world generation a chunk is. Chunks on the edge of the
+/// explored world are often only partially generated.
+///
+/// Statuses are parsed with or without the `minecraft:` namespace, and
+/// statuses this crate does not know are kept as [`ChunkStatus::Other`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum ChunkStatus {
+    Empty,
+    StructureStarts,
+    StructureReferences,
+    Biomes,
+    Noise,
+    Surface,
+    Carvers,
+    LiquidCarvers,
+    Features,
+    InitializeLight,
+    Light,
+    Spawn,
+    Heightmaps,
+    /// The chunk is fully generated. The 1.13 statuses `postprocessed` and
+    /// `fullchunk` are also full.
+    Full,
+    Other(String),
+}
+
+impl From<&str> for ChunkStatus {
+    fn from(status: &str) -> Self {
+        use ChunkStatus::*;
+        match status.strip_prefix("minecraft:").unwrap_or(status) {
+            "empty" => Empty,
+            "structure_starts" => StructureStarts,
+            "structure_references" => StructureReferences,
+            "biomes" => Biomes,
+            "noise" => Noise,
+            "surface" => Surface,
+            "carvers" => Carvers,
+            "liquid_carvers" => LiquidCarvers,
+            "features" => Features,
+            "initialize_light" => InitializeLight,
+            "light" => Light,
+            "spawn" => Spawn,
+            "heightmaps" => Heightmaps,
+            "full" | "postprocessed" | "fullchunk" => Full,
+            _ => Other(status.to_owned()),
+        }
+    }
+}
+
+/// A chunk of a world, independent of the version that saved it.
+///
+/// This trait is object safe, so chunks of different versions can be kept
+/// together as `Box<dyn Chunk>`. See
+/// [`JavaChunk::into_boxed`][`crate::JavaChunk::into_boxed`].
 pub trait Chunk: Send + Sync {
     // Status of the chunk. Typically anything except 'full' means the chunk
     // hasn't been fully generated yet. We use this to skip chunks on map edges
     // that haven't been fully generated yet.
     fn status(&self) -> String;
 
+    /// The status of the chunk, parsed from [`status`][`Chunk::status`].
+    /// Prefer this to comparing the status string, which has changed format
+    /// between versions.
+    fn chunk_status(&self) -> ChunkStatus {
+        ChunkStatus::from(self.status().as_str())
+    }
+
+    /// The data version of the game that saved the chunk, identifying the
+    /// exact release or snapshot. None if the chunk does not have one, which
+    /// is the case for chunks saved before 1.9.
+    fn data_version(&self) -> Option<i32> {
+        None
+    }
+
     /// Get the height of the first air-like block above something not air-like.
     /// Will panic if given x/z coordinates outside of 0..16.
     fn surface_height(&self, x: usize, z: usize, mode: HeightMode) -> isize;
@@ -31,11 +101,65 @@ pub trait Chunk: Send + Sync {
     /// trying to access the block at height 1234 would return None.
     fn biome(&self, x: usize, y: isize, z: usize) -> Option<Biome>;
 
+    /// Get the biomes of the section with the given section y, at the 4x4x4
+    /// resolution biomes are stored at since 1.18. Section y is the block y
+    /// divided by 16, as stored in the chunk's sections.
+    ///
+    /// Biomes increase in x, then z, then y, so the biome of the 4x4x4 cell at
+    /// `(x, y, z)` is at index `y * 16 + z * 4 + x`. Returns None if the
+    /// section is not present.
+    fn biomes_in_section(&self, sec_y: isize) -> Option<Vec<Biome>> {
+        let mut biomes = Vec::with_capacity(4 * 4 * 4);
+        for y in 0..4 {
+            for z in 0..4 {
+                for x in 0..4 {
+                    biomes.push(self.biome(x * 4, sec_y * 16 + y * 4, z * 4)?);
+                }
+            }
+        }
+        Some(biomes)
+    }
+
     /// Get the block at the given coordinates. A block may not exist if the
     /// section of the chunk accessed is not present. For example,
     /// trying to access the block at height 1234 would return None.
+    ///
+    /// None means there is no data for the block, which is different to the
+    /// block being air. A section that is present but has no block data is
+    /// all air, and returns [`Block::air`].
     fn block(&self, x: usize, y: isize, z: usize) -> Option<&Block>;
 
+    /// Get the block at the given coordinates, treating blocks without data
+    /// as air. Useful when rendering, where a missing section looks the same
+    /// as an empty one. Use [`block`][`Chunk::block`] to tell them apart.
+    fn block_or_air(&self, x: usize, y: isize, z: usize) -> &Block {
+        self.block(x, y, z).unwrap_or(Block::air())
+    }
+
+    /// Get the block light level of the given coordinate, from 0 to 15. None
+    /// if the section is not present or the light has not been calculated.
+    fn block_light(&self, _x: usize, _y: isize, _z: usize) -> Option<u8> {
+        None
+    }
+
+    /// Get the sky light level of the given coordinate, from 0 to 15. None if
+    /// the section is not present, the light has not been calculated, or the
+    /// dimension has no sky.
+    fn sky_light(&self, _x: usize, _y: isize, _z: usize) -> Option<u8> {
+        None
+    }
+
+    /// Get the height at the given column from the chunk's stored heightmap of
+    /// the given kind. This is the y of the first block above the highest
+    /// block matching the heightmap's condition. None if the chunk does not
+    /// store that heightmap.
+    ///
+    /// Unlike [`surface_height`][`Chunk::surface_height`] this is never
+    /// calculated from the blocks, and is not cached.
+    fn heightmap(&self, _x: usize, _z: usize, _kind: HeightmapKind) -> Option<isize> {
+        None
+    }
+
     /// Get the range of Y values that are valid for this chunk.
     fn y_range(&self) -> Range<isize>;
 }
@@ -69,3 +193,166 @@ where
     /// provide this so that callers can efficiently find regions to process.
     fn list(&self) -> LoaderResult<Vec<(RCoord, RCoord)>>;
 }
+
+/// Inclusive bounds of the regions in a dimension, in region coordinates.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RegionBounds {
+    pub x_min: RCoord,
+    pub x_max: RCoord,
+    pub z_min: RCoord,
+    pub z_max: RCoord,
+}
+
+/// A dimension of a world, such as the overworld or the nether, made up of
+/// the regions provided by a [`RegionLoader`]. This takes care of finding the
+/// region a chunk is in, and keeps regions open once they have been loaded.
+///
+/// ```no_run
+/// # use fastanvil::{CCoord, Chunk, Dimension, LoaderResult, RegionFileLoader};
+/// # fn main() -> LoaderResult<()> {
+/// let loader = RegionFileLoader::new("world/region".into());
+/// let mut dim = Dimension::new(loader);
+///
+/// if let Some(chunk) = dim.chunk(CCoord(-1), CCoord(40))? {
+///     println!("{}", chunk.status());
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub struct Dimension<S, L>
+where
+    S: Seek + Read + Write,
+    L: RegionLoader<S>,
+{
+    loader: L,
+    // Regions that have been asked for, None if the region does not exist.
+    regions: HashMap<(RCoord, RCoord), Option<Region<S>>>,
+    _s: PhantomData<S>,
+}
+
+impl<S, L> Dimension<S, L>
+where
+    S: Seek + Read + Write,
+    L: RegionLoader<S>,
+{
+    pub fn new(loader: L) -> Self {
+        Self {
+            loader,
+            regions: HashMap::new(),
+            _s: PhantomData,
+        }
+    }
+
+    /// Get the region at the given region coordinates, loading it if it has
+    /// not been already. Returns Ok(None) if the region does not exist.
+    pub fn region(&mut self, x: RCoord, z: RCoord) -> LoaderResult<Option<&mut Region<S>>> {
+        let region = match self.regions.entry((x, z)) {
+            Entry::Occupied(e) => e.into_mut(),
+            Entry::Vacant(e) => e.insert(self.loader.region(x, z)?),
+        };
+        Ok(region.as_mut())
+    }
+
+    /// Get the chunk at the given chunk coordinates, which are the block
+    /// coordinates divided by 16. The region the chunk is in is loaded if
+    /// needed. Returns Ok(None) if the region or chunk does not exist.
+    pub fn chunk(&mut self, x: CCoord, z: CCoord) -> LoaderResult<Option<JavaChunk>> {
+        let (rx, rz) = (RCoord(x.0.div_euclid(32)), RCoord(z.0.div_euclid(32)));
+        let (cx, cz) = (x.0.rem_euclid(32) as usize, z.0.rem_euclid(32) as usize);
+
+        let Some(region) = self.region(rx, rz)? else {
+            return Ok(None);
+        };
+
+        let Some(data) = region
+            .read_chunk(cx, cz)
+            .map_err(|e| LoaderError(e.to_string()))?
+        else {
+            return Ok(None);
+        };
+
+        JavaChunk::from_bytes(&data)
+            .map(Some)
+            .map_err(|e| LoaderError(e.to_string()))
+    }
+
+    /// Get the bounds of all regions in this dimension, according to the
+    /// loader's [`list`][`RegionLoader::list`]. Returns Ok(None) if there are
+    /// no regions.
+    pub fn bounds(&self) -> LoaderResult<Option<RegionBounds>> {
+        let regions = self.loader.list()?;
+        let mut iter = regions.into_iter();
+
+        let Some((x, z)) = iter.next() else {
+            return Ok(None);
+        };
+
+        let bounds = iter.fold(
+            RegionBounds {
+                x_min: x,
+                x_max: x,
+                z_min: z,
+                z_max: z,
+            },
+            |b, (x, z)| RegionBounds {
+                x_min: b.x_min.min(x),
+                x_max: b.x_max.max(x),
+                z_min: b.z_min.min(z),
+                z_max: b.z_max.max(z),
+            },
+        );
+
+        Ok(Some(bounds))
+    }
+
+    /// Call `f` with every region the loader [lists][`RegionLoader::list`],
+    /// processing regions in parallel on rayon's global thread pool. Each
+    /// region is opened fresh from the loader for the call and dropped
+    /// afterwards, regions already loaded by this dimension are not used.
+    ///
+    /// The callback is called from several threads at once, so must be
+    /// [`Sync`], and regions are visited in no particular order. Any state
+    /// the callback shares between regions needs its own synchronisation,
+    /// such as a `Mutex` or atomics.
+    ///
+    /// Returns the first error from the loader. Regions already being
+    /// processed when an error happens are still completed.
+    ///
+    /// ```no_run
+    /// # use fastanvil::{Dimension, LoaderResult, RegionFileLoader};
+    /// # use std::sync::atomic::{AtomicUsize, Ordering};
+    /// # fn main() -> LoaderResult<()> {
+    /// let dim = Dimension::new(RegionFileLoader::new("world/region".into()));
+    /// let chunks = AtomicUsize::new(0);
+    ///
+    /// dim.par_for_each_region(|_coord, region| {
+    ///     let count = region.iter().count();
+    ///     chunks.fetch_add(count, Ordering::Relaxed);
+    /// })?;
+    ///
+    /// println!("{} chunks", chunks.into_inner());
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "rayon")]
+    pub fn par_for_each_region<F>(&self, f: F) -> LoaderResult<()>
+    where
+        L: Sync,
+        F: Fn((RCoord, RCoord), &mut Region<S>) + Sync,
+    {
+        use rayon::prelude::*;
+
+        let loader = &self.loader;
+        loader.list()?.into_par_iter().try_for_each(|(x, z)| {
+            if let Some(mut region) = loader.region(x, z)? {
+                f((x, z), &mut region);
+            }
+            Ok(())
+        })
+    }
+
+    /// Consumes the dimension, returning the loader.
+    pub fn into_loader(self) -> L {
+        self.loader
+    }
+}
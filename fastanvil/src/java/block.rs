@@ -1,15 +1,26 @@
 use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
 
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
+use super::AIR;
+
+/// A block state, such as `minecraft:oak_stairs` facing east.
+///
+/// Blocks can be used as keys in a `HashMap` or `HashSet`. They are compared
+/// and hashed by their [encoded description][`Block::encoded_description`],
+/// so this is as cheap as comparing a single string. Blocks that differ only
+/// in properties left out of the encoded description, such as `waterlogged`,
+/// are equal.
 #[derive(Debug, Clone)]
 pub struct Block {
     pub(crate) name: String,
     pub(crate) encoded: String,
     pub(crate) archetype: BlockArchetype,
+    pub(crate) properties: HashMap<String, String>,
 }
 
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone)]
 pub enum BlockArchetype {
     Normal,
     Airy,
@@ -18,6 +29,34 @@ pub enum BlockArchetype {
 }
 
 impl Block {
+    /// Create a block from its encoded description, taking the properties from
+    /// it. See [`encoded_description`][`Block::encoded_description`].
+    pub(crate) fn new(name: String, encoded: String, archetype: BlockArchetype) -> Self {
+        let properties = encoded
+            .split_once('|')
+            .map(|(_, props)| props)
+            .unwrap_or_default()
+            .split(',')
+            .filter_map(|prop| prop.split_once('='))
+            .map(|(k, v)| (k.to_owned(), v.to_owned()))
+            .collect();
+
+        Self {
+            name,
+            encoded,
+            archetype,
+            properties,
+        }
+    }
+
+    /// The `minecraft:air` block. This is what chunks return for blocks in a
+    /// section that has no block data, and what
+    /// [`Chunk::block_or_air`][`crate::Chunk::block_or_air`] returns for
+    /// blocks outside of any section.
+    pub fn air() -> &'static Block {
+        &AIR
+    }
+
     pub fn name(&self) -> &str {
         &self.name
     }
@@ -32,6 +71,33 @@ impl Block {
     pub fn encoded_description(&self) -> &str {
         &self.encoded
     }
+
+    /// The block state properties of this block, eg `facing` or `half`. Unlike
+    /// the [encoded description][`Block::encoded_description`], this includes
+    /// every property.
+    pub fn properties(&self) -> &HashMap<String, String> {
+        &self.properties
+    }
+
+    /// Get the value of a single block state property, eg `half` for a slab
+    /// might be `Some("top")`.
+    pub fn property(&self, key: &str) -> Option<&str> {
+        self.properties.get(key).map(String::as_str)
+    }
+}
+
+impl PartialEq for Block {
+    fn eq(&self, other: &Self) -> bool {
+        self.encoded == other.encoded
+    }
+}
+
+impl Eq for Block {}
+
+impl Hash for Block {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.encoded.hash(state);
+    }
 }
 
 #[derive(Deserialize)]
@@ -83,10 +149,34 @@ impl<'de> Deserialize<'de> for Block {
             name: raw.name,
             archetype: arch,
             encoded: id,
+            properties: raw.properties,
         })
     }
 }
 
+/// Serialization side of [`BlockRaw`], borrowing from the block.
+#[derive(Serialize)]
+#[serde(rename_all = "PascalCase")]
+struct BlockRawRef<'a> {
+    name: &'a str,
+
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    properties: &'a HashMap<String, String>,
+}
+
+impl Serialize for Block {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        BlockRawRef {
+            name: &self.name,
+            properties: &self.properties,
+        }
+        .serialize(serializer)
+    }
+}
+
 /// Blocks that are considered as if they are water when determining colour.
 fn is_watery(block: &str) -> bool {
     matches!(
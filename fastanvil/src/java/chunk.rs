@@ -1,11 +1,15 @@
+use std::collections::HashMap;
 use std::ops::Range;
 use std::sync::RwLock;
 
-use serde::Deserialize;
+use fastnbt::Value;
+use serde::{Deserialize, Serialize};
 
-use crate::{biome::Biome, Block, Chunk, HeightMode};
+use crate::{biome::Biome, Block, Chunk, HeightMode, HeightmapKind};
 use crate::{expand_heightmap, Heightmaps, Section, SectionTower};
 
+use super::section::light_at;
+
 use super::AIR;
 
 impl Chunk for CurrentJavaChunk {
@@ -13,6 +17,10 @@ impl Chunk for CurrentJavaChunk {
         self.status.clone()
     }
 
+    fn data_version(&self) -> Option<i32> {
+        Some(self.data_version)
+    }
+
     fn surface_height(&self, x: usize, z: usize, mode: HeightMode) -> isize {
         let mut heightmap = self.lazy_heightmap.read().unwrap();
         if heightmap.is_none() {
@@ -31,7 +39,7 @@ impl Chunk for CurrentJavaChunk {
         let sec = sections.get_section_for_y(y)?;
         let sec_y = (y - sec.y as isize * 16) as usize;
 
-        sec.biomes.at(x, sec_y, z).cloned()
+        sec.biome(x, sec_y, z)
     }
 
     fn block(&self, x: usize, y: isize, z: usize) -> Option<&Block> {
@@ -42,6 +50,25 @@ impl Chunk for CurrentJavaChunk {
         Some(sec.block_states.at(x, sec_y, z).unwrap_or(&AIR))
     }
 
+    fn block_light(&self, x: usize, y: isize, z: usize) -> Option<u8> {
+        let sec = self.sections.as_ref()?.get_section_for_y(y)?;
+        let sec_y = (y - sec.y as isize * 16) as usize;
+        light_at(sec.block_light.as_ref()?, x, sec_y, z)
+    }
+
+    fn sky_light(&self, x: usize, y: isize, z: usize) -> Option<u8> {
+        let sec = self.sections.as_ref()?.get_section_for_y(y)?;
+        let sec_y = (y - sec.y as isize * 16) as usize;
+        light_at(sec.sky_light.as_ref()?, x, sec_y, z)
+    }
+
+    fn heightmap(&self, x: usize, z: usize, kind: HeightmapKind) -> Option<isize> {
+        let y_min = self.sections.as_ref()?.y_min();
+        self.heightmaps
+            .as_ref()?
+            .height_at(kind, x, z, y_min, self.data_version)
+    }
+
     fn y_range(&self) -> Range<isize> {
         match &self.sections {
             Some(sections) => Range {
@@ -59,25 +86,125 @@ impl Chunk for CurrentJavaChunk {
 /// data necessary to render maps for fastanvil. If you need more complete data
 /// you need to write your own chunk struct and implement the serde traits, or
 /// use [`fastnbt::Value`].
-#[derive(Deserialize, Debug)]
+///
+/// The chunk can be serialized back to NBT, eg after editing blocks with
+/// [`BlockData::set`][`crate::BlockData::set`]. Only the fields modelled here
+/// are written, use [`LosslessJavaChunk`] to keep the rest of the chunk.
+#[derive(Deserialize, Serialize, Debug)]
 pub struct CurrentJavaChunk {
     #[serde(rename = "DataVersion")]
     pub data_version: i32,
 
+    #[serde(rename = "xPos", skip_serializing_if = "Option::is_none")]
+    pub x_pos: Option<i32>,
+
+    #[serde(rename = "yPos", skip_serializing_if = "Option::is_none")]
+    pub y_pos: Option<i32>,
+
+    #[serde(rename = "zPos", skip_serializing_if = "Option::is_none")]
+    pub z_pos: Option<i32>,
+
     // Maybe put section and heightmaps together and serde flatten?
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub sections: Option<SectionTower<Section>>,
 
-    #[serde(rename = "Heightmaps")]
+    #[serde(rename = "Heightmaps", skip_serializing_if = "Option::is_none")]
     pub heightmaps: Option<Heightmaps>,
 
     #[serde(rename = "Status")]
     pub status: String,
 
+    #[serde(default)]
+    pub block_entities: Vec<BlockEntity>,
+
     #[serde(skip)]
     pub(crate) lazy_heightmap: RwLock<Option<[i16; 256]>>,
 }
 
+/// A [`CurrentJavaChunk`] that also keeps every field it does not model, so
+/// that serializing it back loses nothing.
+///
+/// Capturing the other fields means buffering the whole chunk while
+/// deserializing, which is noticeably slower. [`CurrentJavaChunk`] and
+/// [`JavaChunk`][`crate::JavaChunk`] do not do this, so only use this type
+/// when the chunk is going to be written back.
+#[derive(Deserialize, Serialize, Debug)]
+pub struct LosslessJavaChunk {
+    #[serde(flatten)]
+    pub chunk: CurrentJavaChunk,
+
+    /// Every other field of the chunk, eg entities, structures and ticks.
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
+}
+
+/// A block entity, such as a chest, sign or spawner.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct BlockEntity {
+    pub id: String,
+
+    /// World coordinates of the block entity. These are absolute, not relative
+    /// to the chunk.
+    pub x: i32,
+    pub y: i32,
+    pub z: i32,
+
+    /// The remaining data of the block entity, which depends on its `id`. This
+    /// is always a [`Value::Compound`].
+    #[serde(flatten)]
+    pub extra: Value,
+}
+
 impl CurrentJavaChunk {
+    /// The block entities in this chunk. See [`BlockEntity`] for details of
+    /// the coordinates.
+    pub fn block_entities(&self) -> &[BlockEntity] {
+        &self.block_entities
+    }
+
+    /// Iterate over every non-air block in the chunk, as `(x, y, z, block)`.
+    /// x and z are relative to the chunk (0..16) and y is the world height,
+    /// the same as [`Chunk::block`].
+    ///
+    /// Blocks are visited section by section, increasing in x, then z, then y
+    /// within each section. Sections without any non-air blocks in their
+    /// palette are skipped without looking at their block states.
+    pub fn iter_blocks(&self) -> impl Iterator<Item = (usize, isize, usize, &Block)> + '_ {
+        self.sections
+            .iter()
+            .flat_map(|sections| sections.sections())
+            .filter_map(|sec| {
+                let palette = sec.block_states.palette();
+                let air: Vec<bool> = palette.iter().map(|b| is_air(b.name())).collect();
+                if air.iter().all(|a| *a) {
+                    return None;
+                }
+
+                // Single entry palettes normally have no state data, and the
+                // game ignores it if there is any.
+                let (states, single) = match palette.len() {
+                    1 => (None, Some(std::iter::repeat_n(0, 16 * 16 * 16))),
+                    _ => (sec.block_states.try_iter_indices(), None),
+                };
+                let indices = states
+                    .into_iter()
+                    .flatten()
+                    .chain(single.into_iter().flatten());
+
+                let base_y = sec.y as isize * 16;
+                Some(indices.enumerate().filter_map(move |(i, p)| {
+                    if *air.get(p)? {
+                        return None;
+                    }
+                    let x = i & 0xF;
+                    let z = (i >> 4) & 0xF;
+                    let y = base_y + (i >> 8) as isize;
+                    Some((x, y, z, &palette[p]))
+                }))
+            })
+            .flatten()
+    }
+
     pub fn recalculate_heightmap(&self, mode: HeightMode) {
         // TODO: Find top section and start there, pointless checking 320 down
         // if its a 1.16 chunk.
@@ -133,3 +260,10 @@ impl CurrentJavaChunk {
         *self.lazy_heightmap.write().unwrap() = Some(map);
     }
 }
+
+fn is_air(name: &str) -> bool {
+    matches!(
+        name,
+        "minecraft:air" | "minecraft:cave_air" | "minecraft:void_air"
+    )
+}
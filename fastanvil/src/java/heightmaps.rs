@@ -1,12 +1,60 @@
 use fastnbt::LongArray;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
+
+use crate::bits::heightmap_at;
 
 /// Various heightmaps kept up to date by Minecraft.
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Serialize, Debug)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub struct Heightmaps {
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub motion_blocking: Option<LongArray>,
-    //pub motion_blocking_no_leaves: Option<Heightmap>,
-    //pub ocean_floor: Option<Heightmap>,
-    //pub world_surface: Option<Heightmap>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub motion_blocking_no_leaves: Option<LongArray>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ocean_floor: Option<LongArray>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub world_surface: Option<LongArray>,
+}
+
+/// The kinds of heightmap stored in a chunk. Each records the height of the
+/// first block above the highest block matching some condition.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum HeightmapKind {
+    /// Highest block that blocks motion or contains a fluid.
+    MotionBlocking,
+    /// As [`MotionBlocking`][`HeightmapKind::MotionBlocking`], but ignoring
+    /// leaves.
+    MotionBlockingNoLeaves,
+    /// Highest non-air block that is solid, ignoring fluids.
+    OceanFloor,
+    /// Highest non-air block.
+    WorldSurface,
+}
+
+impl Heightmaps {
+    /// Get the raw packed data of the given kind of heightmap, if present.
+    pub fn get(&self, kind: HeightmapKind) -> Option<&LongArray> {
+        match kind {
+            HeightmapKind::MotionBlocking => self.motion_blocking.as_ref(),
+            HeightmapKind::MotionBlockingNoLeaves => self.motion_blocking_no_leaves.as_ref(),
+            HeightmapKind::OceanFloor => self.ocean_floor.as_ref(),
+            HeightmapKind::WorldSurface => self.world_surface.as_ref(),
+        }
+    }
+
+    /// Get the height at x, z from the given kind of heightmap, interpreted
+    /// according to `y_min` and `data_version`. Only the requested height is
+    /// unpacked. See [`expand_heightmap`][`crate::expand_heightmap`].
+    pub(crate) fn height_at(
+        &self,
+        kind: HeightmapKind,
+        x: usize,
+        z: usize,
+        y_min: isize,
+        data_version: i32,
+    ) -> Option<isize> {
+        let data = self.get(kind)?;
+        heightmap_at(data, z * 16 + x, y_min, data_version).map(|h| h as isize)
+    }
 }
@@ -21,18 +21,25 @@ pub use section_data::*;
 pub use section_tower::*;
 
 use once_cell::sync::Lazy;
+use serde::Deserialize;
 
 use crate::{biome::Biome, Chunk, HeightMode};
 
-pub static AIR: Lazy<Block> = Lazy::new(|| Block {
-    name: "minecraft:air".to_owned(),
-    encoded: "minecraft:air|".to_owned(),
-    archetype: BlockArchetype::Airy,
+/// The `minecraft:air` block, also available as [`Block::air`].
+pub static AIR: Lazy<Block> = Lazy::new(|| {
+    Block::new(
+        "minecraft:air".to_owned(),
+        "minecraft:air|".to_owned(),
+        BlockArchetype::Airy,
+    )
 });
-pub static SNOW_BLOCK: Lazy<Block> = Lazy::new(|| Block {
-    name: "minecraft:snow_block".to_owned(),
-    encoded: "minecraft:snow_block|".to_owned(),
-    archetype: BlockArchetype::Snowy,
+/// A `minecraft:snow_block`, used to render snowy blocks.
+pub static SNOW_BLOCK: Lazy<Block> = Lazy::new(|| {
+    Block::new(
+        "minecraft:snow_block".to_owned(),
+        "minecraft:snow_block|".to_owned(),
+        BlockArchetype::Snowy,
+    )
 });
 
 /// A Minecraft chunk.
@@ -43,8 +50,39 @@ pub enum JavaChunk {
     Pre13(pre13::JavaChunk),
 }
 
+/// The data version of snapshot 17w47a, leading to 1.13, which replaced
+/// numeric block ids with block states.
+const FLATTENING_DATA_VERSION: i32 = 1451;
+
+/// The data version of snapshot 21w43a, leading to 1.18, which moved chunk
+/// data out of `Level` to the root of the chunk.
+const ROOT_LEVEL_DATA_VERSION: i32 = 2844;
+
 impl JavaChunk {
+    /// Deserialize a chunk of any supported version. The chunk's
+    /// `DataVersion` is read first to pick which version to deserialize as,
+    /// so errors are from that version. Chunks without a `DataVersion`, or
+    /// that do not match their version, are tried as each version in turn.
     pub fn from_bytes(data: &[u8]) -> Result<Self> {
+        #[derive(Deserialize)]
+        struct Version {
+            #[serde(rename = "DataVersion")]
+            data_version: Option<i32>,
+        }
+
+        let version = from_bytes::<Version>(data)?.data_version;
+
+        let chunk = match version {
+            Some(v) if v >= ROOT_LEVEL_DATA_VERSION => from_bytes(data).map(Self::Post18),
+            Some(v) if v >= FLATTENING_DATA_VERSION => from_bytes(data).map(Self::Pre18),
+            Some(_) => from_bytes(data).map(Self::Pre13),
+            None => return Self::from_bytes_by_trial(data),
+        };
+
+        chunk.or_else(|err| Self::from_bytes_by_trial(data).map_err(|_| err))
+    }
+
+    fn from_bytes_by_trial(data: &[u8]) -> Result<Self> {
         let chunk: Result<CurrentJavaChunk> = from_bytes(data);
 
         match chunk {
@@ -55,6 +93,16 @@ impl JavaChunk {
             },
         }
     }
+
+    /// Box the chunk of whichever version this is, so it can be used without
+    /// matching on the version each time.
+    pub fn into_boxed(self) -> Box<dyn Chunk> {
+        match self {
+            JavaChunk::Post18(c) => Box::new(c),
+            JavaChunk::Pre18(c) => Box::new(c),
+            JavaChunk::Pre13(c) => Box::new(c),
+        }
+    }
 }
 
 // TODO: Find a better way to dispatch these methods.
@@ -67,6 +115,14 @@ impl Chunk for JavaChunk {
         }
     }
 
+    fn data_version(&self) -> Option<i32> {
+        match self {
+            JavaChunk::Post18(c) => c.data_version(),
+            JavaChunk::Pre18(c) => c.data_version(),
+            JavaChunk::Pre13(c) => c.data_version(),
+        }
+    }
+
     fn surface_height(&self, x: usize, z: usize, mode: HeightMode) -> isize {
         match self {
             JavaChunk::Post18(c) => c.surface_height(x, z, mode),
@@ -91,6 +147,30 @@ impl Chunk for JavaChunk {
         }
     }
 
+    fn block_light(&self, x: usize, y: isize, z: usize) -> Option<u8> {
+        match self {
+            JavaChunk::Post18(c) => c.block_light(x, y, z),
+            JavaChunk::Pre18(c) => c.block_light(x, y, z),
+            JavaChunk::Pre13(c) => c.block_light(x, y, z),
+        }
+    }
+
+    fn sky_light(&self, x: usize, y: isize, z: usize) -> Option<u8> {
+        match self {
+            JavaChunk::Post18(c) => c.sky_light(x, y, z),
+            JavaChunk::Pre18(c) => c.sky_light(x, y, z),
+            JavaChunk::Pre13(c) => c.sky_light(x, y, z),
+        }
+    }
+
+    fn heightmap(&self, x: usize, z: usize, kind: HeightmapKind) -> Option<isize> {
+        match self {
+            JavaChunk::Post18(c) => c.heightmap(x, z, kind),
+            JavaChunk::Pre18(c) => c.heightmap(x, z, kind),
+            JavaChunk::Pre13(c) => c.heightmap(x, z, kind),
+        }
+    }
+
     fn y_range(&self) -> Range<isize> {
         match self {
             JavaChunk::Post18(c) => c.y_range(),
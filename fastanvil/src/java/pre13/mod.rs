@@ -3,12 +3,14 @@ use std::fmt;
 use std::ops::Range;
 use std::sync::RwLock;
 
-use once_cell::sync::OnceCell;
+use log::debug;
+use once_cell::sync::{Lazy, OnceCell};
 use serde::Deserialize;
 
 use fastnbt::ByteArray;
 
-use crate::{biome::Biome, Block, Chunk, HeightMode};
+use super::section::light_at;
+use crate::{biome::Biome, Block, BlockArchetype, Chunk, HeightMode, HeightmapKind};
 use crate::{expand_heightmap, Heightmaps, SectionLike, SectionTower};
 
 /// Conversion from numeric block ids to string based block names.
@@ -39,6 +41,31 @@ pub fn init_block(block_id: u16, data_value: u8, block: Block) -> Result<(), Blo
     BLOCK_LIST[block_list_index].set(block)
 }
 
+/// Register every vanilla 1.12 block id and data value, for example
+/// `(17, 2)` as `minecraft:birch_log`. Blocks are otherwise registered the
+/// first time a chunk contains them, so this is only needed to avoid that
+/// cost while reading chunks.
+///
+/// Blocks already registered with [`init_block`] are kept, so call this after
+/// registering any blocks of your own.
+pub fn register_vanilla_blocks() {
+    for (index, block) in BLOCK_LIST.iter().enumerate() {
+        let (block_id, data_value) = ((index >> 4) as u16, (index & 0b1111) as u8);
+        block.get_or_init(|| pre13_block_names::init_default_block(block_id, data_value));
+    }
+}
+
+/// The block returned by [`JavaChunk::block`][`crate::Chunk::block`] for block ids in the
+/// 256..=4095 range that the [custom block callback][`set_custom_block_callback`] does not know,
+/// such as blocks added by mods. Its name is `fastanvil:unknown`.
+pub static UNKNOWN_BLOCK: Lazy<Block> = Lazy::new(|| {
+    Block::new(
+        "fastanvil:unknown".to_owned(),
+        "fastanvil:unknown|".to_owned(),
+        BlockArchetype::Normal,
+    )
+});
+
 /// Function used to convert a block ids in the 256..=4095 range and a data value to static
 /// references to `Block`.
 pub type CustomBlockCallback = Box<dyn Send + Sync + Fn(u16, u8) -> Option<&'static Block>>;
@@ -48,7 +75,8 @@ pub type CustomBlockCallback = Box<dyn Send + Sync + Fn(u16, u8) -> Option<&'sta
 static CUSTOM_BLOCK_CALLBACK: OnceCell<RwLock<CustomBlockCallback>> = OnceCell::new();
 
 /// Set a custom callback to convert block ids in the 256..=4095 range and a data value to static
-/// references to `Block`. The callback can return `None` if the block id does not exist.
+/// references to `Block`. The callback can return `None` if the block id does not exist, in which
+/// case the chunk returns [`UNKNOWN_BLOCK`] for it.
 ///
 /// Returns the previously set callback.
 pub fn set_custom_block_callback(f: CustomBlockCallback) -> CustomBlockCallback {
@@ -84,6 +112,10 @@ impl Chunk for JavaChunk {
         "full".to_string()
     }
 
+    fn data_version(&self) -> Option<i32> {
+        self.data_version
+    }
+
     fn surface_height(&self, x: usize, z: usize, mode: HeightMode) -> isize {
         let mut heightmap = self.level.lazy_heightmap.read().unwrap();
         if heightmap.is_none() {
@@ -115,11 +147,38 @@ impl Chunk for JavaChunk {
         } else {
             Some(
                 custom_block_callback(raw_block.block_id(), raw_block.data_value())
-                    .unwrap_or_else(|| panic!("Unknown raw block index {:?}. Use `set_custom_block_callback` to support this block id", raw_block)),
+                    .unwrap_or_else(|| {
+                        debug!("unknown block {:?}", raw_block);
+                        &UNKNOWN_BLOCK
+                    }),
             )
         }
     }
 
+    fn block_light(&self, x: usize, y: isize, z: usize) -> Option<u8> {
+        let sec = self.level.sections.as_ref()?.get_section_for_y(y)?;
+        let sec_y = (y - sec.y as isize * 16) as usize;
+        light_at(sec.block_light.as_ref()?, x, sec_y, z)
+    }
+
+    fn heightmap(&self, x: usize, z: usize, kind: HeightmapKind) -> Option<isize> {
+        let y_min = self.level.sections.as_ref()?.y_min();
+        // TODO: does data_version matter before 1.9?
+        self.level.heightmaps.as_ref()?.height_at(
+            kind,
+            x,
+            z,
+            y_min,
+            self.data_version.unwrap_or(0),
+        )
+    }
+
+    fn sky_light(&self, x: usize, y: isize, z: usize) -> Option<u8> {
+        let sec = self.level.sections.as_ref()?.get_section_for_y(y)?;
+        let sec_y = (y - sec.y as isize * 16) as usize;
+        light_at(sec.sky_light.as_ref()?, x, sec_y, z)
+    }
+
     fn y_range(&self) -> std::ops::Range<isize> {
         match &self.level.sections {
             Some(sections) => Range {
@@ -228,6 +287,12 @@ pub struct Pre13Section {
 
     // 4 bits per block
     pub data: ByteArray,
+
+    /// Block light, 4 bits per block.
+    pub block_light: Option<ByteArray>,
+
+    /// Sky light, 4 bits per block. Missing for dimensions without a sky.
+    pub sky_light: Option<ByteArray>,
 }
 
 impl Pre13Section {
@@ -235,12 +300,12 @@ impl Pre13Section {
         let idx: usize = (sec_y << 8) + (z << 4) + x;
 
         // Important: byte array can have negative values, we want to convert -1 into 255
-        // so we cast first to u8, and then to usize
-        let mut block_id = self.blocks[idx] as u8 as usize;
+        // so we read it as u8 first, and then cast to usize
+        let mut block_id = self.blocks.as_u8_slice()[idx] as usize;
 
         // Add extra bits from add field if present
         if let Some(add) = &self.add {
-            let mut add_id = add[idx / 2] as u8;
+            let mut add_id = add.as_u8_slice()[idx / 2];
             if idx % 2 == 0 {
                 add_id &= 0x0F;
             } else {
@@ -250,7 +315,7 @@ impl Pre13Section {
         }
 
         let block_data = {
-            let mut add_id = self.data[idx / 2] as u8;
+            let mut add_id = self.data.as_u8_slice()[idx / 2];
             if idx % 2 == 0 {
                 add_id &= 0x0F;
             } else {
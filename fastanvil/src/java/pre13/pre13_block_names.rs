@@ -21,11 +21,11 @@ macro_rules! coloured_block {
             15 => "black",
             _ => unreachable!(),
         };
-        Block {
-            name: format!("minecraft:{col}_{}", $a),
-            encoded: format!("minecraft:{col}_{}|", $a),
-            archetype: BlockArchetype::Normal,
-        }
+        Block::new(
+            format!("minecraft:{col}_{}", $a),
+            format!("minecraft:{col}_{}|", $a),
+            BlockArchetype::Normal,
+        )
     }};
 }
 
@@ -61,11 +61,11 @@ fn modern_block(block_name: &'static str, data_value: u8) -> Block {
                 6 | 7 => "invalid_double_wooden_slab",
                 _ => unreachable!(),
             };
-            Block {
-                name: format!("{kind}_slab"),
-                encoded: format!("{kind}_slab|type=double"),
-                archetype: BlockArchetype::Normal,
-            }
+            Block::new(
+                format!("minecraft:{kind}_slab"),
+                format!("minecraft:{kind}_slab|type=double"),
+                BlockArchetype::Normal,
+            )
         }
         "wooden_slab" => {
             let kind = data_value & 0b0111;
@@ -82,14 +82,14 @@ fn modern_block(block_name: &'static str, data_value: u8) -> Block {
             let top = data_value & 0b1000;
             let top = match top {
                 0 => "bottom",
-                1 => "top",
+                8 => "top",
                 _ => unreachable!(),
             };
-            Block {
-                name: format!("{kind}_slab"),
-                encoded: format!("{kind}_slab|type={top}"),
-                archetype: BlockArchetype::Normal,
-            }
+            Block::new(
+                format!("minecraft:{kind}_slab"),
+                format!("minecraft:{kind}_slab|type={top}"),
+                BlockArchetype::Normal,
+            )
         }
         "flower" => {
             let kind = data_value & 0b1111;
@@ -106,11 +106,7 @@ fn modern_block(block_name: &'static str, data_value: u8) -> Block {
                 9..=15 => "invalid_flower",
                 _ => unreachable!(),
             };
-            Block {
-                name: ns(kind),
-                encoded: enc0(kind),
-                archetype: BlockArchetype::Normal,
-            }
+            Block::new(ns(kind), enc0(kind), BlockArchetype::Normal)
         }
         "red_sandstone" => {
             let kind = data_value & 0b0011;
@@ -121,11 +117,7 @@ fn modern_block(block_name: &'static str, data_value: u8) -> Block {
                 3 => "invalid_red_sandstone",
                 _ => unreachable!(),
             };
-            Block {
-                name: ns(kind),
-                encoded: enc0(kind),
-                archetype: BlockArchetype::Normal,
-            }
+            Block::new(ns(kind), enc0(kind), BlockArchetype::Normal)
         }
         "sandstone" => {
             let kind = data_value & 0b0011;
@@ -136,11 +128,7 @@ fn modern_block(block_name: &'static str, data_value: u8) -> Block {
                 3 => "invalid_sandstone",
                 _ => unreachable!(),
             };
-            Block {
-                name: ns(kind),
-                encoded: enc0(kind),
-                archetype: BlockArchetype::Normal,
-            }
+            Block::new(ns(kind), enc0(kind), BlockArchetype::Normal)
         }
         "double_stone_slab" => {
             let kind = data_value & 0b0111;
@@ -155,11 +143,11 @@ fn modern_block(block_name: &'static str, data_value: u8) -> Block {
                 7 => "quartz",
                 _ => unreachable!(),
             };
-            Block {
-                name: format!("{kind}_slab"),
-                encoded: format!("{kind}_slab|type=double"),
-                archetype: BlockArchetype::Normal,
-            }
+            Block::new(
+                format!("minecraft:{kind}_slab"),
+                format!("minecraft:{kind}_slab|type=double"),
+                BlockArchetype::Normal,
+            )
         }
         "stone_slab" => {
             let kind = data_value & 0b0111;
@@ -177,20 +165,20 @@ fn modern_block(block_name: &'static str, data_value: u8) -> Block {
             let top = data_value & 0b1000;
             let top = match top {
                 0 => "bottom",
-                1 => "top",
+                8 => "top",
                 _ => unreachable!(),
             };
-            Block {
-                name: format!("{kind}_slab"),
-                encoded: format!("{kind}_slab|type={top}"),
-                archetype: BlockArchetype::Normal,
-            }
+            Block::new(
+                format!("minecraft:{kind}_slab"),
+                format!("minecraft:{kind}_slab|type={top}"),
+                BlockArchetype::Normal,
+            )
         }
-        "double_stone_slab2" => Block {
-            name: ns("red_sandstone_slab"),
-            encoded: enc0("red_sandstone_slab|type=double"),
-            archetype: BlockArchetype::Normal,
-        },
+        "double_stone_slab2" => Block::new(
+            ns("red_sandstone_slab"),
+            enc0("red_sandstone_slab|type=double"),
+            BlockArchetype::Normal,
+        ),
         "stone_slab2" => {
             let top = data_value & 0b1000;
             let top = match top {
@@ -198,11 +186,11 @@ fn modern_block(block_name: &'static str, data_value: u8) -> Block {
                 8 => "top",
                 _ => unreachable!(),
             };
-            Block {
-                name: ns("red_sandstone_slab"),
-                encoded: format!("red_sandstone_slab|type={top}"),
-                archetype: BlockArchetype::Normal,
-            }
+            Block::new(
+                ns("red_sandstone_slab"),
+                format!("red_sandstone_slab|type={top}"),
+                BlockArchetype::Normal,
+            )
         }
         "stained_glass" => {
             coloured_block!("stained_glass", data_value)
@@ -220,11 +208,7 @@ fn modern_block(block_name: &'static str, data_value: u8) -> Block {
                 1 => "red_sand",
                 _ => unreachable!(),
             };
-            Block {
-                name: ns(kind),
-                encoded: enc0(kind),
-                archetype: BlockArchetype::Normal,
-            }
+            Block::new(ns(kind), enc0(kind), BlockArchetype::Normal)
         }
         "sapling" => {
             let kind = data_value & 0b0111;
@@ -235,13 +219,10 @@ fn modern_block(block_name: &'static str, data_value: u8) -> Block {
                 3 => "jungle_sapling",
                 4 => "acacia_sapling",
                 5 => "dark_oak_sapling",
+                6 | 7 => "invalid_sapling",
                 _ => unreachable!(),
             };
-            Block {
-                name: ns(kind),
-                encoded: enc0(kind),
-                archetype: BlockArchetype::Normal,
-            }
+            Block::new(ns(kind), enc0(kind), BlockArchetype::Normal)
         }
         "dirt" => {
             let kind = data_value & 0b0011;
@@ -249,13 +230,10 @@ fn modern_block(block_name: &'static str, data_value: u8) -> Block {
                 0 => "dirt",
                 1 => "coarse_dirt",
                 2 => "podzol",
+                3 => "invalid_dirt",
                 _ => unreachable!(),
             };
-            Block {
-                name: ns(kind),
-                encoded: enc0(kind),
-                archetype: BlockArchetype::Normal,
-            }
+            Block::new(ns(kind), enc0(kind), BlockArchetype::Normal)
         }
         "stone" => {
             let kind = data_value & 0b0111;
@@ -267,13 +245,10 @@ fn modern_block(block_name: &'static str, data_value: u8) -> Block {
                 4 => "polished_diorite",
                 5 => "andesite",
                 6 => "polished_andesite",
+                7 => "invalid_stone",
                 _ => unreachable!(),
             };
-            Block {
-                name: ns(kind),
-                encoded: enc0(kind),
-                archetype: BlockArchetype::Normal,
-            }
+            Block::new(ns(kind), enc0(kind), BlockArchetype::Normal)
         }
         "leaves" => {
             let leaf = data_value & 0b0011;
@@ -284,11 +259,7 @@ fn modern_block(block_name: &'static str, data_value: u8) -> Block {
                 3 => "jungle_leaves",
                 _ => unreachable!(),
             };
-            Block {
-                name: ns(leaf),
-                encoded: enc0(leaf),
-                archetype: BlockArchetype::Normal,
-            }
+            Block::new(ns(leaf), enc0(leaf), BlockArchetype::Normal)
         }
         "leaves2" => {
             let leaf = data_value & 0b0011;
@@ -298,15 +269,11 @@ fn modern_block(block_name: &'static str, data_value: u8) -> Block {
                 2 | 3 => "invalid_leaves",
                 _ => unreachable!(),
             };
-            Block {
-                name: ns(leaf),
-                encoded: enc0(leaf),
-                archetype: BlockArchetype::Normal,
-            }
+            Block::new(ns(leaf), enc0(leaf), BlockArchetype::Normal)
         }
         "log" => {
             let log = data_value & 0b0011;
-            let axis = data_value & 0b1100;
+            let axis = (data_value >> 2) & 0b0011;
             let axis = match axis {
                 0 => "y",
                 1 => "x",
@@ -321,15 +288,15 @@ fn modern_block(block_name: &'static str, data_value: u8) -> Block {
                 3 => "jungle_log",
                 _ => unreachable!(),
             };
-            Block {
-                name: ns(log),
-                encoded: format!("minecraft:{log}|axis={axis}"),
-                archetype: BlockArchetype::Normal,
-            }
+            Block::new(
+                ns(log),
+                format!("minecraft:{log}|axis={axis}"),
+                BlockArchetype::Normal,
+            )
         }
         "log2" => {
             let log = data_value & 0b0011;
-            let axis = data_value & 0b1100;
+            let axis = (data_value >> 2) & 0b0011;
             let axis = match axis {
                 0 => "y",
                 1 => "x",
@@ -343,19 +310,19 @@ fn modern_block(block_name: &'static str, data_value: u8) -> Block {
                 2 | 3 => "invalid_log",
                 _ => unreachable!(),
             };
-            Block {
-                name: ns(log),
-                encoded: format!("minecraft:{log}|axis={axis}"),
-                archetype: BlockArchetype::Normal,
-            }
+            Block::new(
+                ns(log),
+                format!("minecraft:{log}|axis={axis}"),
+                BlockArchetype::Normal,
+            )
         }
         "snow_layer" => {
             let layers = (data_value & 0b0111) + 1;
-            Block {
-                name: ns("snow"),
-                encoded: format!("minecraft:snow|layers={layers}"),
-                archetype: BlockArchetype::Normal,
-            }
+            Block::new(
+                ns("snow"),
+                format!("minecraft:snow|layers={layers}"),
+                BlockArchetype::Normal,
+            )
         }
         "stained_hardened_clay" => {
             let col = match data_value & 0b1111 {
@@ -377,32 +344,16 @@ fn modern_block(block_name: &'static str, data_value: u8) -> Block {
                 15 => "black",
                 _ => unreachable!(),
             };
-            Block {
-                name: format!("minecraft:{col}_terracotta"),
-                encoded: format!("minecraft:{col}_terracotta|"),
-                archetype: BlockArchetype::Normal,
-            }
+            Block::new(
+                format!("minecraft:{col}_terracotta"),
+                format!("minecraft:{col}_terracotta|"),
+                BlockArchetype::Normal,
+            )
         }
-        "hardened_clay" => Block {
-            name: ns("terracotta"),
-            encoded,
-            archetype: BlockArchetype::Normal,
-        },
-        "tallgrass" => Block {
-            name: ns("tall_grass"),
-            encoded,
-            archetype: BlockArchetype::Normal,
-        },
-        "waterlily" => Block {
-            name: ns("lily_pad"),
-            encoded,
-            archetype: BlockArchetype::Normal,
-        },
-        _ => Block {
-            name: ns(block_name),
-            encoded,
-            archetype: BlockArchetype::Normal,
-        },
+        "hardened_clay" => Block::new(ns("terracotta"), encoded, BlockArchetype::Normal),
+        "tallgrass" => Block::new(ns("tall_grass"), encoded, BlockArchetype::Normal),
+        "waterlily" => Block::new(ns("lily_pad"), encoded, BlockArchetype::Normal),
+        _ => Block::new(ns(block_name), encoded, BlockArchetype::Normal),
     }
 }
 
@@ -2,12 +2,14 @@ use std::convert::TryFrom;
 use std::ops::Range;
 use std::sync::RwLock;
 
-use fastnbt::IntArray;
+use fastnbt::{ByteArray, IntArray};
 use once_cell::sync::OnceCell;
 use serde::Deserialize;
 
+use crate::bits::padded_blockstates;
+use crate::java::section::light_at;
 use crate::java::AIR;
-use crate::{biome::Biome, Block, Chunk, HeightMode};
+use crate::{biome::Biome, Block, Chunk, HeightMode, HeightmapKind};
 use crate::{bits_per_block, expand_heightmap, Heightmaps, PackedBits, SectionLike, SectionTower};
 
 /// A Minecraft chunk.
@@ -23,6 +25,10 @@ impl Chunk for JavaChunk {
         self.level.status.clone()
     }
 
+    fn data_version(&self) -> Option<i32> {
+        Some(self.data_version)
+    }
+
     fn surface_height(&self, x: usize, z: usize, mode: HeightMode) -> isize {
         let mut heightmap = self.level.lazy_heightmap.read().unwrap();
         if heightmap.is_none() {
@@ -76,12 +82,33 @@ impl Chunk for JavaChunk {
             None => Some(&AIR),
             Some(blockstates) => {
                 let sec_y = (y - sec.y as isize * 16) as usize;
-                let pal_index = blockstates.state(x, sec_y, z, sec.palette.len());
+                let pal_index =
+                    blockstates.state(x, sec_y, z, sec.palette.len(), self.data_version);
                 sec.palette.get(pal_index)
             }
         }
     }
 
+    fn block_light(&self, x: usize, y: isize, z: usize) -> Option<u8> {
+        let sec = self.level.sections.as_ref()?.get_section_for_y(y)?;
+        let sec_y = (y - sec.y as isize * 16) as usize;
+        light_at(sec.block_light.as_ref()?, x, sec_y, z)
+    }
+
+    fn heightmap(&self, x: usize, z: usize, kind: HeightmapKind) -> Option<isize> {
+        let y_min = self.level.sections.as_ref()?.y_min();
+        self.level
+            .heightmaps
+            .as_ref()?
+            .height_at(kind, x, z, y_min, self.data_version)
+    }
+
+    fn sky_light(&self, x: usize, y: isize, z: usize) -> Option<u8> {
+        let sec = self.level.sections.as_ref()?.get_section_for_y(y)?;
+        let sec_y = (y - sec.y as isize * 16) as usize;
+        light_at(sec.sky_light.as_ref()?, x, sec_y, z)
+    }
+
     fn y_range(&self) -> std::ops::Range<isize> {
         match &self.level.sections {
             Some(sections) => Range {
@@ -188,6 +215,12 @@ pub struct Pre18Section {
 
     #[serde(default)]
     pub palette: Vec<Block>,
+
+    /// Block light, 4 bits per block.
+    pub block_light: Option<ByteArray>,
+
+    /// Sky light, 4 bits per block. Missing for dimensions without a sky.
+    pub sky_light: Option<ByteArray>,
 }
 
 impl SectionLike for Pre18Section {
@@ -209,17 +242,21 @@ pub struct Pre18Blockstates {
 impl Pre18Blockstates {
     /// Get the state for the given block at x,y,z, where x, y, and z are
     /// relative to the section ie 0..16
+    ///
+    /// The data version is that of the chunk, and decides whether the states
+    /// are in the 1.16+ padded layout. The states are unpacked on first use,
+    /// so later calls must pass the same palette length and data version.
     #[inline(always)]
-    pub fn state(&self, x: usize, sec_y: usize, z: usize, pal_len: usize) -> usize {
-        let unpacked = self.unpacked.get_or_init(|| {
-            let bits_per_item = bits_per_block(pal_len);
-            let mut buf = [0u16; 16 * 16 * 16];
-            self.packed.unpack_blockstates(bits_per_item, &mut buf);
-            buf
-        });
-
+    pub fn state(
+        &self,
+        x: usize,
+        sec_y: usize,
+        z: usize,
+        pal_len: usize,
+        data_version: i32,
+    ) -> usize {
         let state_index = (sec_y * 16 * 16) + z * 16 + x;
-        unpacked[state_index] as usize
+        self.unpacked(pal_len, data_version)[state_index] as usize
     }
 
     /// Get iterator for the state indicies. This will increase in x, then z,
@@ -227,7 +264,7 @@ impl Pre18Blockstates {
     /// data for that block.
     ///
     /// The pal_len must be the length of the palette corresponding to these
-    /// blockstates.
+    /// blockstates, and data_version that of the chunk they came from.
     ///
     /// You can recover the coordinate be enumerating the iterator:
     ///
@@ -235,22 +272,31 @@ impl Pre18Blockstates {
     /// # use fastanvil::pre18::Pre18Blockstates;
     /// # fn main() {
     /// # let states: Pre18Blockstates = todo!();
-    /// for (i, block_index) in states.iter_indices(10).enumerate() {
+    /// for (i, block_index) in states.iter_indices(10, 2730).enumerate() {
     ///     let x = i & 0x000F;
     ///     let y = (i & 0x0F00) >> 8;
     ///     let z = (i & 0x00F0) >> 4;
     /// }
     /// # }
     /// ```
-    pub fn iter_indices(&self, pal_len: usize) -> impl Iterator<Item = usize> + '_ {
-        let unpacked = self.unpacked.get_or_init(|| {
+    pub fn iter_indices(
+        &self,
+        pal_len: usize,
+        data_version: i32,
+    ) -> impl Iterator<Item = usize> + '_ {
+        self.unpacked(pal_len, data_version)
+            .iter()
+            .map(|&i| i as usize)
+    }
+
+    fn unpacked(&self, pal_len: usize, data_version: i32) -> &[u16; 16 * 16 * 16] {
+        self.unpacked.get_or_init(|| {
             let bits_per_item = bits_per_block(pal_len);
             let mut buf = [0u16; 16 * 16 * 16];
-            self.packed.unpack_blockstates(bits_per_item, &mut buf);
+            self.packed
+                .unpack_into(bits_per_item, padded_blockstates(data_version), &mut buf);
             buf
-        });
-
-        unpacked.iter().map(|&i| i as usize)
+        })
     }
 }
 
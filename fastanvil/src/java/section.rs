@@ -1,4 +1,5 @@
-use serde::Deserialize;
+use fastnbt::ByteArray;
+use serde::{Deserialize, Serialize};
 
 use crate::{biome::Biome, BiomeData, Block, BlockData};
 
@@ -8,16 +9,51 @@ pub trait SectionLike {
 }
 
 /// A vertical section of a chunk (ie a 16x16x16 block cube)
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Serialize, Debug)]
 pub struct Section {
     #[serde(rename = "Y")]
     pub y: i8,
 
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "BlockData::is_empty")]
     pub block_states: BlockData<Block>,
 
-    #[serde(default)]
-    pub biomes: BiomeData<Biome>,
+    /// Biome ids, eg `minecraft:plains`. These are kept as written rather
+    /// than as [`Biome`] so that modded or newer biomes are written back
+    /// unchanged. Use [`Section::biome`] to get a [`Biome`].
+    #[serde(default, skip_serializing_if = "BiomeData::is_empty")]
+    pub biomes: BiomeData<String>,
+
+    /// Block light, 4 bits per block. Missing if the light has not been
+    /// calculated.
+    #[serde(rename = "BlockLight", skip_serializing_if = "Option::is_none")]
+    pub block_light: Option<ByteArray>,
+
+    /// Sky light, 4 bits per block. Missing if the light has not been
+    /// calculated, or for dimensions without a sky.
+    #[serde(rename = "SkyLight", skip_serializing_if = "Option::is_none")]
+    pub sky_light: Option<ByteArray>,
+}
+
+impl Section {
+    /// The biome at the section-relative coordinates, ie 0..16. Ids without a
+    /// known name are [`Biome::Unknown`]. Returns None if the section has no
+    /// biomes.
+    pub fn biome(&self, x: usize, sec_y: usize, z: usize) -> Option<Biome> {
+        self.biomes.at(x, sec_y, z).map(|id| Biome::from_name(id))
+    }
+}
+
+/// Get the light level at the section-relative coordinates from a light array.
+/// Light arrays store a 4 bit level for each block of the section, in the
+/// order y, z, x. Returns None if the array is too short.
+pub(crate) fn light_at(light: &[i8], x: usize, sec_y: usize, z: usize) -> Option<u8> {
+    let idx = (sec_y << 8) + (z << 4) + x;
+    let byte = *light.get(idx / 2)? as u8;
+
+    match idx % 2 {
+        0 => Some(byte & 0x0F),
+        _ => Some(byte >> 4),
+    }
 }
 
 impl SectionLike for Section {
@@ -1,16 +1,16 @@
 use bit_field::BitField;
 use fastnbt::LongArray;
 
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use std::fmt::Debug;
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Serialize, Debug)]
 #[serde(transparent)]
 pub struct BlockData<T: Debug> {
     inner: DataInner<T>,
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Serialize, Debug)]
 #[serde(transparent)]
 pub struct BiomeData<T: Debug> {
     inner: DataInner<T>,
@@ -59,6 +59,26 @@ impl<T: Debug> BlockData<T> {
     pub fn palette(&self) -> &[T] {
         self.inner.palette.as_slice()
     }
+
+    /// Set the block at x,y,z, where x,y,z are relative to the section ie
+    /// 0..16. The palette and packed data are rebuilt, so the bits per block
+    /// grow or shrink as needed and unused palette entries are dropped.
+    ///
+    /// Returns false without changing anything if there is no palette. This
+    /// happens when the block states were missing from the chunk entirely.
+    pub fn set(&mut self, x: usize, sec_y: usize, z: usize, value: T) -> bool
+    where
+        T: PartialEq,
+    {
+        let state_index = (sec_y * 16 * 16) + z * 16 + x;
+        self.inner
+            .set(state_index, 16 * 16 * 16, blockstates_bits_per_block, value)
+    }
+
+    /// True if there is no palette, ie the block states were missing.
+    pub(crate) fn is_empty(&self) -> bool {
+        self.inner.palette.is_empty()
+    }
 }
 
 impl<T: Debug> BiomeData<T> {
@@ -86,17 +106,36 @@ impl<T: Debug> BiomeData<T> {
     pub fn palette(&self) -> &[T] {
         self.inner.palette.as_slice()
     }
+
+    /// Set the biome of the 4x4x4 cell containing x,y,z, where x,y,z are
+    /// relative to the section ie 0..16. See [`BlockData::set`].
+    pub fn set(&mut self, x: usize, sec_y: usize, z: usize, value: T) -> bool
+    where
+        T: PartialEq,
+    {
+        let state_index = (sec_y / 4 * 4 * 4) + z / 4 * 4 + x / 4;
+        self.inner
+            .set(state_index, 4 * 4 * 4, biomes_bits_per_block, value)
+    }
+
+    /// True if there is no palette, ie the biomes were missing.
+    pub(crate) fn is_empty(&self) -> bool {
+        self.inner.palette.is_empty()
+    }
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Serialize, Debug)]
 struct DataInner<T: Debug> {
+    #[serde(skip_serializing_if = "Option::is_none")]
     data: Option<LongArray>,
     palette: Vec<T>,
 }
 
 impl<T: Debug> DataInner<T> {
     pub fn at(&self, index: usize, bits_per_item: usize) -> Option<&T> {
-        if self.data.is_none() && self.palette.len() == 1 {
+        // A single entry palette means every value is that entry. The game
+        // leaves out the data in this case, and ignores it if present.
+        if self.palette.len() == 1 {
             return self.palette.get(0);
         }
 
@@ -111,12 +150,79 @@ impl<T: Debug> DataInner<T> {
         // Super important line: treat the i64 as an u64.
         // Bug 1: Kept i64 and the get_bits interprets as signed.
         // Bug 2: Went to usize, worked on 64bit platforms broke on 32 bit like WASM.
-        let long = data[long_index] as u64;
+        // Data can be shorter than the palette implies in corrupt or unusual
+        // chunks, treat the block as missing rather than panic.
+        let long = *data.get(long_index)? as u64;
 
         let palette_index = long.get_bits(range);
 
         self.palette.get(palette_index as usize)
     }
+
+    /// Set the value at the given index, then rebuild the palette and packed
+    /// data. `len` is the number of values in the container, and
+    /// `bits_per_item` gives the bits needed for a palette length.
+    fn set(&mut self, index: usize, len: usize, bits_per_item: fn(usize) -> usize, value: T) -> bool
+    where
+        T: PartialEq,
+    {
+        if self.palette.is_empty() {
+            return false;
+        }
+
+        let mut indices: Vec<usize> = match &self.data {
+            Some(data) if self.palette.len() > 1 => {
+                StatesIter::new(bits_per_item(self.palette.len()), len, data).collect()
+            }
+            _ => vec![],
+        };
+        // Missing or short data is treated as the first palette entry, as
+        // [`DataInner::at`] would otherwise report nothing for it.
+        indices.resize(len, 0);
+
+        let new_index = match self.palette.iter().position(|p| *p == value) {
+            Some(i) => i,
+            None => {
+                self.palette.push(value);
+                self.palette.len() - 1
+            }
+        };
+        indices[index] = new_index;
+
+        // Drop palette entries that are no longer used, remapping indices.
+        let mut used = vec![false; self.palette.len()];
+        for i in &indices {
+            used[*i] = true;
+        }
+
+        let mut remap = vec![0; self.palette.len()];
+        let mut next = 0;
+        for (i, is_used) in used.iter().enumerate() {
+            remap[i] = next;
+            next += *is_used as usize;
+        }
+
+        let mut used = used.into_iter();
+        self.palette.retain(|_| used.next().unwrap_or(false));
+
+        if self.palette.len() == 1 {
+            self.data = None;
+            return true;
+        }
+
+        let bits = bits_per_item(self.palette.len());
+        let values_per_long = 64 / bits;
+        let mut data = vec![0i64; len.div_ceil(values_per_long)];
+
+        for (i, pal_index) in indices.iter().enumerate() {
+            let pal_index = remap[*pal_index] as u64;
+            let shift = (i % values_per_long) * bits;
+            data[i / values_per_long] |= (pal_index << shift) as i64;
+        }
+
+        self.data = Some(LongArray::new(data));
+        true
+    }
 }
 
 // Block states at the least can be missing from the world data. This typically
@@ -161,7 +267,7 @@ fn biomes_bits_per_block(palette_len: usize) -> usize {
 }
 
 pub(crate) fn min_bits_for_n_states(palette_len: usize) -> usize {
-    (usize::BITS - (palette_len - 1).leading_zeros()) as usize
+    (usize::BITS - palette_len.saturating_sub(1).leading_zeros()) as usize
 }
 
 /// Iterator over block state data. Each value is the index into the relevant palette.
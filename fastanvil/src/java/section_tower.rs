@@ -1,4 +1,4 @@
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
 use crate::SectionLike;
 
@@ -14,10 +14,34 @@ pub struct SectionTower<S> {
 }
 
 impl<S> SectionTower<S> {
+    /// All sections, in the order they were stored in the chunk. This can
+    /// include the empty section some versions store below the world. See
+    /// [`iter_sections`][`SectionTower::iter_sections`] for sections in order
+    /// of height.
     pub fn sections(&self) -> &[S] {
         &self.sections
     }
 
+    /// Iterate over the sections that are part of the world, from the lowest
+    /// to the highest. Sections missing from the chunk are skipped. The first
+    /// section covers the blocks from [`y_min`][`SectionTower::y_min`].
+    ///
+    /// This is much faster than getting each block through
+    /// [`Chunk::block`][`crate::Chunk::block`] when processing a whole
+    /// section at a time.
+    pub fn iter_sections(&self) -> impl Iterator<Item = &S> + '_ {
+        self.map
+            .iter()
+            .filter_map(|index| self.sections.get((*index)?))
+    }
+
+    /// Mutable access to the sections, eg to edit their blocks. Changing the
+    /// `y` of a section is not reflected by
+    /// [`get_section_for_y`][`SectionTower::get_section_for_y`].
+    pub fn sections_mut(&mut self) -> &mut [S] {
+        &mut self.sections
+    }
+
     pub(crate) fn take_sections(self) -> Vec<S> {
         self.sections
     }
@@ -35,10 +59,13 @@ impl<S> SectionTower<S> {
         self.sections.get(section_index?)
     }
 
+    /// The lowest block y in the tower, always the bottom of a section.
     pub fn y_min(&self) -> isize {
         self.y_min
     }
 
+    /// One more than the highest block y in the tower, so the tower covers
+    /// `y_min()..y_max()`. Both are zero if there are no sections.
     pub fn y_max(&self) -> isize {
         self.y_max
     }
@@ -107,6 +134,16 @@ impl<'de, S: SectionLike + Deserialize<'de>> Deserialize<'de> for SectionTower<S
     }
 }
 
+/// Serializes as the list of sections, as they were deserialized.
+impl<S: Serialize> Serialize for SectionTower<S> {
+    fn serialize<Ser>(&self, serializer: Ser) -> Result<Ser::Ok, Ser::Error>
+    where
+        Ser: serde::Serializer,
+    {
+        self.sections.serialize(serializer)
+    }
+}
+
 const fn y_to_index(y: isize, y_min: isize) -> u8 {
     ((y - y_min) >> 4) as u8
 }
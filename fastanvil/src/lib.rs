@@ -4,8 +4,9 @@
 //!
 //! This crate also contains a [`JavaChunk`] that allows deserializing 1.18
 //! down to about 1.15 chunks into some structs. This doesn't record all
-//! information from a chunk however, eg entities are lost. It is not suitable
-//! for serializing back into a region.
+//! information from a chunk however, eg entities are lost. Only 1.18+ chunks
+//! ([`CurrentJavaChunk`]) can be serialized back, and only the data they keep.
+//! Use [`LosslessJavaChunk`] to keep the rest of the chunk as well.
 //!
 //! You can create your own chunk structures to (de)serialize using [`fastnbt`].
 //!
@@ -20,6 +21,7 @@
 pub mod biome;
 pub mod tex;
 pub mod complete;
+pub mod schematic;
 
 mod bits;
 mod dimension;
@@ -50,6 +52,9 @@ pub enum Error {
     InvalidOffset(isize, isize),
     UnknownCompression(u8),
     ChunkTooLarge,
+    ExternalChunk(usize, usize),
+    Nbt(fastnbt::error::Error),
+    TruncatedHeader(u64),
 }
 
 impl From<std::io::Error> for Error {
@@ -58,6 +63,12 @@ impl From<std::io::Error> for Error {
     }
 }
 
+impl From<fastnbt::error::Error> for Error {
+    fn from(err: fastnbt::error::Error) -> Error {
+        Error::Nbt(err)
+    }
+}
+
 pub type Result<T> = std::result::Result<T, Error>;
 
 impl std::fmt::Display for Error {
@@ -71,6 +82,13 @@ impl std::fmt::Display for Error {
                 "compression scheme ({scheme}) was not recognised for chunk"
             )),
             Error::ChunkTooLarge => f.write_str("chunk too large to store"),
+            Error::ExternalChunk(x, z) => f.write_fmt(format_args!(
+                "chunk x = {x}, z = {z} is stored in an external file"
+            )),
+            Error::Nbt(e) => f.write_fmt(format_args!("nbt error: {e}")),
+            Error::TruncatedHeader(len) => f.write_fmt(format_args!(
+                "region header truncated: found {len} bytes, expected at least 8192"
+            )),
         }
     }
 }
@@ -1,25 +1,27 @@
 use std::convert::TryFrom;
 use std::io::{self, Cursor, Read, Seek, SeekFrom, Write};
+use std::time::{SystemTime, UNIX_EPOCH};
 
 use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
-use flate2::read::ZlibEncoder;
+use flate2::read::{GzEncoder, ZlibEncoder};
 use flate2::Compression;
 use num_enum::TryFromPrimitive;
 
-use crate::{Error, Result};
+use crate::{Error, JavaChunk, Result};
 
 /// the size in bytes of a 'sector' in a region file. Sectors are Minecraft's size unit
 /// for chunks. For example, a chunk might be `3 * SECTOR_SIZE` bytes. The
 /// actual compressed bytes of a chunk may be smaller and the exact value is
 /// tracking in the chunk header.
-pub(crate) const SECTOR_SIZE: usize = 4096;
+pub const SECTOR_SIZE: usize = 4096;
 
-/// the size of the region file header.
-pub(crate) const REGION_HEADER_SIZE: usize = 2 * SECTOR_SIZE;
+/// the size of the region file header. The header is a sector of chunk
+/// locations followed by a sector of chunk timestamps.
+pub const REGION_HEADER_SIZE: usize = 2 * SECTOR_SIZE;
 
 /// size of header for each chunk in the region file. This header proceeds the
 /// compressed chunk data.
-pub(crate) const CHUNK_HEADER_SIZE: usize = 5;
+pub const CHUNK_HEADER_SIZE: usize = 5;
 
 /// A Minecraft Region, allowing reading and writing of chunk data to a stream (eg a
 /// File). This does not concern itself with manipulating chunk data, users are
@@ -40,11 +42,39 @@ impl<S> Region<S>
 where
     S: Read + Seek,
 {
+    /// Load a region from an existing stream, checking the stream is long
+    /// enough to hold the region header first. An empty stream is a valid
+    /// empty region. A stream shorter than the header returns
+    /// [`Error::TruncatedHeader`].
+    ///
+    /// Otherwise this is the same as [`from_stream`][`Region::from_stream`].
+    pub fn load(mut stream: S) -> Result<Self> {
+        let len = stream.seek(SeekFrom::End(0))?;
+        stream.rewind()?;
+
+        if len == 0 {
+            return Ok(Self {
+                stream,
+                offsets: vec![2], // 2 is the end of the header
+            });
+        }
+
+        if len < REGION_HEADER_SIZE as u64 {
+            return Err(Error::TruncatedHeader(len));
+        }
+
+        Self::from_stream(stream)
+    }
+
     /// Load a region from an existing stream, meaning something that implements
     /// [`Read`] and [`Seek`]. This will assume a seek of zero is the start of
     /// the region. This does not load all region data into memory immediately.
     /// Chunks are read from the underlying stream when needed.
     ///
+    /// The length of the stream is not checked, so a truncated header results
+    /// in an IO error when a chunk is read. Use [`load`][`Region::load`] to
+    /// check the header up front.
+    ///
     /// The most obvious 'stream' is a file:
     /// ```no_run
     /// # use fastanvil::Region;
@@ -101,8 +131,10 @@ where
 
         tmp.offsets.sort_unstable();
 
-        // we add an offset representing the end of sectors that are in use.
-        tmp.offsets.push(max_offset + max_offsets_sector_count);
+        // we add an offset representing the end of sectors that are in use. A
+        // region without chunks still has the header in use.
+        tmp.offsets
+            .push((max_offset + max_offsets_sector_count).max(2));
         Ok(tmp)
     }
 
@@ -111,6 +143,12 @@ where
     /// not exist, which will be the case if that chunk has not generated.  If
     /// `x` or `z` are outside `0..32`, [`Error::InvalidOffset`] is returned.
     ///
+    /// Chunks too large to fit in the region are stored by Minecraft in a
+    /// separate `c.X.Z.mcc` file. Reading such a chunk with this method returns
+    /// [`Error::ExternalChunk`]; use
+    /// [`read_chunk_with_external`][`Region::read_chunk_with_external`] to read
+    /// them.
+    ///
     /// ```no_run
     /// # use fastanvil::Region;
     /// # use fastanvil::Result;
@@ -130,34 +168,137 @@ where
     /// # }
     ///  ```
     pub fn read_chunk(&mut self, x: usize, z: usize) -> Result<Option<Vec<u8>>> {
-        self.compression_scheme(x, z)?
-            .map(|scheme| match scheme {
-                CompressionScheme::Zlib => {
-                    let mut decoder = flate2::write::ZlibDecoder::new(vec![]);
-                    self.read_compressed_chunk(x, z, &mut decoder)?;
-                    Ok(decoder.finish()?)
-                }
-                CompressionScheme::Gzip => {
-                    let mut decoder = flate2::write::GzDecoder::new(vec![]);
-                    self.read_compressed_chunk(x, z, &mut decoder)?;
-                    Ok(decoder.finish()?)
-                }
-                CompressionScheme::Uncompressed => {
-                    let mut buf = vec![];
-                    self.read_compressed_chunk(x, z, &mut buf)?;
-                    Ok(buf)
-                }
-                CompressionScheme::Lz4 => {
-                    let mut decoder = Lz4DecoderWrapper::new(vec![]);
-                    self.read_compressed_chunk(x, z, &mut decoder)?;
-                    Ok(decoder.finish()?)
-                }
-            })
-            .transpose()
+        self.read_chunk_with_external(x, z, |x, z| Err(Error::ExternalChunk(x, z)))
     }
 
-    /// Get the location of the chunk in the stream.
-    pub(crate) fn location(&mut self, x: usize, z: usize) -> Result<Option<ChunkLocation>> {
+    /// Read the chunk located at the chunk coordinates `x`, `z`, like
+    /// [`read_chunk`][`Region::read_chunk`]. If the chunk is stored externally
+    /// the `external` closure is called with the chunk coordinates and should
+    /// return the compressed contents of the external file.
+    ///
+    /// Minecraft names these files `c.X.Z.mcc` where `X` and `Z` are the
+    /// absolute chunk coordinates, and stores them next to the region files:
+    ///
+    /// ```no_run
+    /// # use fastanvil::Region;
+    /// # use fastanvil::Result;
+    /// # use std::fs::File;
+    /// # fn main() -> Result<()> {
+    /// let (region_x, region_z) = (-1, 2);
+    /// let file = File::open("region/r.-1.2.mca")?;
+    /// let mut region = Region::from_stream(file)?;
+    ///
+    /// let chunk = region.read_chunk_with_external(4, 5, |x, z| {
+    ///     let x = region_x * 32 + x as isize;
+    ///     let z = region_z * 32 + z as isize;
+    ///     Ok(std::fs::read(format!("region/c.{x}.{z}.mcc"))?)
+    /// })?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn read_chunk_with_external<F>(
+        &mut self,
+        x: usize,
+        z: usize,
+        mut external: F,
+    ) -> Result<Option<Vec<u8>>>
+    where
+        F: FnMut(usize, usize) -> Result<Vec<u8>>,
+    {
+        let mut buf = vec![];
+        let found = self.read_chunk_into_with_external(x, z, &mut buf, &mut external)?;
+        Ok(found.then_some(buf))
+    }
+
+    /// Read the chunk located at the chunk coordinates `x`, `z` into `buf`,
+    /// replacing its contents, like [`read_chunk`][`Region::read_chunk`].
+    /// Returns false if the chunk is not present.
+    ///
+    /// Reusing one buffer avoids an allocation per chunk when scanning a
+    /// region. Since [`fastnbt::from_bytes`] can borrow from the buffer, the
+    /// parsed chunk can hold types like [`fastnbt::borrow::LongArray`] without
+    /// copying, as long as it is dropped before the next chunk is read:
+    ///
+    /// ```no_run
+    /// # use fastanvil::{Region, Result};
+    /// use fastnbt::borrow::LongArray;
+    /// use serde::Deserialize;
+    ///
+    /// #[derive(Deserialize)]
+    /// struct Heights<'a> {
+    ///     #[serde(borrow, rename = "MOTION_BLOCKING")]
+    ///     motion_blocking: Option<LongArray<'a>>,
+    /// }
+    ///
+    /// #[derive(Deserialize)]
+    /// struct Chunk<'a> {
+    ///     #[serde(borrow, rename = "Heightmaps")]
+    ///     heightmaps: Heights<'a>,
+    /// }
+    ///
+    /// # fn main() -> Result<()> {
+    /// let mut region = Region::from_stream(std::fs::File::open("r.0.0.mca")?)?;
+    /// let mut buf = Vec::new();
+    ///
+    /// for z in 0..32 {
+    ///     for x in 0..32 {
+    ///         if !region.read_chunk_into(x, z, &mut buf)? {
+    ///             continue;
+    ///         }
+    ///         let chunk: Chunk = fastnbt::from_bytes(&buf)?;
+    ///         let longs = chunk.heightmaps.motion_blocking.map(|h| h.iter().count());
+    ///         println!("{x},{z}: {longs:?}");
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// If reading fails the buffer may contain partial data.
+    pub fn read_chunk_into(&mut self, x: usize, z: usize, buf: &mut Vec<u8>) -> Result<bool> {
+        self.read_chunk_into_with_external(x, z, buf, &mut |x, z| Err(Error::ExternalChunk(x, z)))
+    }
+
+    fn read_chunk_into_with_external(
+        &mut self,
+        x: usize,
+        z: usize,
+        buf: &mut Vec<u8>,
+        external: &mut dyn FnMut(usize, usize) -> Result<Vec<u8>>,
+    ) -> Result<bool> {
+        buf.clear();
+
+        let Some(scheme) = self.compression_scheme(x, z)? else {
+            return Ok(false);
+        };
+
+        match scheme {
+            CompressionScheme::Zlib => {
+                let mut decoder = flate2::write::ZlibDecoder::new(buf);
+                self.read_compressed_chunk(x, z, &mut decoder, external)?;
+                decoder.finish()?;
+            }
+            CompressionScheme::Gzip => {
+                let mut decoder = flate2::write::GzDecoder::new(buf);
+                self.read_compressed_chunk(x, z, &mut decoder, external)?;
+                decoder.finish()?;
+            }
+            CompressionScheme::Uncompressed => {
+                self.read_compressed_chunk(x, z, buf, external)?;
+            }
+            CompressionScheme::Lz4 => {
+                let mut decoder = Lz4DecoderWrapper::new(buf);
+                self.read_compressed_chunk(x, z, &mut decoder, external)?;
+                decoder.finish()?;
+            }
+        }
+
+        Ok(true)
+    }
+
+    /// Get the location of the chunk in the stream, read from the region
+    /// header. `Ok(None)` means the chunk does not exist.
+    pub fn location(&mut self, x: usize, z: usize) -> Result<Option<ChunkLocation>> {
         if x >= 32 || z >= 32 {
             return Err(Error::InvalidOffset(x as isize, z as isize));
         }
@@ -165,27 +306,148 @@ where
         self.stream.seek(SeekFrom::Start(header_pos(x, z)))?;
 
         let mut buf = [0u8; 4];
-        self.stream.read_exact(&mut buf[..])?;
+        if !self.read_header_exact(&mut buf)? {
+            return Ok(None);
+        }
+
+        Ok(parse_location(buf))
+    }
+
+    /// Get the location of every chunk in the region, reading the whole
+    /// location table of the header at once. This is much faster than calling
+    /// [`location`][`Region::location`] for every chunk.
+    ///
+    /// The location of the chunk at `x`, `z` is at index `z * 32 + x`, and is
+    /// None if the chunk does not exist.
+    pub fn read_locations(&mut self) -> Result<[Option<ChunkLocation>; 1024]> {
+        self.stream.seek(SeekFrom::Start(0))?;
+
+        let mut buf = [0u8; SECTOR_SIZE];
+        if !self.read_header_exact(&mut buf)? {
+            return Ok([None; 1024]);
+        }
+
+        Ok(std::array::from_fn(|i| {
+            let entry = &buf[i * 4..i * 4 + 4];
+            parse_location([entry[0], entry[1], entry[2], entry[3]])
+        }))
+    }
 
-        let mut offset = 0u64;
-        offset |= (buf[0] as u64) << 16;
-        offset |= (buf[1] as u64) << 8;
-        offset |= buf[2] as u64;
-        let sectors = buf[3] as u64;
+    /// Fill `buf` from the current position in the header. Returns false if
+    /// the stream is empty, which is an empty region, see `Region::load`.
+    fn read_header_exact(&mut self, buf: &mut [u8]) -> Result<bool> {
+        if let Err(e) = self.stream.read_exact(buf) {
+            if e.kind() == std::io::ErrorKind::UnexpectedEof
+                && self.stream.seek(SeekFrom::End(0))? == 0
+            {
+                return Ok(false);
+            }
+            return Err(e.into());
+        }
+        Ok(true)
+    }
+
+    /// Whether there is a chunk at the chunk coordinates `x`, `z`. Only the
+    /// region header is read, so this is much cheaper than reading the chunk.
+    /// If `x` or `z` are outside `0..32`, [`Error::InvalidOffset`] is
+    /// returned.
+    pub fn has_chunk(&mut self, x: usize, z: usize) -> Result<bool> {
+        Ok(self.location(x, z)?.is_some())
+    }
+
+    /// The coordinates of every chunk present in the region, read from the
+    /// region header. Chunks are in the same order as [`iter`][`Region::iter`],
+    /// increasing in x then z.
+    pub fn present_chunks(&mut self) -> Result<impl Iterator<Item = (usize, usize)>> {
+        let locations = self.read_locations()?;
+        Ok((0..1024)
+            .filter(move |&i| locations[i].is_some())
+            .map(|i| (i % 32, i / 32)))
+    }
+
+    /// Check the region header for entries that cannot be right, such as
+    /// chunks that overlap each other or extend past the end of the stream.
+    /// Only the header is read, the chunks themselves are not checked.
+    ///
+    /// Problems are in chunk order, increasing in x then z. An empty list
+    /// means no problems were found.
+    pub fn validate(&mut self) -> Result<Vec<RegionProblem>> {
+        // Tolerate the last chunk not being padded to a whole sector.
+        let len = unstable_stream_len(&mut self.stream)?;
+        let len_sectors = unstable_div_ceil(len as usize, SECTOR_SIZE) as u64;
+
+        let mut problems = vec![];
+        let mut placed = vec![];
+
+        for (i, loc) in self.read_locations()?.into_iter().enumerate() {
+            let Some(loc) = loc else {
+                continue;
+            };
+            let (x, z) = (i % 32, i / 32);
+
+            if loc.sectors == 0 {
+                problems.push(RegionProblem::ZeroLength { x, z });
+            } else if loc.offset < 2 {
+                problems.push(RegionProblem::InHeader { x, z });
+            } else if loc.offset + loc.sectors > len_sectors {
+                problems.push(RegionProblem::BeyondEnd { x, z });
+            } else {
+                placed.push((loc.offset, loc.offset + loc.sectors, (x, z)));
+            }
+        }
+
+        // Any chunk starting before the furthest end seen so far overlaps the
+        // chunk with that end.
+        placed.sort_unstable();
+        let mut furthest: Option<(u64, (usize, usize))> = None;
+
+        for (start, end, (x, z)) in placed {
+            match furthest {
+                Some((furthest_end, other)) if start < furthest_end => {
+                    problems.push(RegionProblem::Overlap { x, z, other });
+                    if end > furthest_end {
+                        furthest = Some((end, (x, z)));
+                    }
+                }
+                _ => furthest = Some((end, (x, z))),
+            }
+        }
+
+        problems.sort_by_key(|p| {
+            let (x, z) = p.chunk();
+            (z, x)
+        });
+        Ok(problems)
+    }
+
+    /// Get the last-modified timestamp of the chunk at `x`, `z`, in seconds
+    /// since the unix epoch. This is read from the region header, so does not
+    /// require reading the chunk itself. `Ok(None)` means the chunk does not
+    /// exist. If `x` or `z` are outside `0..32`, [`Error::InvalidOffset`] is
+    /// returned.
+    pub fn chunk_timestamp(&mut self, x: usize, z: usize) -> Result<Option<u32>> {
+        if self.location(x, z)?.is_none() {
+            return Ok(None);
+        }
 
-        Ok((offset != 0 || sectors != 0).then_some(ChunkLocation { offset, sectors }))
+        self.stream.seek(SeekFrom::Start(timestamp_pos(x, z)))?;
+        Ok(Some(self.stream.read_u32::<BigEndian>()?))
     }
 
     /// Low level method. Read a compressed chunk into the given writer. The
     /// `compression_scheme` method can be used to discover how the chunk
     /// written is compressed, allowing you to write directly to a decompresser.
     ///
+    /// If the chunk is stored externally, `external` is called to get the
+    /// compressed data instead.
+    ///
     /// Returns a bool indicating if a chunk was found at the given x,z.
     fn read_compressed_chunk(
         &mut self,
         x: usize,
         z: usize,
         writer: &mut dyn Write,
+        external: &mut dyn FnMut(usize, usize) -> Result<Vec<u8>>,
     ) -> Result<bool> {
         let Some(loc) = self.location(x, z)? else {
             return Ok(false);
@@ -198,6 +460,11 @@ where
         self.stream.read_exact(&mut buf)?;
         let metadata = ChunkMeta::new(&buf)?;
 
+        if metadata.external {
+            writer.write_all(&external(x, z)?)?;
+            return Ok(true);
+        }
+
         let mut adapted = (&mut self.stream).take(metadata.compressed_len as u64);
 
         io::copy(&mut adapted, writer)?;
@@ -259,10 +526,46 @@ where
     /// compressed with in the region. Used in conjuction with
     /// `read_compressed_chunk`.
     fn compression_scheme(&mut self, x: usize, z: usize) -> Result<Option<CompressionScheme>> {
-        if x >= 32 || z >= 32 {
-            return Err(Error::InvalidOffset(x as isize, z as isize));
-        }
+        Ok(self
+            .read_chunk_meta(x, z)?
+            .map(|meta| meta.compression_scheme))
+    }
 
+    /// Get the length and compression scheme from the 5 byte header in front
+    /// of the chunk's data, without reading or decompressing the data itself.
+    /// `Ok(None)` means the chunk does not exist.
+    ///
+    /// The length is as declared in the file, so includes the byte for the
+    /// compression scheme. Comparing it with the sectors allocated to the
+    /// chunk, see [`location`][`Region::location`], can help find corrupt
+    /// chunks.
+    ///
+    /// ```no_run
+    /// # use fastanvil::{Region, Result};
+    /// # fn main() -> Result<()> {
+    /// let file = std::fs::File::open("r.0.0.mca")?;
+    /// let mut region = Region::from_stream(file)?;
+    ///
+    /// if let (Some(loc), Some((len, _))) = (region.location(0, 0)?, region.chunk_raw_meta(0, 0)?) {
+    ///     // sectors are 4 KiB, and the length itself takes 4 bytes.
+    ///     if len as u64 + 4 > loc.sectors * 4096 {
+    ///         println!("chunk 0,0 claims to be longer than its sectors");
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn chunk_raw_meta(
+        &mut self,
+        x: usize,
+        z: usize,
+    ) -> Result<Option<(u32, CompressionScheme)>> {
+        Ok(self
+            .read_chunk_meta(x, z)?
+            .map(|meta| (meta.len, meta.compression_scheme)))
+    }
+
+    fn read_chunk_meta(&mut self, x: usize, z: usize) -> Result<Option<ChunkMeta>> {
         let Some(loc) = self.location(x, z)? else {
             return Ok(None);
         };
@@ -272,9 +575,7 @@ where
 
         let mut buf = [0u8; 5];
         self.stream.read_exact(&mut buf)?;
-        let metadata = ChunkMeta::new(&buf)?;
-
-        Ok(Some(metadata.compression_scheme))
+        Ok(Some(ChunkMeta::new(&buf)?))
     }
 
     /// Create an iterator for the chunks of the region. Chunks not present in
@@ -283,6 +584,20 @@ where
         RegionIter::new(self)
     }
 
+    /// Create an iterator for the chunks of the region, parsed into
+    /// [`JavaChunk`]s. Chunks not present in the file are skipped. Chunks that
+    /// fail to read or parse are returned as errors, and iteration can
+    /// continue past them.
+    pub fn chunks_parsed(
+        &mut self,
+    ) -> impl Iterator<Item = Result<(usize, usize, JavaChunk)>> + '_ {
+        self.iter().map(|chunk| {
+            let chunk = chunk?;
+            let parsed = JavaChunk::from_bytes(&chunk.data)?;
+            Ok((chunk.x, chunk.z, parsed))
+        })
+    }
+
     fn chunk_meta(&self, compressed_chunk_size: u32, scheme: CompressionScheme) -> [u8; 5] {
         let mut buf = [0u8; 5];
         let mut c = Cursor::new(buf.as_mut_slice());
@@ -296,6 +611,29 @@ where
     }
 }
 
+impl<'a> Region<Cursor<&'a [u8]>> {
+    /// Load a read-only region from data already in memory, such as a region
+    /// fetched in a web context. The region borrows the data rather than
+    /// copying it.
+    ///
+    /// This is the same as calling [`load`][`Region::load`] with the data
+    /// wrapped in a [`Cursor`].
+    ///
+    /// ```no_run
+    /// # use fastanvil::Region;
+    /// # use fastanvil::Result;
+    /// # fn main() -> Result<()> {
+    /// let data: Vec<u8> = todo!("get data");
+    /// let mut region = Region::from_slice(&data)?;
+    /// let chunk = region.read_chunk(0, 0)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn from_slice(data: &'a [u8]) -> Result<Self> {
+        Self::load(Cursor::new(data))
+    }
+}
+
 impl<S> Region<S>
 where
     S: Read + Write + Seek,
@@ -303,6 +641,20 @@ where
     /// Create an new empty region. **The provided stream will be overwritten**, and
     /// will assume a seek to 0 is the start of the region. The stream needs
     /// read, write, and seek, like a file provides.
+    ///
+    /// This writes the two zeroed header sectors, so chunks can be written
+    /// straight away:
+    ///
+    /// ```
+    /// # use fastanvil::{Region, Result};
+    /// # use std::io::Cursor;
+    /// # fn main() -> Result<()> {
+    /// let mut region = Region::create(Cursor::new(Vec::new()))?;
+    /// region.write_chunk(0, 0, &[10, 0, 0, 0])?;
+    /// assert!(region.read_chunk(0, 0)?.is_some());
+    /// # Ok(())
+    /// # }
+    /// ```
     pub fn create(mut stream: S) -> Result<Self> {
         stream.rewind()?;
         stream.write_all(&[0; REGION_HEADER_SIZE])?;
@@ -314,23 +666,65 @@ where
     }
 
     /// Write the given uncompressed NBT chunk data to the chunk coordinates x,
-    /// z.
+    /// z. The chunk's timestamp in the region header is set to the current
+    /// time.
     ///
     /// The chunk data will be compressed with zlib by default. You can use
     /// write_compressed_chunk if you want more control. If `x` or `z` are
-    /// outside `0..32`, [`Error::InvalidOffset`] is returned.
+    /// outside `0..32`, [`Error::InvalidOffset`] is returned. If the compressed
+    /// chunk would need more than 255 sectors, [`Error::ChunkTooLarge`] is
+    /// returned and the region is left untouched.
     pub fn write_chunk(&mut self, x: usize, z: usize, uncompressed_chunk: &[u8]) -> Result<()> {
-        let mut buf = vec![];
-        let mut enc = ZlibEncoder::new(uncompressed_chunk, Compression::fast());
-        enc.read_to_end(&mut buf)?;
-        self.write_compressed_chunk(x, z, CompressionScheme::Zlib, &buf)
+        self.write_chunk_with(x, z, CompressionScheme::Zlib, uncompressed_chunk)
+    }
+
+    /// Write the given uncompressed NBT chunk data to the chunk coordinates x,
+    /// z, compressing it with the given scheme. Otherwise behaves the same as
+    /// [`write_chunk`][`Region::write_chunk`].
+    pub fn write_chunk_with(
+        &mut self,
+        x: usize,
+        z: usize,
+        scheme: CompressionScheme,
+        uncompressed_chunk: &[u8],
+    ) -> Result<()> {
+        let buf = match scheme {
+            CompressionScheme::Zlib => {
+                let mut buf = vec![];
+                let mut enc = ZlibEncoder::new(uncompressed_chunk, Compression::fast());
+                enc.read_to_end(&mut buf)?;
+                buf
+            }
+            CompressionScheme::Gzip => {
+                let mut buf = vec![];
+                let mut enc = GzEncoder::new(uncompressed_chunk, Compression::fast());
+                enc.read_to_end(&mut buf)?;
+                buf
+            }
+            CompressionScheme::Uncompressed => uncompressed_chunk.to_vec(),
+            CompressionScheme::Lz4 => {
+                let mut buf = vec![];
+                let mut enc = lz4_java_wrc::Lz4BlockOutput::new(&mut buf);
+                enc.write_all(uncompressed_chunk)?;
+                enc.flush()?;
+                drop(enc);
+                buf
+            }
+        };
+
+        self.write_compressed_chunk(x, z, scheme, &buf)
     }
 
     /// Low level method to write the given compressed chunk data to the stream.
     ///
     /// It is the callers responsibility to make sure the compression scheme
     /// matches the compression used. If `x` or `z` are outside `0..32`,
-    /// [`Error::InvalidOffset`] is returned.
+    /// [`Error::InvalidOffset`] is returned. If the chunk would need more than
+    /// 255 sectors, [`Error::ChunkTooLarge`] is returned.
+    ///
+    /// If the chunk already exists and the new data fits in its current
+    /// sectors, they are reused. Otherwise the chunk is appended to the end of
+    /// the region, leaving a gap where it used to be.
     pub fn write_compressed_chunk(
         &mut self,
         x: usize,
@@ -342,6 +736,12 @@ where
         let required_sectors =
             unstable_div_ceil(CHUNK_HEADER_SIZE + compressed_chunk.len(), SECTOR_SIZE);
 
+        // check before touching the stream so a failed write leaves the region
+        // intact.
+        if required_sectors > 255 {
+            return Err(Error::ChunkTooLarge);
+        }
+
         if let Some(loc) = loc {
             // chunk already exists in the region file, need to update it.
             let i = self.offsets.binary_search(&loc.offset).unwrap();
@@ -376,28 +776,34 @@ where
             self.set_header(x, z, offset, required_sectors)?;
         }
 
+        self.set_chunk_timestamp(x, z, now())?;
+
         Ok(())
     }
 
-    /// Remove the chunk at the chunk location with the coordinates x and z.
+    /// Remove the chunk at the chunk location with the coordinates x and z,
+    /// so that Minecraft generates it again. Returns whether there was a chunk
+    /// to remove.
     ///
-    /// If you are stripping chunks from regions to save disk space, you should
-    /// instead iterate through the chunks of the region, and write the desired
-    /// chunks to a new region, and write that to disk. This will ensure chunks are
+    /// The sectors the chunk used are left as a gap in the region. If you are
+    /// stripping chunks from regions to save disk space, you should instead
+    /// iterate through the chunks of the region, and write the desired chunks
+    /// to a new region, and write that to disk. This will ensure chunks are
     /// compactly stored with no gaps.
-    pub fn remove_chunk(&mut self, x: usize, z: usize) -> Result<()> {
+    pub fn remove_chunk(&mut self, x: usize, z: usize) -> Result<bool> {
         let Some(loc) = self.location(x, z)? else {
-            return Ok(());
+            return Ok(false);
         };
 
-        // zero the region header for the chunk
+        // zero the region header and timestamp for the chunk
         self.set_header(x, z, 0, 0)?;
+        self.set_chunk_timestamp(x, z, 0)?;
 
         // remove the offset of the chunk
         let i = self.offsets.binary_search(&loc.offset).unwrap();
         self.offsets.remove(i);
 
-        Ok(())
+        Ok(true)
     }
 
     /// Write the chunk data to the given offset, does no checking.
@@ -445,15 +851,30 @@ where
         self.stream.write_all(&buf)?;
         Ok(())
     }
+
+    /// Set the last-modified timestamp of the chunk at `x`, `z`, in seconds
+    /// since the unix epoch. Writing a chunk already sets this to the current
+    /// time, so this is only needed to set a specific time. If `x` or `z` are
+    /// outside `0..32`, [`Error::InvalidOffset`] is returned.
+    pub fn set_chunk_timestamp(&mut self, x: usize, z: usize, timestamp: u32) -> Result<()> {
+        if x >= 32 || z >= 32 {
+            return Err(Error::InvalidOffset(x as isize, z as isize));
+        }
+
+        self.stream.seek(SeekFrom::Start(timestamp_pos(x, z)))?;
+        self.stream.write_u32::<BigEndian>(timestamp)?;
+        Ok(())
+    }
 }
 
 /// Various compression schemes that NBT data is typically compressed with.
-#[derive(Debug, TryFromPrimitive)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, TryFromPrimitive)]
 #[repr(u8)]
 pub enum CompressionScheme {
     Gzip = 1,
     Zlib = 2,
     Uncompressed = 3,
+    /// LZ4 in the lz4-java block format. Available since Minecraft 1.20.5.
     Lz4 = 4,
 }
 
@@ -537,37 +958,101 @@ fn unstable_stream_len(seek: &mut impl Seek) -> Result<u64> {
     Ok(len)
 }
 
+/// Parse a location table entry, None if there is no chunk.
+fn parse_location(buf: [u8; 4]) -> Option<ChunkLocation> {
+    let offset = u32::from_be_bytes([0, buf[0], buf[1], buf[2]]) as u64;
+    let sectors = buf[3] as u64;
+
+    (offset != 0 || sectors != 0).then_some(ChunkLocation { offset, sectors })
+}
+
 fn header_pos(x: usize, z: usize) -> u64 {
     (4 * ((x % 32) + (z % 32) * 32)) as u64
 }
 
-#[derive(Debug)]
+fn timestamp_pos(x: usize, z: usize) -> u64 {
+    SECTOR_SIZE as u64 + header_pos(x, z)
+}
+
+/// Seconds since the unix epoch, as stored in the region timestamp table.
+fn now() -> u32 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as u32)
+        .unwrap_or(0)
+}
+
+/// A problem with a chunk's entry in the region header, found by
+/// [`Region::validate`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RegionProblem {
+    /// The chunk has an offset but occupies no sectors.
+    ZeroLength { x: usize, z: usize },
+
+    /// The chunk starts within the region header.
+    InHeader { x: usize, z: usize },
+
+    /// The chunk's sectors extend past the end of the stream.
+    BeyondEnd { x: usize, z: usize },
+
+    /// The chunk's sectors overlap those of the chunk at `other`.
+    Overlap {
+        x: usize,
+        z: usize,
+        other: (usize, usize),
+    },
+}
+
+impl RegionProblem {
+    /// The coordinates of the chunk with the problem.
+    pub fn chunk(&self) -> (usize, usize) {
+        match *self {
+            RegionProblem::ZeroLength { x, z }
+            | RegionProblem::InHeader { x, z }
+            | RegionProblem::BeyondEnd { x, z }
+            | RegionProblem::Overlap { x, z, .. } => (x, z),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct ChunkLocation {
     /// The offset, in units of 4kiB sectors, into the region file this chunk is
-    /// located at. Offset 0 is the start of the file.
+    /// located at. Offset 0 is the start of the file. Multiply by
+    /// [`SECTOR_SIZE`] for the offset in bytes.
     pub offset: u64,
 
     /// The number of 4 kiB sectors that this chunk occupies in the region file.
     pub sectors: u64,
 }
 
+/// Bit set in the compression scheme byte when the chunk data is stored in a
+/// separate `.mcc` file rather than in the region.
+const EXTERNAL_CHUNK_FLAG: u8 = 0x80;
+
 /// Encodes how the NBT-Data is compressed
 #[derive(Debug)]
 struct ChunkMeta {
+    /// Length as stored, including the compression scheme byte.
+    pub len: u32,
     pub compressed_len: u32,
     pub compression_scheme: CompressionScheme,
+    pub external: bool,
 }
 
 impl ChunkMeta {
     fn new(mut data: &[u8]) -> Result<Self> {
         let len = data.read_u32::<BigEndian>()?;
         let scheme = data.read_u8()?;
-        let scheme =
-            CompressionScheme::try_from(scheme).map_err(|_| Error::UnknownCompression(scheme))?;
+        let external = scheme & EXTERNAL_CHUNK_FLAG != 0;
+        let scheme = CompressionScheme::try_from(scheme & !EXTERNAL_CHUNK_FLAG)
+            .map_err(|_| Error::UnknownCompression(scheme))?;
 
         Ok(Self {
-            compressed_len: len - 1, // this len include the compression byte.
+            len,
+            compressed_len: len.saturating_sub(1), // this len include the compression byte.
             compression_scheme: scheme,
+            external,
         })
     }
 }
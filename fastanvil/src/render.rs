@@ -4,8 +4,8 @@ use std::{
 };
 
 use crate::{
-    Block, BlockArchetype, CCoord, Chunk, HeightMode, JavaChunk, LoaderError, LoaderResult, RCoord,
-    RegionLoader,
+    Block, BlockArchetype, CCoord, Chunk, ChunkStatus, HeightMode, JavaChunk, LoaderError,
+    LoaderResult, RCoord, RegionLoader,
 };
 
 use super::biome::Biome;
@@ -16,11 +16,54 @@ pub type Rgba = [u8; 4];
 /// should render to.
 pub trait Palette {
     fn pick(&self, block: &Block, biome: Option<Biome>) -> Rgba;
+
+    /// Pick a colour knowing where the block is in its column, for example to
+    /// shade water by how deep it is. Renderers call this rather than
+    /// [`pick`][`Palette::pick`]. By default this ignores the context.
+    fn pick_with_context(
+        &self,
+        block: &Block,
+        biome: Option<Biome>,
+        _column: &ColumnContext,
+    ) -> Rgba {
+        self.pick(block, biome)
+    }
+}
+
+/// Where a block being rendered is within its column.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ColumnContext {
+    /// The y of the block.
+    pub y: isize,
+
+    /// The y of the first block below that is not water, ie the floor that
+    /// water sits on. For other blocks this is the block directly below.
+    pub below_y: isize,
+
+    /// How many blocks of water deep the column is from this block, if the
+    /// block is water.
+    pub water_depth: Option<isize>,
+}
+
+/// Options for the height shading done by [`TopShadeRenderer`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ShadeOpts {
+    /// How strongly to shade blocks lower than their northern neighbour.
+    /// `0.0` draws flat colours, `1.0` is similar to Minecraft's own maps.
+    /// Larger values exaggerate the shading.
+    pub strength: f32,
+}
+
+impl Default for ShadeOpts {
+    fn default() -> Self {
+        Self { strength: 1.0 }
+    }
 }
 
 pub struct TopShadeRenderer<'a, P: Palette> {
     palette: &'a P,
     height_mode: HeightMode,
+    shade: ShadeOpts,
 }
 
 impl<'a, P: Palette> TopShadeRenderer<'a, P> {
@@ -28,15 +71,20 @@ impl<'a, P: Palette> TopShadeRenderer<'a, P> {
         Self {
             palette,
             height_mode: mode,
+            shade: ShadeOpts::default(),
         }
     }
 
+    /// Use the given options for height shading rather than the default.
+    pub fn with_shade(mut self, shade: ShadeOpts) -> Self {
+        self.shade = shade;
+        self
+    }
+
     pub fn render<C: Chunk + ?Sized>(&self, chunk: &C, north: Option<&C>) -> [Rgba; 16 * 16] {
         let mut data = [[0, 0, 0, 0]; 16 * 16];
 
-        let status = chunk.status();
-        const OK_STATUSES: [&str; 8] = ["full", "spawn", "postprocessed", "fullchunk", "minecraft:full", "minecraft:spawn", "minecraft:postprocessed", "minecraft:fullchunk"];
-        if !OK_STATUSES.contains(&status.as_str()) {
+        if !matches!(chunk.chunk_status(), ChunkStatus::Full | ChunkStatus::Spawn) {
             // Chunks that have been fully generated will have a 'full' status.
             // Skip chunks that don't; the way they render is unpredictable.
             return data;
@@ -58,7 +106,7 @@ impl<'a, P: Palette> TopShadeRenderer<'a, P> {
                         .unwrap_or(block_height),
                     z => chunk.surface_height(x, z - 1, self.height_mode),
                 };
-                let colour = top_shade_colour(colour, air_height, north_air_height);
+                let colour = top_shade_colour(colour, air_height, north_air_height, self.shade);
 
                 data[z * 16 + x] = colour;
             }
@@ -92,8 +140,16 @@ impl<'a, P: Palette> TopShadeRenderer<'a, P> {
                     // TODO: Can potentially optimize this for ocean floor using
                     // heightmaps.
                     BlockArchetype::Watery => {
-                        let mut block_colour = self.palette.pick(current_block, current_biome);
                         let water_depth = water_depth(x, y, z, chunk, y_min);
+                        // water_depth counts one more than the water blocks.
+                        let context = ColumnContext {
+                            y,
+                            below_y: y - (water_depth - 1),
+                            water_depth: Some(water_depth - 1),
+                        };
+                        let mut block_colour =
+                            self.palette
+                                .pick_with_context(current_block, current_biome, &context);
                         let alpha = water_depth_to_alpha(water_depth);
 
                         block_colour[3] = alpha;
@@ -102,7 +158,14 @@ impl<'a, P: Palette> TopShadeRenderer<'a, P> {
                         y -= water_depth;
                     }
                     _ => {
-                        let block_colour = self.palette.pick(current_block, current_biome);
+                        let context = ColumnContext {
+                            y,
+                            below_y: y - 1,
+                            water_depth: None,
+                        };
+                        let block_colour =
+                            self.palette
+                                .pick_with_context(current_block, current_biome, &context);
                         colour = a_over_b_colour(colour, block_colour);
                         y -= 1;
                     }
@@ -187,6 +250,23 @@ fn a_over_b_colour(colour: [u8; 4], below_colour: [u8; 4]) -> [u8; 4] {
     ]
 }
 
+/// Render a single chunk top-down, one colour per column, without any height
+/// shading. Each column is the colour of the blocks seen looking down from
+/// the chunk's surface height, blending through transparent blocks and water.
+/// Chunks that are not fully generated render fully transparent.
+///
+/// Indexed by `z * 16 + x`. Use [`TopShadeRenderer`] for shading based on the
+/// height of neighbouring blocks.
+pub fn render_chunk<C, P>(chunk: &C, palette: &P) -> [Rgba; 16 * 16]
+where
+    C: Chunk + ?Sized,
+    P: Palette,
+{
+    TopShadeRenderer::new(palette, HeightMode::Trust)
+        .with_shade(ShadeOpts { strength: 0.0 })
+        .render(chunk, None)
+}
+
 pub struct RegionMap<T> {
     pub data: Vec<T>,
     pub x: RCoord,
@@ -235,15 +315,18 @@ where
 
     let mut cache: [Option<JavaChunk>; 32] = Default::default();
 
+    // Decompress every chunk into the same buffer rather than allocating one
+    // per chunk.
+    let mut chunk_data = Vec::new();
+
     // Cache the last row of chunks from the above region to allow top-shading
     // on region boundaries.
     if let Some(mut r) = loader.region(x, RCoord(z.0 - 1))? {
         for (x, entry) in cache.iter_mut().enumerate() {
-            *entry = r
-                .read_chunk(x, 31)
-                .ok()
-                .flatten()
-                .and_then(|b| JavaChunk::from_bytes(&b).ok())
+            *entry = match r.read_chunk_into(x, 31, &mut chunk_data) {
+                Ok(true) => JavaChunk::from_bytes(&chunk_data).ok(),
+                _ => None,
+            }
         }
     }
 
@@ -251,19 +334,16 @@ where
         for (x, cache) in cache.iter_mut().enumerate() {
             let data = map.chunk_mut(CCoord(x as isize), CCoord(z as isize));
 
-            let chunk_data = region
-                .read_chunk(x, z)
+            let found = region
+                .read_chunk_into(x, z, &mut chunk_data)
                 .map_err(|e| LoaderError(e.to_string()))?;
-            let chunk_data = match chunk_data {
-                Some(data) => data,
-                None => {
-                    // If there's no chunk here, we still need to set the cache
-                    // otherwise the chunks below this will top-shade with an
-                    // incorrect chunk.
-                    *cache = None;
-                    continue;
-                }
-            };
+            if !found {
+                // If there's no chunk here, we still need to set the cache
+                // otherwise the chunks below this will top-shade with an
+                // incorrect chunk.
+                *cache = None;
+                continue;
+            }
 
             let chunk =
                 JavaChunk::from_bytes(&chunk_data).map_err(|e| LoaderError(e.to_string()))?;
@@ -293,13 +373,20 @@ where
 /// smaller.
 ///
 /// Technically this function darkens colours, but this is also how Minecraft
-/// itself shades maps.
-fn top_shade_colour(colour: Rgba, height: isize, shade_height: isize) -> Rgba {
+/// itself shades maps. The amount of darkening is scaled by the `strength` of
+/// `opts`.
+pub(crate) fn top_shade_colour(
+    colour: Rgba,
+    height: isize,
+    shade_height: isize,
+    opts: ShadeOpts,
+) -> Rgba {
     let shade = match height.cmp(&shade_height) {
-        Ordering::Less => 180usize,
-        Ordering::Equal => 220,
-        Ordering::Greater => 255,
+        Ordering::Less => 180.,
+        Ordering::Equal => 220.,
+        Ordering::Greater => 255.,
     };
+    let shade = (255. - (255. - shade) * opts.strength).clamp(0., 255.) as usize;
     [
         (colour[0] as usize * shade / 255) as u8,
         (colour[1] as usize * shade / 255) as u8,
@@ -6,40 +6,208 @@ use std::{
 
 use flate2::bufread::GzDecoder;
 use log::debug;
+use serde::{Deserialize, Serialize};
 
 use crate::{biome::Biome, Block, Palette, Rgba, SNOW_BLOCK};
 
 pub struct RenderedPalette {
     pub blockstates: std::collections::HashMap<String, Rgba>,
+    /// Blockstates whose colour is multiplied by a biome colourmap. Blocks
+    /// missing from here are not tinted.
+    pub tints: std::collections::HashMap<String, Tint>,
     pub grass: image::RgbaImage,
     pub foliage: image::RgbaImage,
+    /// The format version of the palette, see [`RenderedPalette::VERSION`].
+    pub version: u32,
+}
+
+/// The biome colour a block is tinted by.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum Tint {
+    #[default]
+    None,
+    Grass,
+    Foliage,
+    Water,
+}
+
+/// An entry in the `blockstates.json` of a rendered palette.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PaletteEntry {
+    pub colour: Rgba,
+    #[serde(default)]
+    pub tint: Tint,
+}
+
+/// Multiply a colour by a colourmap sample.
+fn multiply(colour: Rgba, tint: Rgba) -> Rgba {
+    let m = |c: u8, t: u8| ((c as u16 * t as u16) / 255) as u8;
+    [
+        m(colour[0], tint[0]),
+        m(colour[1], tint[1]),
+        m(colour[2], tint[2]),
+        colour[3],
+    ]
+}
+
+/// Sample a grass or foliage colourmap by the temperature and rainfall of the
+/// biome.
+fn sample_colourmap(map: &image::RgbaImage, b: Biome) -> Rgba {
+    let climate = b.climate();
+    let t = climate.temperature.clamp(0., 1.);
+    let r = climate.rainfall.clamp(0., 1.) * t;
+
+    let t = 255 - (t * 255.).ceil() as u32;
+    let r = 255 - (r * 255.).ceil() as u32;
+
+    map.get_pixel(t, r).0
+}
+
+fn is_badlands(b: Biome) -> bool {
+    use Biome::*;
+    matches!(
+        b,
+        Badlands
+            | BadlandsPlateau
+            | ModifiedBadlandsPlateau
+            | WoodedBadlandsPlateau
+            | ModifiedWoodedBadlandsPlateau
+            | ErodedBadlands
+            | WoodedBadlands
+    )
 }
 
 impl RenderedPalette {
-    fn pick_grass(&self, b: Option<Biome>) -> Rgba {
-        b.map(|b| {
-            let climate = b.climate();
-            let t = climate.temperature.clamp(0., 1.);
-            let r = climate.rainfall.clamp(0., 1.) * t;
+    /// The palette format this crate reads. Version 2 added biome tints to
+    /// the blockstates. Palettes without a `version` file are version 1.
+    pub const VERSION: u32 = 2;
+
+    /// Load a rendered palette from a gzipped tar archive made by the
+    /// `anvil-palette` tool. Any reader works, including a byte slice.
+    ///
+    /// Fails if the palette is not [`RenderedPalette::VERSION`]. Older palettes
+    /// lack the information to render correctly, and need to be made again
+    /// with the `anvil-palette` tool. This includes the palette.tar.gz
+    /// alongside the fastnbt project repository, which is still version 1.
+    pub fn from_reader(palette: impl Read) -> std::result::Result<Self, PaletteError> {
+        let f = GzDecoder::new(BufReader::new(palette));
+        let mut ar = tar::Archive::new(f);
+        let mut version = 1;
+        let mut grass = Err(PaletteError("no grass colour map".to_owned()));
+        let mut foliage = Err(PaletteError("no foliage colour map".to_owned()));
+        let mut blockstates = Err(PaletteError("no blockstate palette".to_owned()));
+
+        for file in ar.entries().map_err(PaletteError::new)? {
+            let mut file = file.map_err(PaletteError::new)?;
+            match file
+                .path()
+                .map_err(PaletteError::new)?
+                .to_str()
+                .ok_or(PaletteError("invalid path".to_owned()))?
+            {
+                "version" => {
+                    let mut buf = String::new();
+                    file.read_to_string(&mut buf).map_err(PaletteError::new)?;
+
+                    version = buf.trim().parse().map_err(|_| {
+                        PaletteError(format!("invalid palette version: {:?}", buf.trim()))
+                    })?;
+                }
+                "grass-colourmap.png" => {
+                    let mut buf = vec![];
+                    file.read_to_end(&mut buf).map_err(PaletteError::new)?;
+
+                    grass = Ok(
+                        image::load(std::io::Cursor::new(buf), image::ImageFormat::Png)
+                            .map_err(PaletteError::new)?
+                            .into_rgba8(),
+                    );
+                }
+                "foliage-colourmap.png" => {
+                    let mut buf = vec![];
+                    file.read_to_end(&mut buf).map_err(PaletteError::new)?;
+
+                    foliage = Ok(
+                        image::load(std::io::Cursor::new(buf), image::ImageFormat::Png)
+                            .map_err(PaletteError::new)?
+                            .into_rgba8(),
+                    );
+                }
+                "blockstates.json" => {
+                    let mut buf = vec![];
+                    file.read_to_end(&mut buf).map_err(PaletteError::new)?;
+                    blockstates = Ok(buf);
+                }
+                _ => {}
+            }
+        }
+
+        // Check the version before parsing the blockstates, as older palettes
+        // fail to parse in confusing ways.
+        if version != Self::VERSION {
+            let age = if version < Self::VERSION {
+                "older"
+            } else {
+                "newer"
+            };
+            return Err(PaletteError(format!(
+                "palette version {} is {} than the supported version {}",
+                version,
+                age,
+                Self::VERSION
+            )));
+        }
 
-            let t = 255 - (t * 255.).ceil() as u32;
-            let r = 255 - (r * 255.).ceil() as u32;
+        let entries: std::collections::HashMap<String, PaletteEntry> =
+            serde_json::from_slice(&blockstates?).map_err(PaletteError::new)?;
+        let tints = entries
+            .iter()
+            .filter(|(_, entry)| entry.tint != Tint::None)
+            .map(|(k, entry)| (k.clone(), entry.tint))
+            .collect();
 
-            self.grass.get_pixel(t, r).0
+        Ok(RenderedPalette {
+            blockstates: entries
+                .into_iter()
+                .map(|(k, entry)| (k, entry.colour))
+                .collect(),
+            tints,
+            grass: grass?,
+            foliage: foliage?,
+            version,
         })
-        .unwrap_or([255, 0, 0, 0])
     }
 
-    fn pick_foliage(&self, b: Option<Biome>) -> Rgba {
-        b.map(|b| {
-            let climate = b.climate();
-            let t = climate.temperature.clamp(0., 1.);
-            let r = climate.rainfall.clamp(0., 1.) * t;
+    /// The format version of the palette that was loaded.
+    pub fn palette_version(&self) -> u32 {
+        self.version
+    }
 
-            let t = 255 - (t * 255.).ceil() as u32;
-            let r = 255 - (r * 255.).ceil() as u32;
+    fn pick_grass(&self, b: Option<Biome>) -> Rgba {
+        use Biome::*;
+        b.map(|b| match b {
+            // Some biomes override or modify the colourmap, values are from
+            // the game.
+            Swamp | SwampHills | MangroveSwamp => [0x6a, 0x70, 0x39, 255],
+            b if is_badlands(b) => [0x90, 0x81, 0x4d, 255],
+            DarkForest | DarkForestHills => {
+                let [r, g, bl, a] = sample_colourmap(&self.grass, b);
+                let modify = |c: u8, m: u8| (((c & 0xfe) as u16 + m as u16) >> 1) as u8;
+                [modify(r, 0x28), modify(g, 0x34), modify(bl, 0x0a), a]
+            }
+            b => sample_colourmap(&self.grass, b),
+        })
+        .unwrap_or([255, 0, 0, 0])
+    }
 
-            self.foliage.get_pixel(t, r).0
+    fn pick_foliage(&self, b: Option<Biome>) -> Rgba {
+        use Biome::*;
+        b.map(|b| match b {
+            Swamp | SwampHills => [0x6a, 0x70, 0x39, 255],
+            MangroveSwamp => [0x8d, 0xb1, 0x27, 255],
+            b if is_badlands(b) => [0x9e, 0x81, 0x4d, 255],
+            b => sample_colourmap(&self.foliage, b),
         })
         .unwrap_or([255, 0, 0, 0])
     }
@@ -116,13 +284,20 @@ impl Palette for RenderedPalette {
             }
         }
 
-        let col = self
-            .blockstates
-            .get(block.encoded_description())
-            .or_else(|| self.blockstates.get(block.name()));
+        let key = [block.encoded_description(), block.name()]
+            .into_iter()
+            .find(|key| self.blockstates.contains_key(*key));
 
-        match col {
-            Some(c) => *c,
+        match key {
+            Some(key) => {
+                let col = self.blockstates[key];
+                match self.tints.get(key) {
+                    Some(Tint::Grass) => multiply(col, self.pick_grass(biome)),
+                    Some(Tint::Foliage) => multiply(col, self.pick_foliage(biome)),
+                    Some(Tint::Water) => multiply(col, self.pick_water(biome)),
+                    Some(Tint::None) | None => col,
+                }
+            }
             None => {
                 debug!("could not draw {}", block.encoded_description());
                 missing_colour
@@ -149,58 +324,10 @@ impl Display for PaletteError {
 impl Error for PaletteError {}
 
 /**
- * Load a prepared rendered palette. This is for use with the palette.tar.gz
- * alongside the fastnbt project repository.
+ * Load a prepared rendered palette, see [`RenderedPalette::from_reader`].
  */
 pub fn load_rendered_palette(
     palette: impl Read,
 ) -> std::result::Result<RenderedPalette, PaletteError> {
-    let f = GzDecoder::new(BufReader::new(palette));
-    let mut ar = tar::Archive::new(f);
-    let mut grass = Err(PaletteError("no grass colour map".to_owned()));
-    let mut foliage = Err(PaletteError("no foliage colour map".to_owned()));
-    let mut blockstates = Err(PaletteError("no blockstate palette".to_owned()));
-
-    for file in ar.entries().map_err(PaletteError::new)? {
-        let mut file = file.map_err(PaletteError::new)?;
-        match file
-            .path()
-            .map_err(PaletteError::new)?
-            .to_str()
-            .ok_or(PaletteError("invalid path".to_owned()))?
-        {
-            "grass-colourmap.png" => {
-                let mut buf = vec![];
-                file.read_to_end(&mut buf).map_err(PaletteError::new)?;
-
-                grass = Ok(
-                    image::load(std::io::Cursor::new(buf), image::ImageFormat::Png)
-                        .map_err(PaletteError::new)?
-                        .into_rgba8(),
-                );
-            }
-            "foliage-colourmap.png" => {
-                let mut buf = vec![];
-                file.read_to_end(&mut buf).map_err(PaletteError::new)?;
-
-                foliage = Ok(
-                    image::load(std::io::Cursor::new(buf), image::ImageFormat::Png)
-                        .map_err(PaletteError::new)?
-                        .into_rgba8(),
-                );
-            }
-            "blockstates.json" => {
-                let json: std::collections::HashMap<String, Rgba> =
-                    serde_json::from_reader(file).map_err(PaletteError::new)?;
-                blockstates = Ok(json);
-            }
-            _ => {}
-        }
-    }
-
-    Ok(RenderedPalette {
-        blockstates: blockstates?,
-        grass: grass?,
-        foliage: foliage?,
-    })
+    RenderedPalette::from_reader(palette)
 }
@@ -0,0 +1,106 @@
+//! Reading Sponge schematics (`.schem` files), version 2, as made by
+//! WorldEdit and similar tools.
+//!
+//! ```no_run
+//! # use fastanvil::schematic::Schematic;
+//! # use flate2::read::GzDecoder;
+//! # use std::fs::File;
+//! let file = File::open("house.schem").unwrap();
+//! let schem: Schematic = fastnbt::from_reader(GzDecoder::new(file)).unwrap();
+//!
+//! println!("{:?}", schem.block_at(0, 0, 0));
+//! ```
+
+use std::collections::HashMap;
+
+use fastnbt::{varint::decode_varints, ByteArray, IntArray, Value};
+use once_cell::sync::OnceCell;
+use serde::{Deserialize, Deserializer};
+
+/// A Sponge schematic, version 2. Blocks are stored as a palette index per
+/// block, in order of increasing x, then z, then y.
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "PascalCase")]
+pub struct Schematic {
+    pub version: i32,
+    pub data_version: Option<i32>,
+
+    /// Size along the x axis.
+    #[serde(deserialize_with = "unsigned_short")]
+    pub width: u16,
+
+    /// Size along the y axis.
+    #[serde(deserialize_with = "unsigned_short")]
+    pub height: u16,
+
+    /// Size along the z axis.
+    #[serde(deserialize_with = "unsigned_short")]
+    pub length: u16,
+
+    /// Position of the schematic relative to the player that saved it.
+    pub offset: Option<IntArray>,
+
+    pub palette_max: Option<i32>,
+
+    /// Map from block state, eg `minecraft:oak_stairs[facing=east]`, to the
+    /// id used in [`block_data`][`Schematic::block_data`].
+    pub palette: HashMap<String, i32>,
+
+    /// Palette ids of each block, each encoded as a varint.
+    pub block_data: ByteArray,
+
+    #[serde(default)]
+    pub block_entities: Vec<Value>,
+
+    #[serde(skip)]
+    blocks: OnceCell<Vec<u32>>,
+
+    #[serde(skip)]
+    names: OnceCell<HashMap<u32, String>>,
+}
+
+impl Schematic {
+    /// Get the block state at the given coordinates, relative to the
+    /// schematic's origin. None if the coordinates are outside the schematic,
+    /// or the block data or palette do not have the block.
+    pub fn block_at(&self, x: usize, y: usize, z: usize) -> Option<&str> {
+        let (width, height, length) = (
+            self.width as usize,
+            self.height as usize,
+            self.length as usize,
+        );
+        if x >= width || y >= height || z >= length {
+            return None;
+        }
+
+        let id = *self.blocks().get(x + z * width + y * width * length)?;
+        self.names().get(&id).map(String::as_str)
+    }
+
+    /// The palette id of every block, in order of increasing x, then z, then
+    /// y. If the block data ends part way through an id, the ids before it are
+    /// returned.
+    pub fn blocks(&self) -> &[u32] {
+        self.blocks.get_or_init(|| {
+            decode_varints(self.block_data.to_bytes().as_slice())
+                .map(|id| id as u32)
+                .collect()
+        })
+    }
+
+    /// Block state names by palette id. A map rather than a `Vec` as the ids
+    /// come from the file and can be arbitrarily large.
+    fn names(&self) -> &HashMap<u32, String> {
+        self.names.get_or_init(|| {
+            self.palette
+                .iter()
+                .filter_map(|(name, &id)| Some((u32::try_from(id).ok()?, name.clone())))
+                .collect()
+        })
+    }
+}
+
+/// Sizes are stored as shorts but are unsigned.
+fn unsigned_short<'de, D: Deserializer<'de>>(d: D) -> Result<u16, D::Error> {
+    Ok(i16::deserialize(d)? as u16)
+}
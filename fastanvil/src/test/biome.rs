@@ -0,0 +1,43 @@
+use std::str::FromStr;
+
+use crate::biome::Biome;
+
+#[test]
+fn name_round_trips() {
+    for biome in [
+        Biome::Plains,
+        Biome::DeepDark,
+        Biome::WindsweptSavanna,
+        Biome::CherryGrove,
+        Biome::PaleGarden,
+    ] {
+        assert_eq!(Biome::from_name(&biome.to_string()), biome);
+        assert_eq!(Biome::from_str(&biome.to_string()), Ok(biome));
+    }
+}
+
+#[test]
+fn display_is_namespaced_id() {
+    assert_eq!(Biome::MangroveSwamp.to_string(), "minecraft:mangrove_swamp");
+    assert_eq!(Biome::MangroveSwamp.name(), Some("mangrove_swamp"));
+}
+
+#[test]
+fn unknown_names() {
+    assert_eq!(Biome::from_name("minecraft:not_a_biome"), Biome::Unknown);
+    assert_eq!(Biome::from_name("plains"), Biome::Unknown);
+    assert!(Biome::from_str("minecraft:not_a_biome").is_err());
+
+    // Pre 1.18 only biomes have no name.
+    assert_eq!(Biome::WoodedHills.name(), None);
+    assert_eq!(Biome::Unknown.name(), None);
+}
+
+#[test]
+fn climate_matches_vanilla() {
+    assert_eq!(Biome::Plains.temperature(), 0.8);
+    assert_eq!(Biome::Plains.downfall(), 0.4);
+    assert_eq!(Biome::WarmOcean.temperature(), 0.5);
+    assert_eq!(Biome::Savanna.temperature(), 2.0);
+    assert_eq!(Biome::DeepDark.downfall(), 0.4);
+}
@@ -0,0 +1,139 @@
+use std::collections::HashMap;
+
+use fastnbt::{nbt, to_bytes, ByteArray};
+
+use crate::{Block, BlockArchetype, Chunk, JavaChunk};
+
+#[test]
+fn block_properties() {
+    let block = nbt!({
+        "Name": "minecraft:oak_slab",
+        "Properties": {
+            "type": "top",
+            "waterlogged": "true",
+        },
+    });
+
+    let block: Block = fastnbt::from_bytes(&to_bytes(&block).unwrap()).unwrap();
+
+    assert_eq!(block.property("type"), Some("top"));
+    assert_eq!(block.property("waterlogged"), Some("true"));
+    assert_eq!(block.property("facing"), None);
+    assert_eq!(block.properties().len(), 2);
+
+    // the encoded description leaves out waterlogging, but properties do not.
+    assert_eq!(block.encoded_description(), "minecraft:oak_slab|type=top");
+}
+
+#[test]
+fn block_without_properties() {
+    let block = nbt!({
+        "Name": "minecraft:stone",
+    });
+
+    let block: Block = fastnbt::from_bytes(&to_bytes(&block).unwrap()).unwrap();
+
+    assert!(block.properties().is_empty());
+}
+
+#[test]
+fn properties_from_encoded_description() {
+    // pre-1.13 blocks are built from their encoded description.
+    let block = Block::new(
+        "oak_slab".to_owned(),
+        "oak_slab|type=double".to_owned(),
+        BlockArchetype::Normal,
+    );
+    assert_eq!(block.property("type"), Some("double"));
+
+    let block = Block::new(
+        "minecraft:stone".to_owned(),
+        "minecraft:stone|".to_owned(),
+        BlockArchetype::Normal,
+    );
+    assert!(block.properties().is_empty());
+}
+
+#[test]
+fn blocks_as_map_keys() {
+    let block = |name: &str, props| {
+        let block = nbt!({"Name": name, "Properties": props});
+        fastnbt::from_bytes::<Block>(&to_bytes(&block).unwrap()).unwrap()
+    };
+
+    let mut colours = HashMap::new();
+    colours.insert(block("minecraft:oak_slab", nbt!({"type": "top"})), 1);
+    colours.insert(block("minecraft:oak_slab", nbt!({"type": "bottom"})), 2);
+    colours.insert(block("minecraft:oak_slab", nbt!({"type": "top"})), 3);
+
+    assert_eq!(colours.len(), 2);
+    assert_eq!(
+        colours.get(&block("minecraft:oak_slab", nbt!({"type": "top"}))),
+        Some(&3)
+    );
+
+    // waterlogging is not in the encoded description, so the blocks are the
+    // same key.
+    let waterlogged = block(
+        "minecraft:oak_slab",
+        nbt!({"type": "top", "waterlogged": "true"}),
+    );
+    assert_eq!(
+        waterlogged,
+        block("minecraft:oak_slab", nbt!({"type": "top"}))
+    );
+    assert_eq!(colours.get(&waterlogged), Some(&3));
+}
+
+#[test]
+fn register_vanilla_pre13_blocks() {
+    // every id and data value has a vanilla block, even if it is invalid. This
+    // fills the process-wide block list, so no other test may register pre13
+    // blocks with init_block.
+    crate::pre13::register_vanilla_blocks();
+
+    // (block id, data value) for x = 0..4 along the bottom row of a section.
+    let ids = [(17, 2), (17, 6), (126, 8), (3, 3)];
+    let mut blocks = vec![0; 4096];
+    let mut data = vec![0; 2048];
+    for (x, (id, value)) in ids.into_iter().enumerate() {
+        blocks[x] = id;
+        data[x / 2] |= value << (4 * (x % 2));
+    }
+
+    let chunk = nbt!({
+        "DataVersion": 1343,
+        "Level": {
+            "xPos": 0,
+            "zPos": 0,
+            "Sections": [{
+                "Y": 0_i8,
+                "Blocks": ByteArray::new(blocks),
+                "Data": ByteArray::new(data),
+            }],
+        },
+    });
+    let chunk = JavaChunk::from_bytes(&to_bytes(&chunk).unwrap()).unwrap();
+
+    let birch = chunk.block(0, 0, 0).unwrap();
+    assert_eq!(birch.name(), "minecraft:birch_log");
+    assert_eq!(birch.property("axis"), Some("y"));
+
+    let sideways = chunk.block(1, 0, 0).unwrap();
+    assert_eq!(sideways.name(), "minecraft:birch_log");
+    assert_eq!(sideways.property("axis"), Some("x"));
+
+    let slab = chunk.block(2, 0, 0).unwrap();
+    assert_eq!(slab.name(), "minecraft:oak_slab");
+    assert_eq!(slab.property("type"), Some("top"));
+
+    // dirt with an unused data value still maps to a block.
+    assert!(chunk.block(3, 0, 0).is_some());
+
+    let custom = Block::new(
+        "custom:log".to_owned(),
+        "custom:log|".to_owned(),
+        BlockArchetype::Normal,
+    );
+    assert!(crate::pre13::init_block(17, 2, custom).is_err());
+}
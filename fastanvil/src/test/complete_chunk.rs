@@ -1,4 +1,6 @@
-use crate::{complete, Chunk, HeightMode, JavaChunk, Region};
+use fastnbt::{nbt, to_bytes};
+
+use crate::{biome::Biome, complete, Chunk, HeightMode, JavaChunk, Region};
 
 fn get_test_chunk() -> Vec<(JavaChunk, complete::Chunk)> {
     //todo better test region (different bioms)
@@ -16,8 +18,7 @@ fn get_test_chunk() -> Vec<(JavaChunk, complete::Chunk)> {
     let complete_chunk_pre18 =
         complete::Chunk::from_bytes(include_bytes!("../../resources/1.17.1.chunk")).unwrap();
 
-    let chunk_pre13 =
-        JavaChunk::from_bytes(include_bytes!("../../resources/1.12.chunk")).unwrap();
+    let chunk_pre13 = JavaChunk::from_bytes(include_bytes!("../../resources/1.12.chunk")).unwrap();
 
     let complete_chunk_pre13 =
         complete::Chunk::from_bytes(include_bytes!("../../resources/1.12.chunk")).unwrap();
@@ -116,3 +117,57 @@ fn surface_height_returns_same_as_current_java_chunk() {
         }
     }
 }
+
+#[test]
+fn single_entry_palette_is_whole_section() {
+    // The first section leaves out data as the game does, the second has
+    // data that is nonsense for a single entry palette and should be ignored.
+    let chunk = nbt!({
+        "DataVersion": 3953,
+        "Status": "minecraft:full",
+        "sections": [
+            {
+                "Y": -4_i8,
+                "block_states": {
+                    "palette": [{"Name": "minecraft:deepslate"}],
+                },
+                "biomes": {
+                    "palette": ["minecraft:plains"],
+                },
+            },
+            {
+                "Y": -3_i8,
+                "block_states": {
+                    "palette": [{"Name": "minecraft:stone"}],
+                    "data": [L; -1_i64],
+                },
+                "biomes": {
+                    "palette": ["minecraft:desert"],
+                    "data": [L; -1_i64],
+                },
+            },
+        ],
+    });
+    let data = to_bytes(&chunk).unwrap();
+
+    let java_chunk = JavaChunk::from_bytes(&data).unwrap();
+    let complete_chunk = complete::Chunk::from_bytes(&data).unwrap();
+
+    for x in 0..16 {
+        for z in 0..16 {
+            for y in -64..-32 {
+                let expected = match y {
+                    -64..=-49 => "minecraft:deepslate",
+                    _ => "minecraft:stone",
+                };
+                assert_eq!(complete_chunk.block(x, y, z).unwrap().name(), expected);
+                assert_eq!(java_chunk.block(x, y, z).unwrap().name(), expected);
+            }
+        }
+    }
+
+    assert_eq!(complete_chunk.biome(0, -64, 0), Some(Biome::Plains));
+    assert_eq!(complete_chunk.biome(15, -33, 15), Some(Biome::Desert));
+    assert_eq!(java_chunk.biome(15, -33, 15), Some(Biome::Desert));
+    assert_eq!(complete_chunk.iter_blocks().count(), 2 * 16 * 16 * 16);
+}
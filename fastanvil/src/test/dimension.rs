@@ -0,0 +1,162 @@
+use std::{
+    collections::HashMap,
+    io::Cursor,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use fastnbt::{nbt, to_bytes};
+
+use crate::{
+    CCoord, Chunk, ChunkStatus, Dimension, JavaChunk, LoaderResult, RCoord, Region, RegionBounds,
+    RegionLoader,
+};
+
+/// Loader serving regions from memory, counting how often regions are loaded.
+struct MemoryLoader {
+    regions: HashMap<(isize, isize), Vec<u8>>,
+    loads: AtomicUsize,
+}
+
+impl RegionLoader<Cursor<Vec<u8>>> for MemoryLoader {
+    fn region(&self, x: RCoord, z: RCoord) -> LoaderResult<Option<Region<Cursor<Vec<u8>>>>> {
+        self.loads.fetch_add(1, Ordering::Relaxed);
+        Ok(self
+            .regions
+            .get(&(x.0, z.0))
+            .map(|data| Region::from_stream(Cursor::new(data.clone())).unwrap()))
+    }
+
+    fn list(&self) -> LoaderResult<Vec<(RCoord, RCoord)>> {
+        Ok(self
+            .regions
+            .keys()
+            .map(|(x, z)| (RCoord(*x), RCoord(*z)))
+            .collect())
+    }
+}
+
+fn region_with_chunk(x: usize, z: usize, status: &str) -> Vec<u8> {
+    let chunk = nbt!({
+        "DataVersion": 3465,
+        "Status": status,
+    });
+    let mut region = Region::create(Cursor::new(vec![])).unwrap();
+    region
+        .write_chunk(x, z, &to_bytes(&chunk).unwrap())
+        .unwrap();
+    region.into_inner().unwrap().into_inner()
+}
+
+fn loader() -> MemoryLoader {
+    MemoryLoader {
+        regions: HashMap::from([
+            ((0, 0), region_with_chunk(3, 4, "minecraft:full")),
+            ((-1, 2), region_with_chunk(31, 0, "minecraft:features")),
+        ]),
+        loads: AtomicUsize::new(0),
+    }
+}
+
+fn status(chunk: Option<JavaChunk>) -> Option<String> {
+    chunk.map(|c| c.status())
+}
+
+#[test]
+fn chunk_by_chunk_coords() {
+    let mut dim = Dimension::new(loader());
+
+    assert_eq!(
+        status(dim.chunk(CCoord(3), CCoord(4)).unwrap()).as_deref(),
+        Some("minecraft:full")
+    );
+    assert_eq!(
+        status(dim.chunk(CCoord(-1), CCoord(64)).unwrap()).as_deref(),
+        Some("minecraft:features")
+    );
+
+    // chunk missing from a present region, and a missing region.
+    assert!(dim.chunk(CCoord(0), CCoord(0)).unwrap().is_none());
+    assert!(dim.chunk(CCoord(100), CCoord(0)).unwrap().is_none());
+}
+
+#[test]
+fn regions_are_loaded_once() {
+    let mut dim = Dimension::new(loader());
+
+    for _ in 0..3 {
+        dim.chunk(CCoord(3), CCoord(4)).unwrap();
+        dim.chunk(CCoord(100), CCoord(0)).unwrap();
+    }
+
+    assert_eq!(dim.into_loader().loads.into_inner(), 2);
+}
+
+#[test]
+fn bounds_cover_all_regions() {
+    let dim = Dimension::new(loader());
+    assert_eq!(
+        dim.bounds().unwrap(),
+        Some(RegionBounds {
+            x_min: RCoord(-1),
+            x_max: RCoord(0),
+            z_min: RCoord(0),
+            z_max: RCoord(2),
+        })
+    );
+
+    let empty = MemoryLoader {
+        regions: HashMap::new(),
+        loads: AtomicUsize::new(0),
+    };
+    assert_eq!(Dimension::new(empty).bounds().unwrap(), None);
+}
+
+#[test]
+fn chunk_status_is_typed() {
+    let mut dim = Dimension::new(loader());
+
+    let full = dim.chunk(CCoord(3), CCoord(4)).unwrap().unwrap();
+    assert_eq!(full.chunk_status(), ChunkStatus::Full);
+
+    let partial = dim.chunk(CCoord(-1), CCoord(64)).unwrap().unwrap();
+    assert_eq!(partial.chunk_status(), ChunkStatus::Features);
+}
+
+#[test]
+fn chunk_status_ignores_namespace() {
+    assert_eq!(ChunkStatus::from("full"), ChunkStatus::Full);
+    assert_eq!(ChunkStatus::from("minecraft:full"), ChunkStatus::Full);
+    assert_eq!(
+        ChunkStatus::from("minecraft:structure_starts"),
+        ChunkStatus::StructureStarts
+    );
+
+    // unknown statuses are kept as they were.
+    assert_eq!(
+        ChunkStatus::from("minecraft:something_new"),
+        ChunkStatus::Other("minecraft:something_new".to_owned())
+    );
+}
+
+#[cfg(feature = "rayon")]
+#[test]
+fn par_for_each_region_visits_every_region() {
+    use std::sync::Mutex;
+
+    let dim = Dimension::new(loader());
+    let seen = Mutex::new(vec![]);
+
+    dim.par_for_each_region(|(x, z), region| {
+        let chunks: Vec<_> = region
+            .iter()
+            .map(|c| c.unwrap())
+            .map(|c| (c.x, c.z))
+            .collect();
+        seen.lock().unwrap().push((x.0, z.0, chunks));
+    })
+    .unwrap();
+
+    let mut seen = seen.into_inner().unwrap();
+    seen.sort();
+    assert_eq!(seen, vec![(-1, 2, vec![(31, 0)]), (0, 0, vec![(3, 4)])]);
+}
@@ -0,0 +1,729 @@
+use fastnbt::{nbt, to_bytes, ByteArray, Value};
+
+use crate::{
+    biome::Biome, pre18::Pre18Section, Block, BlockData, BlockEntity, Chunk, CurrentJavaChunk,
+    HeightmapKind, JavaChunk, LosslessJavaChunk, Region, SectionTower,
+};
+
+#[test]
+fn block_entities() {
+    let chunk = nbt!({
+        "DataVersion": 3465,
+        "Status": "minecraft:full",
+        "block_entities": [
+            {
+                "id": "minecraft:chest",
+                "x": -10,
+                "y": 64,
+                "z": 35,
+                "keepPacked": false,
+                "Items": [],
+            },
+        ],
+    });
+
+    let chunk: CurrentJavaChunk = fastnbt::from_bytes(&to_bytes(&chunk).unwrap()).unwrap();
+    let entities = chunk.block_entities();
+
+    assert_eq!(
+        entities,
+        &[BlockEntity {
+            id: "minecraft:chest".to_owned(),
+            x: -10,
+            y: 64,
+            z: 35,
+            extra: nbt!({
+                "keepPacked": false,
+                "Items": [],
+            }),
+        }]
+    );
+}
+
+#[test]
+fn missing_block_entities_is_empty() {
+    let chunk = nbt!({
+        "DataVersion": 3465,
+        "Status": "minecraft:full",
+    });
+
+    let chunk: CurrentJavaChunk = fastnbt::from_bytes(&to_bytes(&chunk).unwrap()).unwrap();
+    assert!(chunk.block_entities().is_empty());
+}
+
+#[test]
+fn biome_uses_y_within_section() {
+    // Single bit per biome, with the top half of the section set to desert.
+    let chunk = nbt!({
+        "DataVersion": 3465,
+        "Status": "minecraft:full",
+        "sections": [
+            {
+                "Y": 0_i8,
+                "biomes": {
+                    "palette": ["minecraft:plains", "minecraft:desert"],
+                    "data": [L; -4294967296_i64],
+                },
+            },
+            {
+                "Y": 1_i8,
+                "biomes": {
+                    "palette": ["minecraft:lush_caves"],
+                },
+            },
+        ],
+    });
+
+    let chunk: CurrentJavaChunk = fastnbt::from_bytes(&to_bytes(&chunk).unwrap()).unwrap();
+
+    assert_eq!(chunk.biome(3, 0, 15), Some(Biome::Plains));
+    assert_eq!(chunk.biome(3, 7, 15), Some(Biome::Plains));
+    assert_eq!(chunk.biome(3, 8, 15), Some(Biome::Desert));
+    assert_eq!(chunk.biome(3, 15, 15), Some(Biome::Desert));
+    assert_eq!(chunk.biome(3, 16, 15), Some(Biome::LushCaves));
+
+    let biomes = chunk.biomes_in_section(0).unwrap();
+    assert_eq!(biomes.len(), 64);
+    assert!(biomes[..32].iter().all(|b| *b == Biome::Plains));
+    assert!(biomes[32..].iter().all(|b| *b == Biome::Desert));
+
+    let biomes = chunk.biomes_in_section(1).unwrap();
+    assert!(biomes.iter().all(|b| *b == Biome::LushCaves));
+
+    assert_eq!(chunk.biomes_in_section(2), None);
+}
+
+#[test]
+fn block_and_sky_light() {
+    // index of a block is y, z, x order, two blocks per byte with the first
+    // in the low nibble.
+    let mut block_light = vec![0i8; 2048];
+    block_light[0] = 0x2f; // (0,0,0) = 15, (1,0,0) = 2
+    let idx = (3 << 8) + (4 << 4) + 5; // (5,3,4)
+    block_light[idx / 2] = 0x70;
+
+    let chunk = nbt!({
+        "DataVersion": 3465,
+        "Status": "minecraft:full",
+        "sections": [
+            {
+                "Y": 0_i8,
+                "BlockLight": ByteArray::new(block_light),
+                "SkyLight": ByteArray::new(vec![-1; 2048]),
+            },
+            {
+                "Y": 1_i8,
+            },
+        ],
+    });
+
+    let chunk: CurrentJavaChunk = fastnbt::from_bytes(&to_bytes(&chunk).unwrap()).unwrap();
+
+    assert_eq!(chunk.block_light(0, 0, 0), Some(15));
+    assert_eq!(chunk.block_light(1, 0, 0), Some(2));
+    assert_eq!(chunk.block_light(5, 3, 4), Some(7));
+    assert_eq!(chunk.block_light(4, 3, 4), Some(0));
+    assert_eq!(chunk.sky_light(15, 15, 15), Some(15));
+
+    // section without light, and no section at all.
+    assert_eq!(chunk.block_light(0, 16, 0), None);
+    assert_eq!(chunk.sky_light(0, 16, 0), None);
+    assert_eq!(chunk.block_light(0, 100, 0), None);
+}
+
+#[test]
+fn single_palette_sections_without_data() {
+    // Since 1.18 sections whose palette has a single entry leave out the data
+    // array entirely. 1.21 chunks are full of these.
+    let chunk = nbt!({
+        "DataVersion": 3953,
+        "Status": "minecraft:full",
+        "xPos": 0,
+        "zPos": 0,
+        "yPos": -4,
+        "sections": [
+            {
+                "Y": -4_i8,
+                "block_states": {
+                    "palette": [{"Name": "minecraft:deepslate"}],
+                },
+                "biomes": {
+                    "palette": ["minecraft:plains"],
+                },
+            },
+            {
+                "Y": -3_i8,
+                "block_states": {
+                    "palette": [
+                        {"Name": "minecraft:air"},
+                        {"Name": "minecraft:stone"},
+                    ],
+                    // far too short for the palette, should not panic.
+                    "data": [L; 1_i64],
+                },
+            },
+        ],
+    });
+
+    let chunk = JavaChunk::from_bytes(&to_bytes(&chunk).unwrap()).unwrap();
+    assert!(matches!(chunk, JavaChunk::Post18(_)));
+
+    assert_eq!(
+        chunk.block(0, -64, 0).unwrap().name(),
+        "minecraft:deepslate"
+    );
+    assert_eq!(
+        chunk.block(15, -49, 15).unwrap().name(),
+        "minecraft:deepslate"
+    );
+    assert_eq!(chunk.biome(7, -60, 7), Some(Biome::Plains));
+
+    assert_eq!(chunk.block(0, -48, 0).unwrap().name(), "minecraft:stone");
+    assert_eq!(chunk.block(15, -33, 15).unwrap().name(), "minecraft:air");
+}
+
+/// Packed 1.18+ heightmap with every column at the same raw value.
+fn uniform_heightmap(raw: i64) -> fastnbt::LongArray {
+    let long = (0..7).fold(0, |acc, i| acc | raw << (9 * i));
+    fastnbt::LongArray::new(vec![long; 37])
+}
+
+#[test]
+fn heightmap_kinds() {
+    let chunk = nbt!({
+        "DataVersion": 3465,
+        "Status": "minecraft:full",
+        "sections": [
+            {
+                "Y": -4_i8,
+                "block_states": {
+                    "palette": [{"Name": "minecraft:stone"}],
+                },
+            },
+        ],
+        "Heightmaps": {
+            "MOTION_BLOCKING": uniform_heightmap(140),
+            "OCEAN_FLOOR": uniform_heightmap(100),
+            "WORLD_SURFACE": uniform_heightmap(150),
+        },
+    });
+
+    let chunk = JavaChunk::from_bytes(&to_bytes(&chunk).unwrap()).unwrap();
+
+    // Stored heights are relative to the bottom of the world.
+    assert_eq!(
+        chunk.heightmap(0, 0, HeightmapKind::MotionBlocking),
+        Some(76)
+    );
+    assert_eq!(chunk.heightmap(15, 15, HeightmapKind::OceanFloor), Some(36));
+    assert_eq!(chunk.heightmap(3, 9, HeightmapKind::WorldSurface), Some(86));
+    assert_eq!(
+        chunk.heightmap(3, 9, HeightmapKind::MotionBlockingNoLeaves),
+        None
+    );
+}
+
+#[test]
+fn single_heights_match_expanded_heightmap() {
+    // Arbitrary bits, so every value and any padding is exercised.
+    let data = |len| -> Vec<i64> {
+        (0..len)
+            .map(|i: i64| i.wrapping_mul(0x5851_f42d_4c95_7f2d) ^ 0x1405_7b7e_f767_814f)
+            .collect()
+    };
+
+    // 1.18 with 9 and 10 bits, 1.16 padded and 1.15 tightly packed.
+    for (len, data_version) in [(37, 3465), (43, 3465), (37, 2566), (36, 2230)] {
+        let data = data(len);
+        let expanded = crate::expand_heightmap(&data, -64, data_version);
+
+        for (i, height) in expanded.iter().enumerate() {
+            assert_eq!(
+                crate::bits::heightmap_at(&data, i, -64, data_version),
+                Some(*height),
+                "len {len}, index {i}"
+            );
+        }
+        assert_eq!(
+            crate::bits::heightmap_at(&data, 256, -64, data_version),
+            None
+        );
+    }
+}
+
+fn round_trip(chunk: &CurrentJavaChunk) -> CurrentJavaChunk {
+    fastnbt::from_bytes(&to_bytes(chunk).unwrap()).unwrap()
+}
+
+#[test]
+fn serialize_round_trip() {
+    let chunk = nbt!({
+        "DataVersion": 3465,
+        "Status": "minecraft:full",
+        "xPos": 3,
+        "yPos": -4,
+        "zPos": -7,
+        "sections": [
+            {
+                "Y": -4_i8,
+                "block_states": {
+                    "palette": [
+                        {"Name": "minecraft:air"},
+                        {"Name": "minecraft:oak_log", "Properties": {"axis": "y"}},
+                    ],
+                    // first block of every long is a log.
+                    "data": fastnbt::LongArray::new(vec![1; 256]),
+                },
+                "biomes": {
+                    "palette": ["minecraft:plains"],
+                },
+                "SkyLight": ByteArray::new(vec![-1; 2048]),
+            },
+        ],
+        "Heightmaps": {
+            "WORLD_SURFACE": uniform_heightmap(70),
+        },
+        "block_entities": [
+            {"id": "minecraft:chest", "x": 48, "y": -60, "z": -112, "Items": []},
+        ],
+    });
+
+    let chunk: CurrentJavaChunk = fastnbt::from_bytes(&to_bytes(&chunk).unwrap()).unwrap();
+    let chunk = round_trip(&chunk);
+
+    assert_eq!(
+        (chunk.x_pos, chunk.y_pos, chunk.z_pos),
+        (Some(3), Some(-4), Some(-7))
+    );
+    assert_eq!(chunk.block(0, -64, 0).unwrap().name(), "minecraft:oak_log");
+    assert_eq!(chunk.block(0, -64, 0).unwrap().property("axis"), Some("y"));
+    assert_eq!(chunk.block(1, -64, 0).unwrap().name(), "minecraft:air");
+    assert_eq!(chunk.biome(5, -60, 5), Some(Biome::Plains));
+    assert_eq!(chunk.sky_light(5, -60, 5), Some(15));
+    assert_eq!(chunk.heightmap(2, 2, HeightmapKind::WorldSurface), Some(6));
+    assert_eq!(chunk.block_entities().len(), 1);
+}
+
+#[test]
+fn serialize_keeps_unknown_biomes() {
+    let chunk = nbt!({
+        "DataVersion": 3465,
+        "Status": "minecraft:full",
+        "sections": [
+            {
+                "Y": 0_i8,
+                "biomes": {
+                    "palette": ["minecraft:cherry_grove", "mymod:glowing_marsh"],
+                    // first cell of every long is the modded biome.
+                    "data": fastnbt::LongArray::new(vec![1; 1]),
+                },
+            },
+        ],
+    });
+
+    let chunk: CurrentJavaChunk = fastnbt::from_bytes(&to_bytes(&chunk).unwrap()).unwrap();
+    let chunk = round_trip(&chunk);
+
+    assert_eq!(chunk.biome(0, 0, 0), Some(Biome::Unknown));
+    assert_eq!(chunk.biome(4, 0, 0), Some(Biome::CherryGrove));
+
+    let section = &chunk.sections.as_ref().unwrap().sections()[0];
+    assert_eq!(section.biomes.at(0, 0, 0).unwrap(), "mymod:glowing_marsh");
+    assert_eq!(section.biome(0, 0, 0), Some(Biome::Unknown));
+    assert_eq!(section.biome(4, 0, 0), Some(Biome::CherryGrove));
+}
+
+fn states(chunk: &mut CurrentJavaChunk) -> &mut BlockData<Block> {
+    &mut chunk.sections.as_mut().unwrap().sections_mut()[0].block_states
+}
+
+#[test]
+fn set_block_repacks_section() {
+    let chunk = nbt!({
+        "DataVersion": 3465,
+        "Status": "minecraft:full",
+        "sections": [
+            {
+                "Y": 0_i8,
+                "block_states": {
+                    "palette": [{"Name": "minecraft:stone"}],
+                },
+            },
+        ],
+    });
+
+    let mut chunk: CurrentJavaChunk = fastnbt::from_bytes(&to_bytes(&chunk).unwrap()).unwrap();
+    let stone = chunk.block(0, 0, 0).unwrap().clone();
+    let blocks: Vec<Block> = (0..16)
+        .map(|i| {
+            let block = nbt!({"Name": format!("minecraft:block_{i}")});
+            fastnbt::from_value(&block).unwrap()
+        })
+        .collect();
+
+    assert!(states(&mut chunk).set(1, 2, 3, blocks[0].clone()));
+    let mut chunk = round_trip(&chunk);
+    assert_eq!(chunk.block(1, 2, 3).unwrap().name(), "minecraft:block_0");
+    assert_eq!(chunk.block(1, 2, 4).unwrap().name(), "minecraft:stone");
+    assert_eq!(states(&mut chunk).palette().len(), 2);
+
+    // 17 entries needs 5 bits per block.
+    for (i, block) in blocks.iter().enumerate() {
+        assert!(states(&mut chunk).set(i, 15, 15, block.clone()));
+    }
+    let mut chunk = round_trip(&chunk);
+    assert_eq!(states(&mut chunk).palette().len(), 17);
+    for i in 0..16 {
+        assert_eq!(
+            chunk.block(i, 15, 15).unwrap().name(),
+            format!("minecraft:block_{i}")
+        );
+    }
+    assert_eq!(chunk.block(1, 2, 3).unwrap().name(), "minecraft:block_0");
+    assert_eq!(chunk.block(0, 0, 0).unwrap().name(), "minecraft:stone");
+
+    // Putting stone back everywhere drops the data entirely.
+    for (x, y, z) in (0..16).map(|i| (i, 15, 15)).chain([(1, 2, 3)]) {
+        assert!(states(&mut chunk).set(x, y, z, stone.clone()));
+    }
+    let mut chunk = round_trip(&chunk);
+    assert_eq!(states(&mut chunk).palette().len(), 1);
+    assert!(states(&mut chunk).try_iter_indices().is_none());
+}
+
+#[test]
+fn serialize_keeps_unknown_fields() {
+    let file = std::fs::File::open("./resources/1.19.4.mca").unwrap();
+    let mut region = Region::from_stream(file).unwrap();
+    let data = region.read_chunk(0, 0).unwrap().unwrap();
+
+    let original: Value = fastnbt::from_bytes(&data).unwrap();
+    let chunk: LosslessJavaChunk = fastnbt::from_bytes(&data).unwrap();
+    assert_eq!(chunk.chunk.data_version, 3337);
+    assert!(!chunk.extra.contains_key("sections"));
+
+    let written: Value = fastnbt::from_bytes(&to_bytes(&chunk).unwrap()).unwrap();
+
+    let (Value::Compound(original), Value::Compound(written)) = (original, written) else {
+        panic!("chunks should be compounds");
+    };
+
+    for key in [
+        "structures",
+        "PostProcessing",
+        "InhabitedTime",
+        "fluid_ticks",
+    ] {
+        assert!(original.contains_key(key), "test chunk missing {key}");
+        assert_eq!(original.get(key), written.get(key), "{key} differs");
+    }
+
+    let mut original_keys: Vec<_> = original.keys().collect();
+    let mut written_keys: Vec<_> = written.keys().collect();
+    original_keys.sort();
+    written_keys.sort();
+    assert_eq!(original_keys, written_keys);
+}
+
+#[test]
+fn iter_blocks_matches_block_lookup() {
+    let file = std::fs::File::open("./resources/1.19.4.mca").unwrap();
+    let mut region = Region::from_stream(file).unwrap();
+    let data = region.read_chunk(0, 0).unwrap().unwrap();
+    let chunk: CurrentJavaChunk = fastnbt::from_bytes(&data).unwrap();
+
+    let mut expected = vec![];
+    for y in chunk.y_range() {
+        for z in 0..16 {
+            for x in 0..16 {
+                if let Some(block) = chunk.block(x, y, z) {
+                    if !matches!(block.name(), "minecraft:air" | "minecraft:cave_air") {
+                        expected.push((x, y, z, block.name()));
+                    }
+                }
+            }
+        }
+    }
+
+    let mut actual: Vec<_> = chunk
+        .iter_blocks()
+        .map(|(x, y, z, b)| (x, y, z, b.name()))
+        .collect();
+    actual.sort_by_key(|(x, y, z, _)| (*y, *z, *x));
+
+    assert!(!expected.is_empty());
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn iter_blocks_ignores_data_of_single_palette() {
+    let chunk = nbt!({
+        "DataVersion": 3465,
+        "Status": "minecraft:full",
+        "sections": [
+            {
+                "Y": 0_i8,
+                "block_states": {
+                    "palette": [{"Name": "minecraft:stone"}],
+                    "data": fastnbt::LongArray::new(vec![0; 256]),
+                },
+            },
+        ],
+    });
+
+    let chunk: CurrentJavaChunk = fastnbt::from_bytes(&to_bytes(&chunk).unwrap()).unwrap();
+    let blocks: Vec<_> = chunk.iter_blocks().collect();
+
+    assert_eq!(blocks.len(), 16 * 16 * 16);
+    assert!(blocks
+        .iter()
+        .all(|(_, y, _, b)| (0..16).contains(y) && b.name() == "minecraft:stone"));
+}
+
+#[test]
+fn root_level_and_level_nested_chunks() {
+    // 1.18 moved everything out of Level to the root of the chunk.
+    let current = nbt!({
+        "DataVersion": 2860,
+        "Status": "full",
+        "xPos": 1,
+        "yPos": -4,
+        "zPos": 2,
+        "sections": [
+            {
+                "Y": -4_i8,
+                "block_states": {
+                    "palette": [{ "Name": "minecraft:stone" }],
+                },
+            },
+        ],
+    });
+
+    let chunk = JavaChunk::from_bytes(&to_bytes(&current).unwrap()).unwrap();
+    let JavaChunk::Post18(chunk) = chunk else {
+        panic!("expected 1.18+ chunk, got {chunk:?}");
+    };
+    assert_eq!(chunk.status, "full");
+    assert_eq!(chunk.x_pos, Some(1));
+    assert_eq!(chunk.block(0, -64, 0).unwrap().name(), "minecraft:stone");
+
+    // Before 1.18, data is nested under Level.
+    let nested = nbt!({
+        "DataVersion": 2730,
+        "Level": {
+            "xPos": 1,
+            "zPos": 2,
+            "Status": "full",
+            "Sections": [
+                {
+                    "Y": 0_i8,
+                    "Palette": [{ "Name": "minecraft:stone" }],
+                    "BlockStates": fastnbt::LongArray::new(vec![0; 256]),
+                },
+            ],
+        },
+    });
+
+    let chunk = JavaChunk::from_bytes(&to_bytes(&nested).unwrap()).unwrap();
+    let JavaChunk::Pre18(chunk) = chunk else {
+        panic!("expected pre-1.18 chunk, got {chunk:?}");
+    };
+    assert_eq!(chunk.level.status, "full");
+    assert_eq!(chunk.level.x_pos, 1);
+    assert_eq!(chunk.block(0, 0, 0).unwrap().name(), "minecraft:stone");
+}
+
+#[test]
+fn saved_chunks_pick_their_version() {
+    let chunk = JavaChunk::from_bytes(include_bytes!("../../resources/21w44a-test1.nbt"));
+    assert!(matches!(chunk, Ok(JavaChunk::Post18(_))));
+
+    let chunk = JavaChunk::from_bytes(include_bytes!("../../resources/1.17.1.chunk"));
+    assert!(matches!(chunk, Ok(JavaChunk::Pre18(_))));
+}
+
+#[test]
+fn data_version_picks_error() {
+    // Missing Status, so fails as every version. The error should be from the
+    // version the DataVersion says, not from the last version tried.
+    let chunk = nbt!({
+        "DataVersion": 3465,
+        "sections": [],
+    });
+
+    let err = JavaChunk::from_bytes(&to_bytes(&chunk).unwrap()).unwrap_err();
+    assert!(err.to_string().contains("Status"), "{err}");
+}
+
+#[test]
+fn chunk_not_matching_data_version_is_still_read() {
+    // A Level-nested chunk with a 1.18 data version.
+    let chunk = nbt!({
+        "DataVersion": 3465,
+        "Level": {
+            "xPos": 1,
+            "zPos": 2,
+            "Status": "full",
+        },
+    });
+
+    let chunk = JavaChunk::from_bytes(&to_bytes(&chunk).unwrap()).unwrap();
+    assert!(matches!(chunk, JavaChunk::Pre18(_)));
+}
+
+#[test]
+fn data_version_of_each_version() {
+    let chunk = JavaChunk::from_bytes(include_bytes!("../../resources/21w44a-test1.nbt")).unwrap();
+    assert_eq!(chunk.data_version(), Some(2845));
+
+    let chunk = JavaChunk::from_bytes(include_bytes!("../../resources/1.17.1.chunk")).unwrap();
+    assert_eq!(chunk.data_version(), Some(2730));
+    assert_eq!(chunk.into_boxed().data_version(), Some(2730));
+}
+
+#[test]
+fn section_tower_iterates_sections_by_height() {
+    let sections = nbt!([
+        {"Y": 2_i8, "Palette": [{"Name": "minecraft:stone"}]},
+        {"Y": -1_i8},
+        {"Y": 0_i8, "Palette": [{"Name": "minecraft:dirt"}]},
+    ]);
+
+    let tower: SectionTower<Pre18Section> = fastnbt::from_value(&sections).unwrap();
+
+    // The terminating section at y -1 is not part of the world, and there is
+    // no section at y 1.
+    assert_eq!(tower.y_min(), 0);
+    assert_eq!(tower.y_max(), 48);
+    assert_eq!(tower.sections().len(), 3);
+
+    let ys: Vec<_> = tower.iter_sections().map(|s| s.y).collect();
+    assert_eq!(ys, [0, 2]);
+}
+
+#[test]
+fn missing_sections_are_air_with_block_or_air() {
+    let chunk = nbt!({
+        "DataVersion": 3465,
+        "Status": "minecraft:full",
+        "sections": [
+            {"Y": 0_i8},
+        ],
+    });
+    let chunk: CurrentJavaChunk = fastnbt::from_bytes(&to_bytes(&chunk).unwrap()).unwrap();
+
+    // a section without block states is all air.
+    assert_eq!(chunk.block(0, 0, 0), Some(Block::air()));
+    assert_eq!(chunk.block_or_air(0, 0, 0), Block::air());
+
+    // outside of any section there is no block, unless asking for air.
+    assert_eq!(chunk.block(0, 100, 0), None);
+    assert_eq!(chunk.block_or_air(0, 100, 0).name(), "minecraft:air");
+}
+
+#[test]
+fn pre13_unknown_block_id_is_unknown_block() {
+    // block id 256 at 0,0,0 needs the Add array.
+    let mut add = vec![0; 2048];
+    add[0] = 1;
+
+    let chunk = nbt!({
+        "DataVersion": 1343,
+        "Level": {
+            "xPos": 0,
+            "zPos": 0,
+            "Sections": [{
+                "Y": 0_i8,
+                "Blocks": ByteArray::new(vec![0; 4096]),
+                "Add": ByteArray::new(add),
+                "Data": ByteArray::new(vec![0; 2048]),
+            }],
+        },
+    });
+
+    let chunk = JavaChunk::from_bytes(&to_bytes(&chunk).unwrap()).unwrap();
+    assert!(matches!(chunk, JavaChunk::Pre13(_)));
+
+    assert_eq!(chunk.block(0, 0, 0), Some(&*crate::pre13::UNKNOWN_BLOCK));
+    assert_eq!(chunk.block(0, 0, 0).unwrap().name(), "fastanvil:unknown");
+    assert_eq!(chunk.block(1, 0, 0).unwrap().name(), "minecraft:air");
+}
+
+/// Pack block states into longs, either with the padding used since 1.16 or
+/// tightly packed as before.
+fn pack_states(states: &[u16], bits: usize, padded: bool) -> Vec<i64> {
+    if padded {
+        return states
+            .chunks(64 / bits)
+            .map(|chunk| {
+                let long = chunk
+                    .iter()
+                    .enumerate()
+                    .fold(0u64, |long, (i, &s)| long | (s as u64) << (i * bits));
+                long as i64
+            })
+            .collect();
+    }
+
+    let mut longs = vec![0u64; states.len() * bits / 64];
+    for (i, &s) in states.iter().enumerate() {
+        let (index, offset) = (i * bits / 64, i * bits % 64);
+        longs[index] |= (s as u64) << offset;
+        if offset + bits > 64 {
+            longs[index + 1] |= (s as u64) >> (64 - offset);
+        }
+    }
+    longs.into_iter().map(|long| long as i64).collect()
+}
+
+#[test]
+fn pre18_block_states_decode_by_data_version() {
+    // 20 blocks need 5 bits per block, which straddle longs in 1.15. 600
+    // blocks need 10 bits, too many to tell the layouts apart by length.
+    for pal_len in [20, 600] {
+        let bits = crate::bits_per_block(pal_len);
+        let palette: Vec<_> = (0..pal_len)
+            .map(|i| nbt!({ "Name": (format!("minecraft:block_{i}")) }))
+            .collect();
+        let states: Vec<u16> = (0..16 * 16 * 16)
+            .map(|i| (i * 7 % pal_len) as u16)
+            .collect();
+
+        let chunk = |data_version: i32, padded: bool| {
+            let states = fastnbt::LongArray::new(pack_states(&states, bits, padded));
+            let chunk = nbt!({
+                "DataVersion": #data_version,
+                "Level": {
+                    "xPos": 0,
+                    "zPos": 0,
+                    "Status": "full",
+                    "Sections": [
+                        {
+                            "Y": 0_i8,
+                            "Palette": (Value::List(palette.clone())),
+                            "BlockStates": #states,
+                        },
+                    ],
+                },
+            });
+            JavaChunk::from_bytes(&to_bytes(&chunk).unwrap()).unwrap()
+        };
+
+        // 1.15.2 and 1.16.5.
+        let v1_15 = chunk(2230, false);
+        let v1_16 = chunk(2586, true);
+
+        for y in 0..16 {
+            for z in 0..16 {
+                for x in 0..16 {
+                    let expected = states[y * 256 + z * 16 + x];
+                    let expected = format!("minecraft:block_{expected}");
+                    let y = y as isize;
+
+                    assert_eq!(v1_15.block(x, y, z).unwrap().name(), expected);
+                    assert_eq!(v1_16.block(x, y, z).unwrap().name(), expected);
+                }
+            }
+        }
+    }
+}
@@ -7,10 +7,19 @@ use std::{collections::hash_map::DefaultHasher, hash::Hasher};
 
 use fastnbt::{nbt, LongArray, Value};
 
+mod biome;
+mod block;
+mod dimension;
 mod region;
+#[cfg(feature = "render")]
+mod render;
+#[cfg(feature = "render")]
+mod rendered_palette;
 mod rogue_chunks;
+mod schematic;
 mod section_data;
 mod complete_chunk;
+mod java_chunk;
 #[cfg(feature = "render")]
 mod standard_chunks;
 mod unicode_chunk;
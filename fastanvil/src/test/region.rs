@@ -1,8 +1,12 @@
 use std::io::{Cursor, Read, Seek, Write};
 
+use flate2::{read::ZlibEncoder, Compression};
+use lz4_java_wrc::Lz4BlockOutput;
+
 use crate::{
-    ChunkLocation, CompressionScheme::Uncompressed, Error, Region, CHUNK_HEADER_SIZE,
-    REGION_HEADER_SIZE, SECTOR_SIZE,
+    ChunkLocation,
+    CompressionScheme::{Gzip, Lz4, Uncompressed, Zlib},
+    Error, JavaChunk, Region, RegionProblem, CHUNK_HEADER_SIZE, REGION_HEADER_SIZE, SECTOR_SIZE,
 };
 
 fn new_empty() -> Region<Cursor<Vec<u8>>> {
@@ -39,6 +43,12 @@ fn new_region_should_be_empty() {
     }
 }
 
+#[test]
+fn create_writes_zeroed_header() {
+    let buf = new_empty().into_inner().unwrap().into_inner();
+    assert_eq!(buf, vec![0; REGION_HEADER_SIZE]);
+}
+
 #[test]
 fn blank_write_chunk() {
     let mut r = new_empty();
@@ -96,6 +106,92 @@ fn oversized_chunk_fails() {
     assert!(matches!(res, Err(Error::ChunkTooLarge)))
 }
 
+#[test]
+fn oversized_chunk_leaves_region_untouched() {
+    let mut r = new_empty();
+    let _ = r.write_compressed_chunk(0, 0, Uncompressed, &n_sector_chunk(256));
+    assert!(matches!(r.read_chunk(0, 0), Ok(None)));
+
+    let inner = r.into_inner().unwrap();
+    assert_eq!(inner.get_ref().len(), REGION_HEADER_SIZE);
+}
+
+#[test]
+fn write_chunk_sets_timestamp() {
+    let mut r = new_empty();
+    r.write_chunk(0, 1, &[1, 2, 3]).unwrap();
+
+    let buf = r.into_inner().unwrap().into_inner();
+    let pos = SECTOR_SIZE + 4 * 32;
+    let ts = u32::from_be_bytes(buf[pos..pos + 4].try_into().unwrap());
+    assert!(ts > 0);
+}
+
+#[test]
+fn chunk_timestamp_round_trips() {
+    let mut r = new_empty();
+    r.write_compressed_chunk(2, 3, Uncompressed, &[1, 2, 3])
+        .unwrap();
+    r.set_chunk_timestamp(2, 3, 1234).unwrap();
+    assert_eq!(r.chunk_timestamp(2, 3).unwrap(), Some(1234));
+}
+
+#[test]
+fn absent_chunk_has_no_timestamp() {
+    let mut r = new_empty();
+    assert_eq!(r.chunk_timestamp(0, 0).unwrap(), None);
+    assert!(matches!(
+        r.chunk_timestamp(32, 0),
+        Err(Error::InvalidOffset(..))
+    ));
+}
+
+#[test]
+fn has_chunk_and_present_chunks() {
+    let mut r = new_empty();
+    assert!(!r.has_chunk(0, 0).unwrap());
+    assert_eq!(r.present_chunks().unwrap().count(), 0);
+
+    r.write_compressed_chunk(5, 1, Uncompressed, &[1, 2, 3])
+        .unwrap();
+    r.write_compressed_chunk(2, 3, Uncompressed, &[1, 2, 3])
+        .unwrap();
+    r.write_compressed_chunk(31, 0, Uncompressed, &[1, 2, 3])
+        .unwrap();
+
+    assert!(r.has_chunk(2, 3).unwrap());
+    assert!(!r.has_chunk(3, 2).unwrap());
+    assert!(matches!(r.has_chunk(0, 32), Err(Error::InvalidOffset(..))));
+    assert_eq!(
+        r.present_chunks().unwrap().collect::<Vec<_>>(),
+        [(31, 0), (5, 1), (2, 3)]
+    );
+}
+
+#[test]
+fn write_chunk_round_trips() {
+    let mut r = new_empty();
+    r.write_chunk(3, 4, &[1, 2, 3]).unwrap();
+    assert_eq!(r.read_chunk(3, 4).unwrap().unwrap(), [1, 2, 3]);
+}
+
+#[test]
+fn read_chunk_into_reuses_buffer() {
+    let mut r = new_empty();
+    for (i, scheme) in [Gzip, Zlib, Uncompressed, Lz4].into_iter().enumerate() {
+        r.write_chunk_with(i, 0, scheme, &[i as u8; 100]).unwrap();
+    }
+
+    let mut buf = vec![0xff; 1000];
+    for i in 0..4 {
+        assert!(r.read_chunk_into(i, 0, &mut buf).unwrap());
+        assert_eq!(buf, [i as u8; 100]);
+    }
+
+    assert!(!r.read_chunk_into(5, 5, &mut buf).unwrap());
+    assert!(buf.is_empty());
+}
+
 #[test]
 fn write_several_chunks() {
     let mut r = new_empty();
@@ -117,6 +213,93 @@ fn write_and_get_chunk() {
     assert_eq!(c, &[1, 2, 3]);
 }
 
+#[test]
+fn write_and_get_lz4_chunk() {
+    let data = b"some chunk data, some chunk data, some chunk data".to_vec();
+    let mut compressed = vec![];
+    Lz4BlockOutput::new(&mut compressed)
+        .write_all(&data)
+        .unwrap();
+
+    let mut r = new_empty();
+    r.write_compressed_chunk(0, 0, Lz4, &compressed).unwrap();
+    let c = r.read_chunk(0, 0).unwrap().unwrap();
+    assert_eq!(c, data);
+}
+
+#[test]
+fn write_chunk_with_each_scheme_round_trips() {
+    let data = b"some chunk data, some chunk data, some chunk data".to_vec();
+
+    for scheme in [Gzip, Zlib, Uncompressed, Lz4] {
+        let mut r = new_empty();
+        r.write_chunk_with(1, 2, scheme, &data).unwrap();
+        let c = r.read_chunk(1, 2).unwrap().unwrap();
+        assert_eq!(c, data);
+    }
+}
+
+#[test]
+fn unknown_compression_scheme_errors() {
+    let mut r = new_empty();
+    r.write_compressed_chunk(0, 0, Uncompressed, &[1, 2, 3])
+        .unwrap();
+
+    // corrupt the scheme byte of the chunk.
+    let mut buf = r.into_inner().unwrap().into_inner();
+    buf[REGION_HEADER_SIZE + 4] = 42;
+
+    let mut r = Region::from_stream(Cursor::new(buf)).unwrap();
+    assert!(matches!(
+        r.read_chunk(0, 0),
+        Err(Error::UnknownCompression(42))
+    ));
+}
+
+#[test]
+fn external_chunk_uses_resolver() {
+    let data = b"some chunk data".to_vec();
+    let mut r = new_empty();
+    r.write_compressed_chunk(0, 0, Uncompressed, &[]).unwrap();
+
+    // mark the chunk as external and zlib compressed.
+    let mut buf = r.into_inner().unwrap().into_inner();
+    buf[REGION_HEADER_SIZE + 4] = 0x80 | Zlib as u8;
+    let mut r = Region::from_stream(Cursor::new(buf)).unwrap();
+
+    assert!(matches!(
+        r.read_chunk(0, 0),
+        Err(Error::ExternalChunk(0, 0))
+    ));
+
+    let mut compressed = vec![];
+    ZlibEncoder::new(data.as_slice(), Compression::fast())
+        .read_to_end(&mut compressed)
+        .unwrap();
+
+    let c = r
+        .read_chunk_with_external(0, 0, |x, z| {
+            assert_eq!((x, z), (0, 0));
+            Ok(compressed.clone())
+        })
+        .unwrap()
+        .unwrap();
+    assert_eq!(c, data);
+}
+
+#[test]
+fn chunks_parsed_skips_absent_and_reports_errors() {
+    let chunk = include_bytes!("../../resources/21w44a-test1.nbt");
+    let mut r = new_empty();
+    r.write_chunk(1, 0, chunk).unwrap();
+    r.write_chunk(3, 0, &[1, 2, 3]).unwrap();
+
+    let chunks: Vec<_> = r.chunks_parsed().collect();
+    assert_eq!(chunks.len(), 2);
+    assert!(matches!(chunks[0], Ok((1, 0, JavaChunk::Post18(_)))));
+    assert!(matches!(chunks[1], Err(Error::Nbt(_))));
+}
+
 #[test]
 fn getting_other_chunks_404s() {
     let mut r = new_empty();
@@ -201,6 +384,55 @@ fn load_from_existing_buffer() {
     assert_location(&mut r, 0, 1, 3, 2);
 }
 
+#[test]
+fn from_slice_borrows_data() {
+    let mut r = new_empty();
+    r.write_chunk(1, 2, &[1, 2, 3]).unwrap();
+    let buf = r.into_inner().unwrap().into_inner();
+
+    let mut r = Region::from_slice(&buf).unwrap();
+    assert_eq!(Some(vec![1, 2, 3]), r.read_chunk(1, 2).unwrap());
+    assert_eq!(None, r.read_chunk(0, 0).unwrap());
+
+    assert!(matches!(
+        Region::from_slice(&buf[..100]),
+        Err(Error::TruncatedHeader(100))
+    ));
+}
+
+#[test]
+fn load_checks_header_length() {
+    let r = Region::load(Cursor::new(vec![0; REGION_HEADER_SIZE - 1]));
+    assert!(matches!(
+        r,
+        Err(Error::TruncatedHeader(len)) if len == REGION_HEADER_SIZE as u64 - 1
+    ));
+
+    let mut r = Region::load(Cursor::new(vec![0; REGION_HEADER_SIZE])).unwrap();
+    assert!(matches!(r.read_chunk(0, 0), Ok(None)));
+}
+
+#[test]
+fn load_empty_stream_is_empty_region() {
+    let mut r = Region::load(Cursor::new(vec![])).unwrap();
+    assert!(matches!(r.read_chunk(0, 0), Ok(None)));
+    assert!(matches!(r.chunk_timestamp(31, 31), Ok(None)));
+
+    r.write_chunk(0, 0, &[1, 2, 3]).unwrap();
+    assert_location(&mut r, 0, 0, 2, 1);
+    assert_eq!(r.read_chunk(0, 0).unwrap(), Some(vec![1, 2, 3]));
+}
+
+#[test]
+fn load_existing_header_without_chunks_writes_after_header() {
+    let buf = new_empty().into_inner().unwrap();
+
+    let mut r = Region::load(buf).unwrap();
+    r.write_compressed_chunk(0, 0, Uncompressed, &n_sector_chunk(1))
+        .unwrap();
+    assert_location(&mut r, 0, 0, 2, 1);
+}
+
 #[test]
 fn deleted_chunk_doenst_exist() {
     let mut r = new_empty();
@@ -212,11 +444,16 @@ fn deleted_chunk_doenst_exist() {
     r.write_compressed_chunk(0, 2, Uncompressed, &n_sector_chunk(3))
         .unwrap();
 
-    r.remove_chunk(0, 1).unwrap();
+    assert!(r.remove_chunk(0, 1).unwrap());
 
     assert!(matches!(r.read_chunk(0, 0), Ok(Some(_))));
     assert!(matches!(r.read_chunk(0, 1), Ok(None)));
     assert!(matches!(r.read_chunk(0, 2), Ok(Some(_))));
+
+    // the timestamp is cleared too, at 4 bytes per chunk in the second sector.
+    let data = r.into_inner().unwrap().into_inner();
+    let timestamp = SECTOR_SIZE + 32 * 4;
+    assert_eq!(data[timestamp..timestamp + 4], [0; 4]);
 }
 
 #[test]
@@ -228,7 +465,7 @@ fn deleting_non_existing_chunk_works() {
     r.write_compressed_chunk(0, 2, Uncompressed, &n_sector_chunk(3))
         .unwrap();
 
-    r.remove_chunk(0, 1).unwrap();
+    assert!(!r.remove_chunk(0, 1).unwrap());
 
     assert!(matches!(r.read_chunk(0, 0), Ok(Some(_))));
     assert!(matches!(r.read_chunk(0, 1), Ok(None)));
@@ -268,3 +505,110 @@ fn into_inner_rewinds_behind_header_if_empty_region() {
 // abstraction on top of this providing this. Something that copies a region and
 // only write the to copy until done, then atomically moves the file over the
 // old region.
+
+#[test]
+fn chunk_raw_meta_reads_header_only() {
+    let mut r = new_empty();
+    r.write_compressed_chunk(1, 2, Gzip, &[1, 2, 3]).unwrap();
+    r.write_compressed_chunk(3, 4, Uncompressed, &n_sector_chunk(2))
+        .unwrap();
+
+    // length includes the compression scheme byte.
+    assert_eq!(r.chunk_raw_meta(1, 2).unwrap(), Some((4, Gzip)));
+    assert_eq!(
+        r.chunk_raw_meta(3, 4).unwrap(),
+        Some(((2 * SECTOR_SIZE - 4) as u32, Uncompressed))
+    );
+    assert_eq!(r.chunk_raw_meta(0, 0).unwrap(), None);
+    assert!(matches!(
+        r.chunk_raw_meta(32, 0),
+        Err(Error::InvalidOffset(32, 0))
+    ));
+}
+
+#[test]
+fn validate_finds_bad_header_entries() {
+    let mut r = new_empty();
+    for x in 0..3 {
+        r.write_compressed_chunk(x, 0, Uncompressed, &[1, 2, 3])
+            .unwrap();
+    }
+    assert_eq!(r.validate().unwrap(), []);
+
+    let mut buf = r.into_inner().unwrap().into_inner();
+    let mut set_location = |x: usize, z: usize, offset: u32, sectors: u8| {
+        let pos = 4 * (x + z * 32);
+        buf[pos..pos + 3].copy_from_slice(&offset.to_be_bytes()[1..]);
+        buf[pos + 3] = sectors;
+    };
+
+    set_location(1, 0, 2, 1); // same sector as (0, 0)
+    set_location(3, 0, 100, 1);
+    set_location(4, 0, 5, 0);
+    set_location(0, 1, 1, 1);
+
+    let mut r = Region::from_stream(Cursor::new(buf)).unwrap();
+    assert_eq!(
+        r.validate().unwrap(),
+        [
+            RegionProblem::Overlap {
+                x: 1,
+                z: 0,
+                other: (0, 0)
+            },
+            RegionProblem::BeyondEnd { x: 3, z: 0 },
+            RegionProblem::ZeroLength { x: 4, z: 0 },
+            RegionProblem::InHeader { x: 0, z: 1 },
+        ]
+    );
+}
+
+#[test]
+fn locations_in_bytes_from_sector_size() {
+    let mut r = new_empty();
+    r.write_chunk(0, 0, &[1, 2, 3]).unwrap();
+
+    let location = r.location(0, 0).unwrap().unwrap();
+    let buf = r.into_inner().unwrap().into_inner();
+
+    let start = location.offset as usize * SECTOR_SIZE;
+    assert_eq!(start, REGION_HEADER_SIZE);
+
+    // chunk header is the length, including the compression scheme byte.
+    let len = u32::from_be_bytes(buf[start..start + 4].try_into().unwrap()) as usize;
+    assert!(CHUNK_HEADER_SIZE + len - 1 <= location.sectors as usize * SECTOR_SIZE);
+}
+
+#[test]
+fn nbt_errors_convert_with_question_mark() {
+    fn first_chunk(region: &mut Region<Cursor<Vec<u8>>>) -> crate::Result<JavaChunk> {
+        let data = region.read_chunk(0, 0)?.unwrap_or_default();
+        Ok(JavaChunk::from_bytes(&data)?)
+    }
+
+    let mut r = new_empty();
+    r.write_chunk(0, 0, &[1, 2, 3]).unwrap();
+    assert!(matches!(first_chunk(&mut r), Err(Error::Nbt(_))));
+}
+
+#[test]
+fn read_locations_matches_location() {
+    let mut r = new_empty();
+    r.write_chunk(1, 2, &[1, 2, 3]).unwrap();
+    r.write_chunk(3, 0, &[1, 2, 3]).unwrap();
+    r.write_chunk(31, 31, &[1, 2, 3]).unwrap();
+
+    let locations = r.read_locations().unwrap();
+    assert_eq!(locations.iter().flatten().count(), 3);
+
+    for z in 0..32 {
+        for x in 0..32 {
+            assert_eq!(locations[z * 32 + x], r.location(x, z).unwrap());
+        }
+    }
+    // the first chunk written goes straight after the header.
+    assert_eq!(locations[2 * 32 + 1].map(|loc| loc.offset), Some(2));
+
+    let mut empty = Region::load(Cursor::new(vec![])).unwrap();
+    assert!(empty.read_locations().unwrap().iter().all(Option::is_none));
+}
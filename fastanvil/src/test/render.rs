@@ -0,0 +1,98 @@
+use std::sync::Mutex;
+
+use super::HashPalette;
+use crate::{
+    biome::Biome, render::top_shade_colour, render_chunk, Block, BlockArchetype, Chunk,
+    ColumnContext, HeightMode, JavaChunk, Palette, Rgba, ShadeOpts,
+};
+
+const COLOUR: [u8; 4] = [200, 100, 50, 255];
+
+#[test]
+fn default_shade_matches_minecraft() {
+    let opts = ShadeOpts::default();
+    assert_eq!(top_shade_colour(COLOUR, 10, 10, opts), [172, 86, 43, 255]);
+    assert_eq!(top_shade_colour(COLOUR, 9, 10, opts), [141, 70, 35, 255]);
+    assert_eq!(top_shade_colour(COLOUR, 11, 10, opts), COLOUR);
+}
+
+#[test]
+fn zero_strength_is_flat() {
+    let opts = ShadeOpts { strength: 0.0 };
+    assert_eq!(top_shade_colour(COLOUR, 9, 10, opts), COLOUR);
+    assert_eq!(top_shade_colour(COLOUR, 10, 10, opts), COLOUR);
+}
+
+#[test]
+fn stronger_shade_is_darker() {
+    let weak = top_shade_colour(COLOUR, 9, 10, ShadeOpts { strength: 0.5 });
+    let normal = top_shade_colour(COLOUR, 9, 10, ShadeOpts::default());
+    let strong = top_shade_colour(COLOUR, 9, 10, ShadeOpts { strength: 2.0 });
+
+    assert!(weak[0] > normal[0]);
+    assert!(strong[0] < normal[0]);
+
+    // shading never goes below black.
+    let extreme = top_shade_colour(COLOUR, 9, 10, ShadeOpts { strength: 100.0 });
+    assert_eq!(extreme, [0, 0, 0, 255]);
+}
+
+#[test]
+fn render_chunk_uses_surface_block() {
+    let chunk = JavaChunk::from_bytes(include_bytes!("../../resources/1.17.0.chunk")).unwrap();
+    let map = render_chunk(&chunk, &HashPalette);
+
+    let mut checked = 0;
+    for z in 0..16 {
+        for x in 0..16 {
+            let y = chunk.surface_height(x, z, HeightMode::Trust) - 1;
+            let block = chunk.block(x, y, z).unwrap();
+
+            // HashPalette is opaque, so solid blocks are drawn as is, give or
+            // take rounding when blending.
+            if block.archetype == BlockArchetype::Normal {
+                let expected = HashPalette.pick(block, None);
+                let actual = map[z * 16 + x];
+                for (a, e) in actual.into_iter().zip(expected) {
+                    assert!(a.abs_diff(e) <= 1, "{actual:?} != {expected:?}");
+                }
+                checked += 1;
+            }
+        }
+    }
+    assert!(checked > 0);
+}
+
+#[test]
+fn palette_gets_water_depth() {
+    struct DepthPalette(Mutex<Vec<ColumnContext>>);
+
+    impl Palette for DepthPalette {
+        fn pick(&self, _: &Block, _: Option<Biome>) -> Rgba {
+            unreachable!("renderer should pick with context")
+        }
+
+        fn pick_with_context(&self, _: &Block, _: Option<Biome>, column: &ColumnContext) -> Rgba {
+            self.0.lock().unwrap().push(*column);
+            [0, 0, 255, 255]
+        }
+    }
+
+    // An ocean chunk.
+    let chunk = JavaChunk::from_bytes(include_bytes!("../../resources/1.17.1.chunk")).unwrap();
+    let palette = DepthPalette(Mutex::new(vec![]));
+    render_chunk(&chunk, &palette);
+
+    let contexts = palette.0.into_inner().unwrap();
+    let (x, z) = (0, 0);
+    let surface = contexts[0];
+
+    let depth = surface.water_depth.expect("surface is water");
+    assert!(depth > 1);
+    assert_eq!(surface.below_y, surface.y - depth);
+
+    let floor = chunk.block(x, surface.below_y, z).unwrap();
+    let water = chunk.block(x, surface.below_y + 1, z).unwrap();
+    assert_ne!(floor.archetype, BlockArchetype::Watery);
+    assert_eq!(water.archetype, BlockArchetype::Watery);
+}
@@ -0,0 +1,133 @@
+use std::collections::HashMap;
+
+use fastnbt::{nbt, to_bytes};
+
+use crate::{biome::Biome, Block, Palette, RenderedPalette};
+
+/// Colourmaps where the pixel's red and green are its x and y, so we can tell
+/// where the palette sampled.
+fn palette() -> RenderedPalette {
+    let map = image::RgbaImage::from_fn(256, 256, |x, y| image::Rgba([x as u8, y as u8, 0, 255]));
+    let mut foliage = map.clone();
+    foliage.pixels_mut().for_each(|p| p.0[2] = 1);
+
+    RenderedPalette {
+        blockstates: HashMap::new(),
+        tints: HashMap::new(),
+        grass: map,
+        foliage,
+        version: RenderedPalette::VERSION,
+    }
+}
+
+fn block(name: &str) -> Block {
+    fastnbt::from_bytes(&to_bytes(&nbt!({ "Name": name })).unwrap()).unwrap()
+}
+
+#[test]
+fn grass_is_tinted_by_biome() {
+    let palette = palette();
+    let grass = block("minecraft:grass_block");
+
+    let plains = palette.pick(&grass, Some(Biome::Plains));
+    let jungle = palette.pick(&grass, Some(Biome::Jungle));
+    assert_ne!(plains, jungle);
+
+    // plains is temperature 0.8, rainfall 0.4.
+    assert_eq!(plains, [255 - 204, 255 - 82, 0, 255]);
+}
+
+#[test]
+fn foliage_uses_foliage_colourmap() {
+    let palette = palette();
+    let leaves = palette.pick(&block("minecraft:oak_leaves"), Some(Biome::Forest));
+    assert_eq!(leaves[2], 1);
+}
+
+#[test]
+fn biome_grass_modifiers() {
+    let palette = palette();
+    let grass = block("minecraft:grass_block");
+    let leaves = block("minecraft:oak_leaves");
+
+    assert_eq!(
+        palette.pick(&grass, Some(Biome::Swamp)),
+        [0x6a, 0x70, 0x39, 255]
+    );
+    assert_eq!(
+        palette.pick(&grass, Some(Biome::Badlands)),
+        [0x90, 0x81, 0x4d, 255]
+    );
+    assert_eq!(
+        palette.pick(&leaves, Some(Biome::WoodedBadlands)),
+        [0x9e, 0x81, 0x4d, 255]
+    );
+
+    // forest would be [76, 112, 0], dark forest halves it with an offset.
+    assert_eq!(
+        palette.pick(&grass, Some(Biome::DarkForest)),
+        [58, 82, 5, 255]
+    );
+}
+
+#[test]
+fn tinted_blockstates_multiply_colourmap() {
+    let mut palette = palette();
+    palette
+        .blockstates
+        .insert("minecraft:sugar_cane".to_owned(), [128, 255, 255, 255]);
+    palette
+        .blockstates
+        .insert("minecraft:stone".to_owned(), [128, 255, 255, 255]);
+    palette
+        .tints
+        .insert("minecraft:sugar_cane".to_owned(), crate::Tint::Grass);
+
+    let plains = Some(Biome::Plains);
+    assert_eq!(
+        palette.pick(&block("minecraft:sugar_cane"), plains),
+        [25, 173, 0, 255]
+    );
+    assert_eq!(
+        palette.pick(&block("minecraft:stone"), plains),
+        [128, 255, 255, 255]
+    );
+}
+
+fn archive(files: &[(&str, &[u8])]) -> Vec<u8> {
+    let mut ar = tar::Builder::new(flate2::write::GzEncoder::new(vec![], Default::default()));
+    for (path, data) in files {
+        let mut header = tar::Header::new_gnu();
+        header.set_size(data.len() as u64);
+        header.set_cksum();
+        ar.append_data(&mut header, path, *data).unwrap();
+    }
+    ar.into_inner().unwrap().finish().unwrap()
+}
+
+#[test]
+fn newer_palette_version_fails_to_load() {
+    let data = archive(&[("version", b"3"), ("blockstates.json", b"{}")]);
+    let err = RenderedPalette::from_reader(data.as_slice()).err().unwrap();
+    assert_eq!(
+        err.to_string(),
+        "palette version 3 is newer than the supported version 2"
+    );
+
+    let data = archive(&[("version", b"two")]);
+    assert!(RenderedPalette::from_reader(data.as_slice()).is_err());
+}
+
+#[test]
+fn older_palette_version_fails_to_load() {
+    // No version file, from before tints were recorded.
+    let data = archive(&[(
+        "blockstates.json",
+        br#"{"minecraft:stone": [1, 2, 3, 255]}"#,
+    )]);
+    let err = RenderedPalette::from_reader(data.as_slice()).err().unwrap();
+    assert_eq!(
+        err.to_string(),
+        "palette version 1 is older than the supported version 2"
+    );
+}
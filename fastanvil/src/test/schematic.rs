@@ -0,0 +1,70 @@
+use fastnbt::{nbt, to_bytes, ByteArray};
+
+use crate::schematic::Schematic;
+
+fn schematic(block_data: Vec<u8>) -> Schematic {
+    let schem = nbt!({
+        "Version": 2,
+        "DataVersion": 3465,
+        "Width": 2_i16,
+        "Height": 2_i16,
+        "Length": 1_i16,
+        "PaletteMax": 2,
+        "Palette": {
+            "minecraft:air": 0,
+            "minecraft:oak_stairs[facing=east]": 200,
+        },
+        "BlockData": ByteArray::from_buf(block_data),
+    });
+
+    fastnbt::from_bytes(&to_bytes(&schem).unwrap()).unwrap()
+}
+
+#[test]
+fn block_at_decodes_varints() {
+    // 200 takes two bytes as a varint.
+    let schem = schematic(vec![0, 0xc8, 0x01, 0, 0xc8, 0x01]);
+
+    assert_eq!(schem.width, 2);
+    assert_eq!(schem.blocks(), [0, 200, 0, 200]);
+    assert_eq!(schem.block_at(0, 0, 0), Some("minecraft:air"));
+    assert_eq!(
+        schem.block_at(1, 0, 0),
+        Some("minecraft:oak_stairs[facing=east]")
+    );
+    assert_eq!(
+        schem.block_at(1, 1, 0),
+        Some("minecraft:oak_stairs[facing=east]")
+    );
+
+    // outside the schematic.
+    assert_eq!(schem.block_at(2, 0, 0), None);
+    assert_eq!(schem.block_at(0, 0, 1), None);
+}
+
+#[test]
+fn truncated_block_data() {
+    let schem = schematic(vec![0, 0xc8, 0x01, 0, 0xc8]);
+    assert_eq!(schem.blocks(), [0, 200, 0]);
+    assert_eq!(schem.block_at(1, 1, 0), None);
+}
+
+#[test]
+fn large_palette_ids() {
+    let schem = nbt!({
+        "Version": 2,
+        "Width": 1_i16,
+        "Height": 1_i16,
+        "Length": 2_i16,
+        "Palette": {
+            "minecraft:stone": 1,
+            "minecraft:dirt": i32::MAX,
+            "minecraft:bad": -1,
+        },
+        "BlockData": ByteArray::from_buf(vec![1, 0xff, 0xff, 0xff, 0xff, 0x07]),
+    });
+    let schem: Schematic = fastnbt::from_bytes(&to_bytes(&schem).unwrap()).unwrap();
+
+    assert_eq!(schem.block_at(0, 0, 0), Some("minecraft:stone"));
+    assert_eq!(schem.block_at(0, 0, 1), Some("minecraft:dirt"));
+}
@@ -32,6 +32,7 @@ fn iter_skips_padding() {
 fn min_bits() {
     let ideal = |n: usize| (n as f64).log2().ceil() as usize;
 
+    assert_eq!(0, min_bits_for_n_states(0));
     assert_eq!(0, min_bits_for_n_states(1));
     assert_eq!(1, min_bits_for_n_states(2));
     assert_eq!(2, min_bits_for_n_states(3));
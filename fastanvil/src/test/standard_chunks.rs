@@ -1352,3 +1352,21 @@ fn chunk_forge_1_20_1() {
     let chunk = JavaChunk::from_bytes(CHUNK_FORGE_1_20_1).unwrap();
     assert_eq!(expected, exercise_render(&chunk));
 }
+
+#[test]
+fn boxed_chunks_of_different_versions() {
+    let chunks: Vec<Box<dyn Chunk>> = [CHUNK_1_17_1, CHUNK_21W44A_1, CHUNK_FORGE_1_20_1]
+        .into_iter()
+        .map(|data| JavaChunk::from_bytes(data).unwrap().into_boxed())
+        .collect();
+
+    for (chunk, data) in chunks
+        .iter()
+        .zip([CHUNK_1_17_1, CHUNK_21W44A_1, CHUNK_FORGE_1_20_1])
+    {
+        let unboxed = JavaChunk::from_bytes(data).unwrap();
+        assert_eq!(chunk.status(), unboxed.status());
+        assert_eq!(chunk.y_range(), unboxed.y_range());
+        assert_eq!(exercise_render(chunk.as_ref()), exercise_render(&unboxed));
+    }
+}
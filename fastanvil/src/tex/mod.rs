@@ -28,10 +28,198 @@ pub enum Blockstate {
 
 #[derive(Deserialize, Debug, Clone)]
 pub struct Part {
-    //when: Option<serde_json::Value>,
+    pub when: Option<When>,
     pub apply: Variants,
 }
 
+/// Condition for a multipart [`Part`] to apply to a block.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(untagged)]
+pub enum When {
+    And {
+        #[serde(rename = "AND")]
+        and: Vec<When>,
+    },
+    Or {
+        #[serde(rename = "OR")]
+        or: Vec<When>,
+    },
+    /// Every property must have one of the given values. Several values are
+    /// separated with `|`, eg `"east": "low|tall"`.
+    Properties(HashMap<String, String>),
+}
+
+impl When {
+    /// Whether the block with the given properties meets this condition.
+    pub fn matches(&self, props: &HashMap<&str, &str>) -> bool {
+        match self {
+            When::And { and } => and.iter().all(|w| w.matches(props)),
+            When::Or { or } => or.iter().any(|w| w.matches(props)),
+            When::Properties(conds) => conds.iter().all(|(k, v)| {
+                props
+                    .get(k.as_str())
+                    .map(|actual| v.split('|').any(|v| v == *actual))
+                    .unwrap_or(false)
+            }),
+        }
+    }
+}
+
+/// Parse properties of the form "prop1=val1,prop2=val2".
+fn parse_props(encoded_props: &str) -> HashMap<&str, &str> {
+    encoded_props
+        .split(',')
+        .filter_map(|prop| prop.split_once('='))
+        .collect()
+}
+
+/// Draw `top` over `bottom`, blending by the alpha of `top`. If the textures
+/// are different sizes, such as when a resource pack only has high resolution
+/// versions of some textures, `top` is resized to match `bottom`.
+fn composite(bottom: &mut Texture, top: &Texture) {
+    let resized;
+    let top = match texture_size(bottom) {
+        Some(size) if top.len() != bottom.len() => {
+            resized = resize_texture(top, size);
+            &resized
+        }
+        _ => top,
+    };
+
+    for (b, t) in bottom.chunks_exact_mut(4).zip(top.chunks_exact(4)) {
+        let alpha = t[3] as u32;
+        for i in 0..3 {
+            b[i] = ((t[i] as u32 * alpha + b[i] as u32 * (255 - alpha)) / 255) as u8;
+        }
+        b[3] = b[3].max(t[3]);
+    }
+}
+
+/// The face of an unrotated model that ends up on top after rotating it `x`
+/// degrees around the x axis, and the number of clockwise quarter turns needed
+/// to line its texture up with the top of the block.
+fn top_face(x: usize) -> (&'static str, usize) {
+    match x % 360 {
+        90 => ("south", 0),
+        180 => ("down", 0),
+        270 => ("north", 2),
+        _ => ("up", 0),
+    }
+}
+
+/// How a [`Renderer`] picks a variant for blocks that have several, such as
+/// the rotated variants of grass and stone.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum VariantSelection {
+    /// Always use the first variant.
+    #[default]
+    First,
+
+    /// Pick a variant from a hash of the block position, so every block
+    /// rendered for the same position gets the same variant. This is
+    /// deterministic, but does not match the variant the game would show.
+    ByPositionHash { x: i32, z: i32 },
+}
+
+impl VariantSelection {
+    /// The variant to use out of the given variants, None if there are none.
+    fn select<'a>(&self, v: &'a Variants) -> Option<&'a Variant> {
+        let variants = match v {
+            Variants::Single(variant) => return Some(variant),
+            Variants::Many(variants) => variants,
+        };
+
+        match *self {
+            VariantSelection::First => variants.first(),
+            VariantSelection::ByPositionHash { x, z } => {
+                if variants.is_empty() {
+                    return None;
+                }
+                // Integer hash so neighbouring positions spread across the
+                // variants. This is not the game's selection, see above.
+                let seed = (x as i64).wrapping_mul(3129871) ^ (z as i64).wrapping_mul(116129781);
+                let seed = seed
+                    .wrapping_mul(seed)
+                    .wrapping_mul(42317861)
+                    .wrapping_add(seed.wrapping_mul(11));
+                let index = (seed >> 16).rem_euclid(variants.len() as i64);
+                variants.get(index as usize)
+            }
+        }
+    }
+}
+
+/// Rotate a square RGBA texture clockwise by some quarter turns, as seen from
+/// above. Textures that are not square, such as animated ones, are returned
+/// unchanged.
+fn rotate_texture(tex: Texture, quarter_turns: usize) -> Texture {
+    let turns = quarter_turns % 4;
+    let n = match texture_size(&tex) {
+        Some(n) if turns != 0 => n,
+        _ => return tex,
+    };
+
+    let mut rotated = vec![0; tex.len()];
+    for y in 0..n {
+        for x in 0..n {
+            // where the pixel at (x, y) in the rotated texture comes from.
+            let (sx, sy) = match turns {
+                1 => (y, n - 1 - x),
+                2 => (n - 1 - x, n - 1 - y),
+                _ => (n - 1 - y, x),
+            };
+            let dst = (y * n + x) * 4;
+            let src = (sy * n + sx) * 4;
+            rotated[dst..dst + 4].copy_from_slice(&tex[src..src + 4]);
+        }
+    }
+    rotated
+}
+
+/// The width and height of a square RGBA texture, or None if the texture is
+/// not square.
+pub fn texture_size(tex: &[u8]) -> Option<usize> {
+    let n = ((tex.len() / 4) as f64).sqrt() as usize;
+    (n * n * 4 == tex.len()).then_some(n)
+}
+
+/// Resize a square RGBA texture to `size` by `size` pixels. Each pixel of the
+/// result is the average of the pixels it covers, so shrinking a 32x32
+/// texture to 16x16 averages each 2x2 block. Textures that are not square
+/// are returned unchanged.
+pub fn resize_texture(tex: &[u8], size: usize) -> Texture {
+    let n = match texture_size(tex) {
+        Some(n) if n != size && n != 0 => n,
+        _ => return tex.to_vec(),
+    };
+
+    // the range of source pixels covering destination pixel i. When growing
+    // a texture this is always a single pixel.
+    let span = |i: usize| {
+        let start = i * n / size;
+        start..((i + 1) * n / size).max(start + 1)
+    };
+
+    let mut resized = Vec::with_capacity(size * size * 4);
+    for y in 0..size {
+        for x in 0..size {
+            let mut sum = [0u32; 4];
+            let mut count = 0;
+            for sy in span(y) {
+                for sx in span(x) {
+                    let src = (sy * n + sx) * 4;
+                    for i in 0..4 {
+                        sum[i] += tex[src + i] as u32;
+                    }
+                    count += 1;
+                }
+            }
+            resized.extend(sum.map(|c| (c / count) as u8));
+        }
+    }
+    resized
+}
+
 #[derive(Deserialize, Debug, Clone)]
 pub struct Model {
     pub parent: Option<String>,
@@ -47,14 +235,110 @@ pub struct Element {
     pub rotation: Option<Rotation>,
 }
 
+impl Element {
+    /// The face of this element that ends up facing the direction of the face
+    /// `face_name` once the element's rotation is applied, and the number of
+    /// clockwise quarter turns needed to line its texture up with that face.
+    fn face_towards(&self, face_name: &str) -> Option<(&Face, usize)> {
+        let target = face_direction(face_name)?;
+        let rotate = |v| match &self.rotation {
+            Some(rotation) => rotation.apply(v),
+            None => v,
+        };
+
+        self.faces.iter().find_map(|(name, face)| {
+            if rotate(face_direction(name)?) != target {
+                return None;
+            }
+
+            // Turn the texture clockwise, as seen looking at the face, until
+            // its top lines up with where the rotation put the top.
+            let inward = target.map(|c| -c);
+            let rotated_up = rotate(texture_up(name));
+            let mut up = texture_up(face_name);
+            for turns in 0..4 {
+                if up == rotated_up {
+                    return Some((face, turns));
+                }
+                up = quarter_turn(up, inward);
+            }
+            None
+        })
+    }
+}
+
+/// An axis of a model.
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Axis {
+    X,
+    Y,
+    Z,
+}
+
+/// The rotation of a model element around a single axis.
 #[derive(Deserialize, Debug, Clone)]
-#[allow(dead_code)]
 pub struct Rotation {
-    origin: Vec<f32>,
-    axis: String,
-    angle: f32,
+    pub origin: Vec<f32>,
+    pub axis: Axis,
+    /// Angle in degrees, counter-clockwise looking down the axis towards the
+    /// origin. Vanilla models only use multiples of 22.5 up to 45 degrees.
+    pub angle: f32,
     #[serde(default)]
-    rescale: bool,
+    pub rescale: bool,
+}
+
+impl Rotation {
+    /// The angle snapped to a whole number of counter-clockwise quarter
+    /// turns, from 0 to 3. Angles of 45 degrees or less snap to no turn, so
+    /// vanilla rotations never change which face is on top.
+    pub fn quarter_turns(&self) -> usize {
+        let turns = (self.angle.abs() / 90.0 - 0.5).ceil().max(0.0) as i32;
+        (turns * self.angle.signum() as i32).rem_euclid(4) as usize
+    }
+
+    /// Apply the snapped rotation to a direction.
+    fn apply(&self, v: [i32; 3]) -> [i32; 3] {
+        let axis = match self.axis {
+            Axis::X => [1, 0, 0],
+            Axis::Y => [0, 1, 0],
+            Axis::Z => [0, 0, 1],
+        };
+        (0..self.quarter_turns()).fold(v, |v, _| quarter_turn(v, axis))
+    }
+}
+
+/// The outward direction of a face, with y up and z south.
+fn face_direction(face_name: &str) -> Option<[i32; 3]> {
+    match face_name {
+        "up" => Some([0, 1, 0]),
+        "down" => Some([0, -1, 0]),
+        "north" => Some([0, 0, -1]),
+        "south" => Some([0, 0, 1]),
+        "west" => Some([-1, 0, 0]),
+        "east" => Some([1, 0, 0]),
+        _ => None,
+    }
+}
+
+/// The direction the top of a face's texture points.
+fn texture_up(face_name: &str) -> [i32; 3] {
+    match face_name {
+        "up" => [0, 0, -1],
+        "down" => [0, 0, 1],
+        _ => [0, 1, 0],
+    }
+}
+
+/// Rotate a direction a quarter turn counter-clockwise around `axis`, looking
+/// down the axis towards the origin.
+fn quarter_turn(v: [i32; 3], axis: [i32; 3]) -> [i32; 3] {
+    let dot = axis[0] * v[0] + axis[1] * v[1] + axis[2] * v[2];
+    [
+        axis[1] * v[2] - axis[2] * v[1] + dot * axis[0],
+        axis[2] * v[0] - axis[0] * v[2] + dot * axis[1],
+        axis[0] * v[1] - axis[1] * v[0] + dot * axis[2],
+    ]
 }
 
 #[derive(Deserialize, Debug, Clone)]
@@ -62,9 +346,12 @@ pub struct Rotation {
 pub struct Face {
     texture: String,
     uv: Option<[f32; 4]>,
+    tintindex: Option<i32>,
 }
 
-pub type Texture = Vec<u8>; // RGBA 16x16 image.
+/// RGBA image data, row by row. Vanilla block textures are 16x16, but
+/// resource packs can have square textures of any size.
+pub type Texture = Vec<u8>;
 
 #[derive(Debug)]
 pub enum Error {
@@ -131,13 +418,54 @@ fn merge_models(child: &Model, mut parent: Model) -> Result<Model> {
 
 pub type Result<T> = std::result::Result<T, Error>;
 
+/// Average colour of RGBA pixel data as seen from a distance.
+///
+/// Colours are weighted by their alpha so that transparent pixels do not
+/// darken the result, and the alpha is the average coverage of the pixels.
+/// Fully transparent data gives `[0, 0, 0, 0]`.
+pub fn average_colour(rgba_data: &[u8]) -> [u8; 4] {
+    let mut sum = [0f64; 3];
+    let mut alpha = 0f64;
+    let mut count = 0;
+
+    for p in rgba_data.chunks_exact(4) {
+        let a = p[3] as f64;
+        for i in 0..3 {
+            sum[i] += a * (p[i] as f64) * (p[i] as f64);
+        }
+        alpha += a;
+        count += 1;
+    }
+
+    if alpha == 0. {
+        return [0, 0, 0, 0];
+    }
+
+    [
+        (sum[0] / alpha).sqrt() as u8,
+        (sum[1] / alpha).sqrt() as u8,
+        (sum[2] / alpha).sqrt() as u8,
+        (alpha / count as f64).round() as u8,
+    ]
+}
+
 pub trait Render {
     fn get_top(&mut self, id: &str, encoded_props: &str) -> Result<Texture>;
+
+    /// The colour of the block when looking straight down on it.
+    ///
+    /// The alpha of the top face is kept, so glass and leaves come out
+    /// partially transparent and can be blended with the blocks below them
+    /// when drawing a map.
+    fn get_top_rgba(&mut self, id: &str, encoded_props: &str) -> Result<[u8; 4]> {
+        Ok(average_colour(&self.get_top(id, encoded_props)?))
+    }
 }
 pub struct Renderer {
     blockstates: HashMap<String, Blockstate>,
     models: HashMap<String, Model>,
     textures: HashMap<String, Texture>,
+    variant_selection: VariantSelection,
 }
 
 impl Renderer {
@@ -150,22 +478,26 @@ impl Renderer {
             blockstates,
             models,
             textures,
+            variant_selection: VariantSelection::First,
         }
     }
 
-    fn model_get_top(&self, id: &str, encoded_props: &str, model_name: &str) -> Result<Texture> {
+    /// Set how to pick between variants for blocks that have several. The
+    /// default is [`VariantSelection::First`].
+    pub fn set_variant_selection(&mut self, selection: VariantSelection) {
+        self.variant_selection = selection;
+    }
+
+    fn model_get_top(&self, id: &str, encoded_props: &str, variant: &Variant) -> Result<Texture> {
+        let model_name = &variant.model;
         let model = self.flatten_model(model_name)?;
-        // Look at elements. Try just looking in the first one for 'up'.
 
-        let els = &model.elements.ok_or_else(|| {
-            Error::MissingElements(
-                id.to_owned(),
-                encoded_props.to_owned(),
-                model_name.to_owned(),
-            )
-        })?;
+        // Rotating the model around the x axis changes which face ends up on
+        // top. Take the face from the first element that has it, after
+        // rotating the element itself.
+        let (face_name, x_turns) = top_face(variant.x.unwrap_or(0));
 
-        let el = els.get(0).ok_or_else(|| {
+        let els = model.elements.as_ref().ok_or_else(|| {
             Error::MissingElements(
                 id.to_owned(),
                 encoded_props.to_owned(),
@@ -173,36 +505,180 @@ impl Renderer {
             )
         })?;
 
-        let face = el.faces.get("up").ok_or_else(|| {
-            Error::MissingElements(
+        let (face, element_turns) = els
+            .iter()
+            .find_map(|el| el.face_towards(face_name))
+            .ok_or_else(|| {
+                Error::MissingElements(
+                    id.to_owned(),
+                    encoded_props.to_owned(),
+                    model_name.to_owned(),
+                )
+            })?;
+
+        let tex = self.face_texture(id, encoded_props, model_name, &model, face)?;
+        let tex = rotate_texture(tex, element_turns);
+
+        // With uvlock the texture stays aligned to the world rather than
+        // turning with the model.
+        if variant.uvlock.unwrap_or(false) {
+            return Ok(tex);
+        }
+
+        let y_turns = variant.y.unwrap_or(0) / 90;
+        Ok(rotate_texture(tex, x_turns + y_turns))
+    }
+
+    /// Look up the texture of a face, resolving any texture variable against
+    /// the flattened model.
+    fn face_texture(
+        &self,
+        id: &str,
+        encoded_props: &str,
+        model_name: &str,
+        model: &Model,
+        face: &Face,
+    ) -> Result<Texture> {
+        let tex = &face.texture;
+
+        let tex = match tex.strip_prefix('#') {
+            Some(rest) => model
+                .textures
+                .as_ref()
+                .ok_or(Error::MissingModelTextures)?
+                .get(rest) // we just checked with 'starts_with'.
+                .ok_or_else(|| {
+                    Error::MissingTextureVariable(
+                        id.to_owned(),
+                        encoded_props.to_owned(),
+                        model_name.to_owned(),
+                        (*tex).clone(),
+                    )
+                })?,
+            None => tex,
+        };
+
+        self.extract_texture(tex)
+    }
+
+    /// The pixels of every face of every element in the model, one texture
+    /// after the other. Used for models with no top face, such as the crossed
+    /// planes of flowers and saplings.
+    fn model_get_all_faces(
+        &self,
+        id: &str,
+        encoded_props: &str,
+        variant: &Variant,
+    ) -> Result<Texture> {
+        let model_name = &variant.model;
+        let model = self.flatten_model(model_name)?;
+
+        let mut pixels = vec![];
+        for face in model
+            .elements
+            .iter()
+            .flatten()
+            .flat_map(|el| el.faces.values())
+        {
+            pixels.extend(self.face_texture(id, encoded_props, model_name, &model, face)?);
+        }
+
+        if pixels.is_empty() {
+            return Err(Error::MissingElements(
                 id.to_owned(),
                 encoded_props.to_owned(),
                 model_name.to_owned(),
-            )
-        })?;
+            ));
+        }
 
-        let tex = &face.texture;
+        Ok(pixels)
+    }
 
-        let tex = match tex.strip_prefix('#') {
-            Some(rest) => {
-                model
-                    .textures
-                    .ok_or(Error::MissingModelTextures)?
-                    .get(rest) // we just checked with 'starts_with'.
-                    .ok_or_else(|| {
-                        Error::MissingTextureVariable(
-                            id.to_owned(),
-                            encoded_props.to_owned(),
-                            model_name.to_owned(),
-                            (*tex).clone(),
-                        )
-                    })?
-                    .clone()
+    /// Whether the faces of the block seen from above are tinted by the
+    /// biome, ie they have a `tintindex`. The model does not say which
+    /// colourmap to tint with, that is decided by the game per block.
+    pub fn is_tinted(&self, id: &str, encoded_props: &str) -> Result<bool> {
+        for variant in self.applied_variants(id, encoded_props)? {
+            let model = self.flatten_model(&variant.model)?;
+            let (face_name, _) = top_face(variant.x.unwrap_or(0));
+            let faces = || model.elements.iter().flatten().flat_map(|el| el.faces.iter());
+
+            // Like `get_top_rgba`, use every face if none are on top.
+            let has_top = faces().any(|(name, _)| name == face_name);
+            let tinted = faces()
+                .filter(|(name, _)| !has_top || *name == face_name)
+                .any(|(_, face)| face.tintindex.is_some());
+
+            if tinted {
+                return Ok(true);
             }
-            None => (*tex).clone(),
-        };
+        }
+
+        Ok(false)
+    }
+
+    /// The variants that make up the block, one per matching multipart part.
+    fn applied_variants(&self, id: &str, encoded_props: &str) -> Result<Vec<&Variant>> {
+        let bs = self
+            .blockstates
+            .get(id)
+            .ok_or_else(|| Error::MissingBlockstate(id.to_string()))?;
+
+        match bs {
+            Blockstate::Variants(variants) => {
+                let v = variants.get(encoded_props).ok_or_else(|| {
+                    Error::MissingVariant(id.to_string(), encoded_props.to_string())
+                })?;
+                Ok(self.variant_selection.select(v).into_iter().collect())
+            }
+            Blockstate::Multipart(parts) => {
+                let props = parse_props(encoded_props);
+                Ok(parts
+                    .iter()
+                    .filter(|part| match &part.when {
+                        Some(when) => when.matches(&props),
+                        None => true,
+                    })
+                    .filter_map(|part| self.variant_selection.select(&part.apply))
+                    .collect())
+            }
+        }
+    }
+
+    fn variants_get_top(&self, id: &str, encoded_props: &str, v: &Variants) -> Result<Texture> {
+        let variant = self
+            .variant_selection
+            .select(v)
+            .ok_or_else(|| Error::MissingVariant(id.to_string(), encoded_props.to_string()))?;
+        self.model_get_top(id, encoded_props, variant)
+    }
 
-        self.extract_texture(&tex)
+    fn multipart_get_top(&self, id: &str, encoded_props: &str, parts: &[Part]) -> Result<Texture> {
+        let props = parse_props(encoded_props);
+        let mut top: Option<Texture> = None;
+        let mut last_err = None;
+
+        let applied = parts.iter().filter(|part| match &part.when {
+            Some(when) => when.matches(&props),
+            None => true,
+        });
+
+        for part in applied {
+            // Some parts have no top face, such as the sides of a fence. These
+            // are not visible from above so are skipped.
+            match self.variants_get_top(id, encoded_props, &part.apply) {
+                Ok(tex) => match top {
+                    Some(ref mut top) => composite(top, &tex),
+                    None => top = Some(tex),
+                },
+                Err(e) => last_err = Some(e),
+            }
+        }
+
+        top.ok_or_else(|| {
+            last_err
+                .unwrap_or_else(|| Error::MissingVariant(id.to_string(), encoded_props.to_string()))
+        })
     }
 
     fn get_model(&self, model: &str) -> Result<&Model> {
@@ -256,21 +732,26 @@ impl Render for Renderer {
                     Error::MissingVariant(id.to_string(), encoded_props.to_string())
                 })?;
 
-                match v {
-                    Variants::Single(variant) => {
-                        let model_name = &variant.model;
-                        self.model_get_top(id, encoded_props, model_name)
-                    }
-                    Variants::Many(variants) => {
-                        // TODO: Should probably actually pick one at random or
-                        // something. How does the game actually decide which
-                        // variant to use?
-                        let model_name = &variants[0].model;
-                        self.model_get_top(id, encoded_props, model_name)
-                    }
+                self.variants_get_top(id, encoded_props, v)
+            }
+            // Block is made up of parts that apply depending on its properties.
+            Blockstate::Multipart(parts) => self.multipart_get_top(id, encoded_props, parts),
+        }
+    }
+
+    fn get_top_rgba(&mut self, id: &str, encoded_props: &str) -> Result<[u8; 4]> {
+        match self.get_top(id, encoded_props) {
+            Ok(tex) => Ok(average_colour(&tex)),
+            // Plants are drawn as crossed planes with no top face. From above
+            // we see a bit of every plane, so use the average of all of them.
+            Err(Error::MissingElements(..)) => {
+                let mut pixels = vec![];
+                for variant in self.applied_variants(id, encoded_props)? {
+                    pixels.extend(self.model_get_all_faces(id, encoded_props, variant)?);
                 }
+                Ok(average_colour(&pixels))
             }
-            Blockstate::Multipart(_) => Err(Error::Unsupported),
+            Err(e) => Err(e),
         }
     }
 }
@@ -299,3 +299,479 @@ fn stairs() {
 
     assert_eq!(tex, acacia_planks_texture());
 }
+
+fn part_model(texture: &str) -> Model {
+    serde_json::from_str(&format!(
+        r##"
+        {{
+            "textures": {{ "top": "{texture}" }},
+            "elements": [
+                {{   "from": [ 0, 0, 0 ],
+                    "to": [ 16, 16, 16 ],
+                    "faces": {{ "up": {{ "texture": "#top" }} }}
+                }}
+            ]
+        }}
+        "##
+    ))
+    .unwrap()
+}
+
+fn multipart_blockstate() -> Blockstate {
+    serde_json::from_str(
+        r#"
+        {
+            "multipart": [
+                { "apply": { "model": "minecraft:block/post" } },
+                {
+                    "when": { "north": "true" },
+                    "apply": { "model": "minecraft:block/side" }
+                },
+                {
+                    "when": { "OR": [ { "east": "low|tall" }, { "AND": [ { "up": "true" }, { "west": "true" } ] } ] },
+                    "apply": { "model": "minecraft:block/extra" }
+                }
+            ]
+        }
+        "#,
+    )
+    .unwrap()
+}
+
+fn multipart_renderer() -> Renderer {
+    let blockstates = vec![("minecraft:wall".to_owned(), multipart_blockstate())]
+        .into_iter()
+        .collect();
+
+    let models = vec![
+        ("minecraft:block/post".to_owned(), part_model("block/post")),
+        ("minecraft:block/side".to_owned(), part_model("block/side")),
+        (
+            "minecraft:block/extra".to_owned(),
+            part_model("block/extra"),
+        ),
+    ]
+    .into_iter()
+    .collect();
+
+    let textures = vec![
+        ("minecraft:block/post".to_owned(), vec![100, 100, 100, 255]),
+        // half transparent, so should blend with the post.
+        ("minecraft:block/side".to_owned(), vec![200, 0, 0, 128]),
+        ("minecraft:block/extra".to_owned(), vec![0, 0, 50, 255]),
+    ]
+    .into_iter()
+    .collect();
+
+    Renderer::new(blockstates, models, textures)
+}
+
+#[test]
+fn multipart_without_conditions_met_uses_unconditional_parts() {
+    let mut renderer = multipart_renderer();
+    let tex = renderer
+        .get_top("minecraft:wall", "east=none,north=false")
+        .unwrap();
+    assert_eq!(tex, vec![100, 100, 100, 255]);
+}
+
+#[test]
+fn multipart_composites_matching_parts() {
+    let mut renderer = multipart_renderer();
+    let tex = renderer
+        .get_top("minecraft:wall", "east=none,north=true")
+        .unwrap();
+    assert_eq!(tex, vec![150, 49, 49, 255]);
+}
+
+#[test]
+fn multipart_or_and_conditions() {
+    let mut renderer = multipart_renderer();
+
+    let tex = renderer.get_top("minecraft:wall", "east=tall").unwrap();
+    assert_eq!(tex, vec![0, 0, 50, 255]);
+
+    let tex = renderer
+        .get_top("minecraft:wall", "up=true,west=true")
+        .unwrap();
+    assert_eq!(tex, vec![0, 0, 50, 255]);
+
+    let tex = renderer
+        .get_top("minecraft:wall", "up=true,west=false")
+        .unwrap();
+    assert_eq!(tex, vec![100, 100, 100, 255]);
+}
+
+#[test]
+fn when_matches() {
+    let when: When = serde_json::from_str(r#"{ "north": "true", "east": "low|tall" }"#).unwrap();
+
+    assert!(when.matches(&[("north", "true"), ("east", "low")].into_iter().collect()));
+    assert!(!when.matches(&[("north", "true"), ("east", "none")].into_iter().collect()));
+    assert!(!when.matches(&[("north", "true")].into_iter().collect()));
+}
+
+// A 2x2 texture, each pixel a different colour. Only the red channel is set.
+fn quad_texture(a: u8, b: u8, c: u8, d: u8) -> Texture {
+    vec![a, 0, 0, 255, b, 0, 0, 255, c, 0, 0, 255, d, 0, 0, 255]
+}
+
+fn rotated_renderer() -> Renderer {
+    let blockstate = serde_json::from_str(
+        r#"
+        {
+            "variants": {
+                "r=none": { "model": "minecraft:block/faces" },
+                "r=y90": { "model": "minecraft:block/faces", "y": 90 },
+                "r=y180": { "model": "minecraft:block/faces", "y": 180 },
+                "r=y90lock": { "model": "minecraft:block/faces", "y": 90, "uvlock": true },
+                "r=x90": { "model": "minecraft:block/faces", "x": 90 },
+                "r=x180": { "model": "minecraft:block/faces", "x": 180 },
+                "r=x270": { "model": "minecraft:block/faces", "x": 270 }
+            }
+        }
+        "#,
+    )
+    .unwrap();
+
+    let model = serde_json::from_str(
+        r##"
+        {
+            "elements": [
+                {   "from": [ 0, 0, 0 ],
+                    "to": [ 16, 16, 16 ],
+                    "faces": {
+                        "up":    { "texture": "block/up" },
+                        "down":  { "texture": "block/down" },
+                        "north": { "texture": "block/north" },
+                        "south": { "texture": "block/south" }
+                    }
+                }
+            ]
+        }
+        "##,
+    )
+    .unwrap();
+
+    let blockstates = vec![("minecraft:rotated".to_owned(), blockstate)]
+        .into_iter()
+        .collect();
+    let models = vec![("minecraft:block/faces".to_owned(), model)]
+        .into_iter()
+        .collect();
+    let textures = vec![
+        ("minecraft:block/up".to_owned(), quad_texture(1, 2, 3, 4)),
+        ("minecraft:block/down".to_owned(), quad_texture(5, 5, 5, 5)),
+        ("minecraft:block/north".to_owned(), quad_texture(6, 7, 8, 9)),
+        (
+            "minecraft:block/south".to_owned(),
+            quad_texture(10, 10, 10, 10),
+        ),
+    ]
+    .into_iter()
+    .collect();
+
+    Renderer::new(blockstates, models, textures)
+}
+
+#[test]
+fn y_rotation_rotates_top_texture() {
+    let mut renderer = rotated_renderer();
+
+    let tex = renderer.get_top("minecraft:rotated", "r=none").unwrap();
+    assert_eq!(tex, quad_texture(1, 2, 3, 4));
+
+    // 1 2    3 1
+    // 3 4 -> 4 2
+    let tex = renderer.get_top("minecraft:rotated", "r=y90").unwrap();
+    assert_eq!(tex, quad_texture(3, 1, 4, 2));
+
+    let tex = renderer.get_top("minecraft:rotated", "r=y180").unwrap();
+    assert_eq!(tex, quad_texture(4, 3, 2, 1));
+}
+
+#[test]
+fn uvlock_keeps_top_texture_aligned() {
+    let mut renderer = rotated_renderer();
+    let tex = renderer.get_top("minecraft:rotated", "r=y90lock").unwrap();
+    assert_eq!(tex, quad_texture(1, 2, 3, 4));
+}
+
+#[test]
+fn x_rotation_changes_top_face() {
+    let mut renderer = rotated_renderer();
+
+    let tex = renderer.get_top("minecraft:rotated", "r=x90").unwrap();
+    assert_eq!(tex, quad_texture(10, 10, 10, 10));
+
+    let tex = renderer.get_top("minecraft:rotated", "r=x180").unwrap();
+    assert_eq!(tex, quad_texture(5, 5, 5, 5));
+
+    // the north face ends up upside down on top.
+    let tex = renderer.get_top("minecraft:rotated", "r=x270").unwrap();
+    assert_eq!(tex, quad_texture(9, 8, 7, 6));
+}
+
+fn plant_renderer() -> Renderer {
+    let blockstates = vec![
+        (
+            "minecraft:fern".to_owned(),
+            serde_json::from_str(r#"{ "variants": { "": { "model": "minecraft:block/fern" } } }"#)
+                .unwrap(),
+        ),
+        (
+            "minecraft:glass".to_owned(),
+            serde_json::from_str(r#"{ "variants": { "": { "model": "minecraft:block/glass" } } }"#)
+                .unwrap(),
+        ),
+    ]
+    .into_iter()
+    .collect();
+
+    // Like the vanilla cross model, two planes with only side faces.
+    let cross = serde_json::from_str(
+        r##"
+        {
+            "textures": { "cross": "block/fern" },
+            "elements": [
+                {   "from": [ 0.8, 0, 8 ],
+                    "to": [ 15.2, 16, 8 ],
+                    "faces": {
+                        "north": { "texture": "#cross", "tintindex": 0 },
+                        "south": { "texture": "#cross", "tintindex": 0 }
+                    }
+                },
+                {   "from": [ 8, 0, 0.8 ],
+                    "to": [ 8, 16, 15.2 ],
+                    "faces": {
+                        "west": { "texture": "#cross" },
+                        "east": { "texture": "#cross" }
+                    }
+                }
+            ]
+        }
+        "##,
+    )
+    .unwrap();
+
+    let models = vec![
+        ("minecraft:block/fern".to_owned(), cross),
+        (
+            "minecraft:block/glass".to_owned(),
+            part_model("block/glass"),
+        ),
+    ]
+    .into_iter()
+    .collect();
+
+    let textures = vec![
+        // half the pixels are transparent, and have a colour that should be
+        // ignored.
+        (
+            "minecraft:block/fern".to_owned(),
+            vec![
+                200, 0, 0, 255, 0, 200, 0, 255, 255, 255, 255, 0, 255, 255, 255, 0,
+            ],
+        ),
+        (
+            "minecraft:block/glass".to_owned(),
+            vec![100, 100, 100, 255, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0],
+        ),
+    ]
+    .into_iter()
+    .collect();
+
+    Renderer::new(blockstates, models, textures)
+}
+
+#[test]
+fn top_rgba_averages_cross_models() {
+    let mut renderer = plant_renderer();
+
+    assert!(matches!(
+        renderer.get_top("minecraft:fern", ""),
+        Err(Error::MissingElements(..))
+    ));
+
+    let col = renderer.get_top_rgba("minecraft:fern", "").unwrap();
+    assert_eq!(col, [141, 141, 0, 128]);
+}
+
+#[test]
+fn top_rgba_keeps_alpha_of_top_face() {
+    let mut renderer = plant_renderer();
+
+    let col = renderer.get_top_rgba("minecraft:glass", "").unwrap();
+    assert_eq!(col, [100, 100, 100, 64]);
+}
+
+#[test]
+fn average_colour_ignores_transparent_pixels() {
+    assert_eq!(
+        average_colour(&[10, 20, 30, 0, 40, 50, 60, 0]),
+        [0, 0, 0, 0]
+    );
+    assert_eq!(
+        average_colour(&[10, 20, 30, 255, 90, 80, 70, 0]),
+        [10, 20, 30, 128]
+    );
+}
+
+#[test]
+fn tinted_faces() {
+    let renderer = plant_renderer();
+    assert!(renderer.is_tinted("minecraft:fern", "").unwrap());
+    assert!(!renderer.is_tinted("minecraft:glass", "").unwrap());
+}
+
+#[test]
+fn resize_texture_averages_and_repeats() {
+    let tex = quad_texture(10, 20, 30, 40);
+    assert_eq!(texture_size(&tex), Some(2));
+    assert_eq!(texture_size(&tex[..12]), None);
+
+    assert_eq!(resize_texture(&tex, 1), vec![25, 0, 0, 255]);
+    assert_eq!(resize_texture(&tex, 2), tex);
+
+    let grown = resize_texture(&[10, 0, 0, 255], 2);
+    assert_eq!(grown, quad_texture(10, 10, 10, 10));
+
+    // not square, so unchanged.
+    assert_eq!(resize_texture(&tex[..12], 1), tex[..12].to_vec());
+}
+
+#[test]
+fn composite_textures_of_different_sizes() {
+    let mut bottom = vec![0, 0, 0, 255];
+    let top = vec![
+        200, 0, 0, 255, 200, 0, 0, 255, //
+        200, 0, 0, 255, 200, 0, 0, 255,
+    ];
+    composite(&mut bottom, &top);
+    assert_eq!(bottom, vec![200, 0, 0, 255]);
+}
+
+#[test]
+fn average_colour_does_not_depend_on_size() {
+    let small = [[30, 60, 90, 255]; 16 * 16].concat();
+    let large = [[30, 60, 90, 255]; 32 * 32].concat();
+    assert_eq!(average_colour(&small), average_colour(&large));
+}
+
+#[test]
+fn variant_selection() {
+    let mut renderer = rotated_renderer();
+    let blockstate = serde_json::from_str(
+        r#"
+        {
+            "variants": {
+                "": [
+                    { "model": "minecraft:block/faces" },
+                    { "model": "minecraft:block/faces", "y": 90 },
+                    { "model": "minecraft:block/faces", "y": 180 },
+                    { "model": "minecraft:block/faces", "y": 270 }
+                ]
+            }
+        }
+        "#,
+    )
+    .unwrap();
+    renderer
+        .blockstates
+        .insert("minecraft:stone".to_owned(), blockstate);
+
+    let first = renderer.get_top("minecraft:stone", "").unwrap();
+    assert_eq!(first, quad_texture(1, 2, 3, 4));
+
+    let mut picked = vec![];
+    for x in 0..8 {
+        renderer.set_variant_selection(VariantSelection::ByPositionHash { x, z: 3 });
+        let tex = renderer.get_top("minecraft:stone", "").unwrap();
+
+        // the same position always gets the same variant.
+        assert_eq!(tex, renderer.get_top("minecraft:stone", "").unwrap());
+        picked.push(tex);
+    }
+
+    // different positions do not all get the same variant.
+    assert!(picked.iter().any(|tex| *tex != picked[0]));
+}
+
+#[test]
+fn empty_variant_list_errors() {
+    let mut renderer = rotated_renderer();
+    let blockstate = serde_json::from_str(r#"{ "variants": { "": [] } }"#).unwrap();
+    renderer
+        .blockstates
+        .insert("minecraft:stone".to_owned(), blockstate);
+
+    assert!(matches!(
+        renderer.get_top("minecraft:stone", ""),
+        Err(Error::MissingVariant(..))
+    ));
+}
+
+fn element_rotation_renderer(axis: &str, angle: f32) -> Renderer {
+    let mut renderer = rotated_renderer();
+    let model = serde_json::from_str(&format!(
+        r##"
+        {{
+            "elements": [
+                {{  "from": [ 0, 0, 0 ],
+                    "to": [ 16, 16, 16 ],
+                    "rotation": {{ "origin": [ 8, 8, 8 ], "axis": "{axis}", "angle": {angle} }},
+                    "faces": {{
+                        "up":    {{ "texture": "block/up" }},
+                        "north": {{ "texture": "block/north" }},
+                        "east":  {{ "texture": "block/north" }}
+                    }}
+                }}
+            ]
+        }}
+        "##
+    ))
+    .unwrap();
+    renderer
+        .models
+        .insert("minecraft:block/faces".to_owned(), model);
+    renderer
+}
+
+#[test]
+fn element_rotation_snaps_to_quarter_turns() {
+    let rotation = |angle| Rotation {
+        origin: vec![8.0; 3],
+        axis: Axis::Y,
+        angle,
+        rescale: false,
+    };
+    assert_eq!(rotation(0.0).quarter_turns(), 0);
+    assert_eq!(rotation(45.0).quarter_turns(), 0);
+    assert_eq!(rotation(-22.5).quarter_turns(), 0);
+    assert_eq!(rotation(90.0).quarter_turns(), 1);
+    assert_eq!(rotation(-90.0).quarter_turns(), 3);
+    assert_eq!(rotation(180.0).quarter_turns(), 2);
+}
+
+#[test]
+fn element_rotation_changes_top_face() {
+    // vanilla angles leave the top alone.
+    let mut renderer = element_rotation_renderer("z", 45.0);
+    let tex = renderer.get_top("minecraft:rotated", "r=none").unwrap();
+    assert_eq!(tex, quad_texture(1, 2, 3, 4));
+
+    // the east face rolls over to the top, turned anticlockwise.
+    let mut renderer = element_rotation_renderer("z", 90.0);
+    let tex = renderer.get_top("minecraft:rotated", "r=none").unwrap();
+    assert_eq!(tex, quad_texture(7, 9, 6, 8));
+
+    // the north face tips back onto the top, upside down.
+    let mut renderer = element_rotation_renderer("x", 90.0);
+    let tex = renderer.get_top("minecraft:rotated", "r=none").unwrap();
+    assert_eq!(tex, quad_texture(9, 8, 7, 6));
+
+    // turning around y turns the top texture anticlockwise.
+    let mut renderer = element_rotation_renderer("y", 90.0);
+    let tex = renderer.get_top("minecraft:rotated", "r=none").unwrap();
+    assert_eq!(tex, quad_texture(2, 4, 1, 3));
+}
@@ -78,8 +78,8 @@ impl ByteArray {
         self.data
     }
 
-    /// Produce a ByteArray from raw data.
-    pub(crate) fn from_bytes(data: &[u8]) -> Self {
+    /// Produce a ByteArray from raw data, treating each byte as signed.
+    pub fn from_bytes(data: &[u8]) -> Self {
         // Safe to treat [u8] as [i8].
         let data = unsafe { &*(data as *const [u8] as *const [i8]) };
         ByteArray {
@@ -87,21 +87,58 @@ impl ByteArray {
         }
     }
 
-    /// Produce a ByteArray from raw data.
-    pub(crate) fn from_buf(data: Vec<u8>) -> Self {
-        // TODO: Remove copy.
-        let data = data.as_slice();
-
-        // Safe to treat [u8] as [i8].
-        let data = unsafe { &*(data as *const [u8] as *const [i8]) };
+    /// Produce a ByteArray from raw data, treating each byte as signed. This
+    /// is the reverse of [`to_bytes`][`ByteArray::to_bytes`].
+    ///
+    /// ```
+    /// # use fastnbt::ByteArray;
+    /// let arr = ByteArray::from_buf(vec![1, 255]);
+    /// assert_eq!(*arr, [1, -1]);
+    /// assert_eq!(arr.to_bytes(), [1, 255]);
+    /// ```
+    pub fn from_buf(data: Vec<u8>) -> Self {
+        // Reuses the allocation of data.
         ByteArray {
-            data: data.to_owned(),
+            data: data.into_iter().map(|b| b as i8).collect(),
         }
     }
 
-    pub(crate) fn to_bytes(&self) -> Vec<u8> {
+    /// The data as unsigned bytes. This is the reverse of
+    /// [`from_buf`][`ByteArray::from_buf`].
+    pub fn to_bytes(&self) -> Vec<u8> {
         self.data.iter().flat_map(|i| i.to_be_bytes()).collect()
     }
+
+    /// View the data as unsigned bytes without copying.
+    ///
+    /// ```
+    /// # use fastnbt::ByteArray;
+    /// let arr = ByteArray::new(vec![1, -1]);
+    /// assert_eq!(arr.as_u8_slice(), [1, 255]);
+    /// ```
+    pub fn as_u8_slice(&self) -> &[u8] {
+        // Safe to treat [i8] as [u8].
+        unsafe { &*(self.data.as_slice() as *const [i8] as *const [u8]) }
+    }
+
+    /// The data as booleans, where any non-zero byte is true. Useful for byte
+    /// arrays that are really flags.
+    pub fn as_bool_vec(&self) -> Vec<bool> {
+        self.data.iter().map(|&b| b != 0).collect()
+    }
+}
+
+impl From<&[bool]> for ByteArray {
+    /// Store booleans as bytes of 1 and 0, as Minecraft does.
+    fn from(bools: &[bool]) -> Self {
+        ByteArray::new(bools.iter().map(|&b| b as i8).collect())
+    }
+}
+
+impl AsRef<[i8]> for ByteArray {
+    fn as_ref(&self) -> &[i8] {
+        &self.data
+    }
 }
 
 impl Deref for ByteArray {
@@ -138,6 +175,18 @@ impl IntArray {
         self.data
     }
 
+    /// View the data as unsigned ints without copying.
+    ///
+    /// ```
+    /// # use fastnbt::IntArray;
+    /// let arr = IntArray::new(vec![1, -1]);
+    /// assert_eq!(arr.as_u32_slice(), [1, u32::MAX]);
+    /// ```
+    pub fn as_u32_slice(&self) -> &[u32] {
+        // Safe to treat [i32] as [u32], they have the same size and alignment.
+        unsafe { &*(self.data.as_slice() as *const [i32] as *const [u32]) }
+    }
+
     /// Produce a IntArray from raw data. This data should be big endian!
     pub(crate) fn from_bytes(data: &[u8]) -> std::io::Result<Self> {
         let data = data
@@ -237,6 +286,66 @@ impl LongArray {
         self.data
     }
 
+    /// View the data as unsigned longs without copying.
+    pub fn as_u64_slice(&self) -> &[u64] {
+        // Safe to treat [i64] as [u64], they have the same size and alignment.
+        unsafe { &*(self.data.as_slice() as *const [i64] as *const [u64]) }
+    }
+
+    /// Unpack values of `bits` bits each that have been packed into the longs,
+    /// such as chunk block states and heightmaps. Values start at the least
+    /// significant bits of the first long.
+    ///
+    /// If `padded` is true the data is in the layout used since Minecraft
+    /// 1.16, where values never straddle two longs and any unused high bits of
+    /// each long are padding. Otherwise values are tightly packed and can
+    /// continue into the next long.
+    ///
+    /// Every complete value in the data is yielded, so there may be more
+    /// values than were packed in, from padding at the end. Use
+    /// [`take`][`Iterator::take`] to read the expected number.
+    ///
+    /// ```
+    /// # use fastnbt::LongArray;
+    /// // Two 5-bit values, 1 and 2, in the padded layout.
+    /// let arr = LongArray::new(vec![1 | 2 << 5]);
+    /// let values: Vec<u64> = arr.bit_reader(5, true).take(2).collect();
+    /// assert_eq!(values, [1, 2]);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// If `bits` is not between 1 and 64.
+    pub fn bit_reader(&self, bits: usize, padded: bool) -> impl Iterator<Item = u64> + '_ {
+        assert!(
+            (1..=64).contains(&bits),
+            "bits must be between 1 and 64, got {bits}"
+        );
+
+        let mask = u64::MAX >> (64 - bits);
+        let per_long = 64 / bits;
+        let count = match padded {
+            true => self.data.len() * per_long,
+            false => self.data.len() * 64 / bits,
+        };
+
+        (0..count).map(move |i| {
+            if padded {
+                let long = self.data[i / per_long] as u64;
+                (long >> (i % per_long * bits)) & mask
+            } else {
+                let start = i * bits;
+                let (index, offset) = (start / 64, start % 64);
+                let mut value = self.data[index] as u64 >> offset;
+                if offset + bits > 64 {
+                    // continues into the low bits of the next long.
+                    value |= (self.data[index + 1] as u64) << (64 - offset);
+                }
+                value & mask
+            }
+        })
+    }
+
     pub(crate) fn from_bytes(data: &[u8]) -> std::io::Result<Self> {
         let data = data
             .chunks_exact(8)
@@ -314,3 +423,44 @@ impl DerefMut for LongArray {
         &mut self.data
     }
 }
+
+macro_rules! impl_iter {
+    ($array:ty, $item:ty) => {
+        impl IntoIterator for $array {
+            type Item = $item;
+            type IntoIter = std::vec::IntoIter<$item>;
+
+            fn into_iter(self) -> Self::IntoIter {
+                self.data.into_iter()
+            }
+        }
+
+        impl<'a> IntoIterator for &'a $array {
+            type Item = &'a $item;
+            type IntoIter = std::slice::Iter<'a, $item>;
+
+            fn into_iter(self) -> Self::IntoIter {
+                self.data.iter()
+            }
+        }
+
+        impl<'a> IntoIterator for &'a mut $array {
+            type Item = &'a mut $item;
+            type IntoIter = std::slice::IterMut<'a, $item>;
+
+            fn into_iter(self) -> Self::IntoIter {
+                self.data.iter_mut()
+            }
+        }
+
+        impl FromIterator<$item> for $array {
+            fn from_iter<I: IntoIterator<Item = $item>>(iter: I) -> Self {
+                Self::new(iter.into_iter().collect())
+            }
+        }
+    };
+}
+
+impl_iter!(ByteArray, i8);
+impl_iter!(IntArray, i32);
+impl_iter!(LongArray, i64);
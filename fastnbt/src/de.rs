@@ -224,7 +224,7 @@
 //!     Full,
 //! }
 //! ```
-use std::io::Read;
+use std::{borrow::Cow, io::Read};
 
 use serde::{
     de::{
@@ -249,20 +249,45 @@ pub struct Deserializer<In> {
     scratch: Vec<u8>,
     seen_root: bool,
     opts: DeOpts,
+    // Number of compounds and lists currently being deserialized, not
+    // counting the root compound.
+    depth: usize,
+    // Set when a lenient list gave up on a malformed element. Where the next
+    // value starts is unknown, so everything still open ends here.
+    abandoned: bool,
 }
 
 impl<'de, In> Deserializer<In>
 where
     In: Input<'de>,
 {
+    /// Create a Deserializer reading from any [`Input`], such as one
+    /// implemented outside this crate. See the [`input`][`crate::input`]
+    /// module for more information.
     pub fn new(input: In, opts: DeOpts) -> Self {
         Self {
             input,
             scratch: Vec::new(),
             seen_root: false,
             opts,
+            depth: 0,
+            abandoned: false,
         }
     }
+
+    fn enter(&mut self) -> Result<()> {
+        if self.depth >= self.opts.max_depth {
+            return Err(Error::max_depth_exceeded());
+        }
+        self.depth += 1;
+        Ok(())
+    }
+
+    /// Skip a value, keeping to the max depth.
+    fn ignore_value(&mut self, tag: Tag) -> Result<()> {
+        let max_depth = self.opts.max_depth - self.depth;
+        self.input.ignore_value(tag, max_depth)
+    }
 }
 
 impl<'a> Deserializer<input::Slice<'a>> {
@@ -271,7 +296,16 @@ impl<'a> Deserializer<input::Slice<'a>> {
     ///
     /// [`de`]: ./index.html
     pub fn from_bytes(bytes: &'a [u8], opts: DeOpts) -> Self {
-        Deserializer::new(input::Slice { data: bytes }, opts)
+        let input = input::Slice {
+            data: bytes,
+            little_endian: opts.little_endian,
+        };
+        Deserializer::new(input, opts)
+    }
+
+    /// Number of bytes of input not yet consumed.
+    pub(crate) fn remaining(&self) -> usize {
+        self.input.data.len()
     }
 }
 
@@ -281,7 +315,11 @@ impl<R: Read> Deserializer<input::Reader<R>> {
     ///
     /// [`de`]: ./index.html
     pub fn from_reader(reader: R, opts: DeOpts) -> Self {
-        Deserializer::new(input::Reader { reader }, opts)
+        let input = input::Reader {
+            reader,
+            little_endian: opts.little_endian,
+        };
+        Deserializer::new(input, opts)
     }
 }
 
@@ -356,6 +394,10 @@ impl<'de, 'a, In: Input<'de> + 'a> de::MapAccess<'de> for MapAccess<'a, In> {
     where
         K: de::DeserializeSeed<'de>,
     {
+        if self.de.abandoned {
+            return Ok(None);
+        }
+
         self.tag = self.de.input.consume_tag()?;
         if self.tag == Tag::End {
             return Ok(None);
@@ -380,7 +422,7 @@ struct MapKey<'a, In> {
     de: &'a mut Deserializer<In>,
 }
 
-fn arr_check(key: &str) -> Result<&str> {
+pub(crate) fn arr_check(key: &str) -> Result<&str> {
     if key.starts_with("__")
         && (key == BYTE_ARRAY_TOKEN || key == INT_ARRAY_TOKEN || key == LONG_ARRAY_TOKEN)
     {
@@ -429,22 +471,60 @@ struct AnonymousValue<'a, In> {
     de: &'a mut Deserializer<In>,
 }
 
-impl<'de, 'a, In> de::Deserializer<'de> for AnonymousValue<'a, In>
+impl<'de, 'a, In> AnonymousValue<'a, In>
 where
     In: Input<'de>,
 {
-    type Error = Error;
+    fn deserialize_list<V>(self, v: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        let tag = self.de.input.consume_tag()?;
+        let mut remaining = self.de.input.consume_i32()? as usize;
+
+        // Only lists read without a type in mind, as for Value, are
+        // lenient. Typed sequences would usually fail anyway.
+        let lenient = self.de.opts.lenient_lists && matches!(self.last_hint, Hint::None);
+
+        // End values have no payload. An end tag on it's own is the payload
+        // of an empty compound. A logical interpretation is that this could
+        // be a list of zero-sized units, but this mean an easy short
+        // malicious payload of a massive list taking up lots of memory (as
+        // the Value type's unit variant would not be zero sized.
+        //
+        // Some old chunks store empty lists as as 'list of end', so if the
+        // size is zero we let it slide.
+        if tag == Tag::End && remaining != 0 {
+            if !lenient {
+                return Err(Error::bespoke(
+                    "unexpected list of type 'end', which is not supported".into(),
+                ));
+            }
+            remaining = 0;
+        }
 
-    forward_to_deserialize_any!(u8 u16 u32 u64 i8 i16 i32 i64 f32
-        f64 str string struct tuple map identifier char);
+        if remaining > self.de.opts.max_seq_len {
+            return Err(Error::bespoke(format!(
+                "size ({}) greater than max sequence length ({})",
+                remaining, self.de.opts.max_seq_len,
+            )));
+        }
 
-    fn deserialize_any<V>(mut self, v: V) -> Result<V::Value>
+        self.de.enter()?;
+        let value = v.visit_seq(ListAccess {
+            de: &mut *self.de,
+            tag,
+            remaining,
+            lenient,
+        });
+        self.de.depth -= 1;
+        value
+    }
+
+    fn deserialize_non_nested<V>(self, v: V) -> Result<V::Value>
     where
         V: de::Visitor<'de>,
     {
-        let last_hint = self.last_hint;
-        self.last_hint = Hint::None;
-
         match self.tag {
             Tag::End => Err(Error::bespoke("expected value, found end tag".into())),
             Tag::Byte => v.visit_i8(self.de.input.consume_byte()? as i8),
@@ -457,59 +537,59 @@ where
                 Reference::Borrowed(s) => v.visit_borrowed_str(s),
                 Reference::Copied(s) => v.visit_str(s),
             },
-            Tag::List => {
-                let tag = self.de.input.consume_tag()?;
-                let remaining = self.de.input.consume_i32()? as usize;
-
-                // End values have no payload. An end tag on it's own is the payload
-                // of an empty compound. A logical interpretation is that this could
-                // be a list of zero-sized units, but this mean an easy short
-                // malicious payload of a massive list taking up lots of memory (as
-                // the Value type's unit variant would not be zero sized.
-                //
-                // Some old chunks store empty lists as as 'list of end', so if the
-                // size is zero we let it slide.
-                if tag == Tag::End && remaining != 0 {
-                    return Err(Error::bespoke(
-                        "unexpected list of type 'end', which is not supported".into(),
-                    ));
-                }
-
-                if remaining > self.de.opts.max_seq_len {
-                    return Err(Error::bespoke(format!(
-                        "size ({}) greater than max sequence length ({})",
-                        remaining, self.de.opts.max_seq_len,
-                    )));
-                }
-
-                v.visit_seq(ListAccess {
-                    de: self.de,
-                    tag,
-                    remaining,
-                })
-            }
-            Tag::Compound => v.visit_map(MapAccess::new(self.de)),
             Tag::ByteArray => {
-                if let Hint::Seq = last_hint {
+                if let Hint::Seq = self.last_hint {
                     return Err(Error::array_as_seq());
                 }
                 let len = self.de.input.consume_i32()? as usize;
                 v.visit_map(ArrayWrapperAccess::bytes(self.de, len)?)
             }
             Tag::IntArray => {
-                if let Hint::Seq = last_hint {
+                if let Hint::Seq = self.last_hint {
                     return Err(Error::array_as_seq());
                 }
                 let len = self.de.input.consume_i32()? as usize;
                 v.visit_map(ArrayWrapperAccess::ints(self.de, len)?)
             }
             Tag::LongArray => {
-                if let Hint::Seq = last_hint {
+                if let Hint::Seq = self.last_hint {
                     return Err(Error::array_as_seq());
                 }
                 let len = self.de.input.consume_i32()? as usize;
                 v.visit_map(ArrayWrapperAccess::longs(self.de, len)?)
             }
+            Tag::List | Tag::Compound => {
+                unreachable!("nested values are handled by deserialize_any")
+            }
+        }
+    }
+}
+
+impl<'de, 'a, In> de::Deserializer<'de> for AnonymousValue<'a, In>
+where
+    In: Input<'de>,
+{
+    type Error = Error;
+
+    forward_to_deserialize_any!(u8 u16 u32 u64 i8 i16 i32 i64 f32
+        f64 str string struct tuple map identifier char);
+
+    fn deserialize_any<V>(self, v: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        // Only compounds and lists recurse. Everything else is handled out of
+        // line so that debug builds, which give every local its own stack
+        // slot, use as little stack as possible per level of nesting.
+        match self.tag {
+            Tag::List => self.deserialize_list(v),
+            Tag::Compound => {
+                self.de.enter()?;
+                let value = v.visit_map(MapAccess::new(&mut *self.de));
+                self.de.depth -= 1;
+                value
+            }
+            _ => self.deserialize_non_nested(v),
         }
     }
 
@@ -536,24 +616,23 @@ where
                 let tag = self.de.input.consume_tag()?;
                 let remaining = self.de.input.consume_i32()? as usize;
 
-                match tag {
-                    Tag::Byte => consume_visit(self.de, remaining, std::mem::size_of::<i8>()),
-                    Tag::Short => consume_visit(self.de, remaining, std::mem::size_of::<i16>()),
-                    Tag::Int => consume_visit(self.de, remaining, std::mem::size_of::<i32>()),
-                    Tag::Long => consume_visit(self.de, remaining, std::mem::size_of::<i64>()),
+                match (tag, tag.element_size()) {
+                    (Tag::Byte | Tag::Short | Tag::Int | Tag::Long, Some(size)) => {
+                        consume_visit(self.de, remaining, size)
+                    }
                     _ => Err(Error::bespoke(format!(
                         "cannot convert list of {} to bytes",
                         tag
                     ))),
                 }
             }
-            Tag::ByteArray => {
-                let remaining = self.de.input.consume_i32()? as usize;
-                consume_visit(self.de, remaining, std::mem::size_of::<i8>())
-            }
-            Tag::LongArray => {
+            Tag::ByteArray | Tag::LongArray => {
                 let remaining = self.de.input.consume_i32()? as usize;
-                consume_visit(self.de, remaining, std::mem::size_of::<i64>())
+                let size = self
+                    .tag
+                    .element_size()
+                    .expect("arrays have an element size");
+                consume_visit(self.de, remaining, size)
             }
             _ => Err(Error::bespoke(format!(
                 "cannot convert {} to bytes",
@@ -584,7 +663,7 @@ where
         // value to 'fill' that hole in your type. This means a unit type can be
         // used to ensure the presense of a value in the NBT without actually
         // caring or deserializing its contents.
-        self.de.input.ignore_value(self.tag)?;
+        self.de.ignore_value(self.tag)?;
         visitor.visit_unit()
     }
 
@@ -629,7 +708,7 @@ where
     where
         V: de::Visitor<'de>,
     {
-        self.de.input.ignore_value(self.tag)?;
+        self.de.ignore_value(self.tag)?;
         visitor.visit_unit()
     }
 
@@ -684,6 +763,7 @@ struct ListAccess<'a, In: 'a> {
     de: &'a mut Deserializer<In>,
     tag: Tag, // current tag
     remaining: usize,
+    lenient: bool,
 }
 
 impl<'de, 'a, In: Input<'de> + 'a> de::SeqAccess<'de> for ListAccess<'a, In> {
@@ -693,14 +773,22 @@ impl<'de, 'a, In: Input<'de> + 'a> de::SeqAccess<'de> for ListAccess<'a, In> {
     where
         T: de::DeserializeSeed<'de>,
     {
-        if self.remaining > 0 {
+        if self.remaining > 0 && !self.de.abandoned {
             self.remaining -= 1;
-            seed.deserialize(AnonymousValue {
+            let value = seed.deserialize(AnonymousValue {
                 de: &mut *self.de,
                 last_hint: Hint::None,
                 tag: self.tag,
-            })
-            .map(Some)
+            });
+
+            match value {
+                Ok(value) => Ok(Some(value)),
+                Err(e) if self.lenient && !e.is_fatal() => {
+                    self.de.abandoned = true;
+                    Ok(None)
+                }
+                Err(e) => Err(e),
+            }
         } else {
             Ok(None)
         }
@@ -775,6 +863,7 @@ pub(crate) struct ArrayWrapperAccess<'a, In: 'a> {
     de: &'a mut Deserializer<In>,
     token: &'static str,
     bytes_size: usize,
+    element_size: usize,
     state: State,
 }
 
@@ -785,6 +874,7 @@ impl<'a, In: 'a> ArrayWrapperAccess<'a, In> {
             bytes_size: size
                 .checked_mul(1)
                 .ok_or_else(|| Error::bespoke("nbt array too large".to_string()))?,
+            element_size: 1,
             token: BYTE_ARRAY_TOKEN,
             state: State::Unread,
         })
@@ -796,6 +886,7 @@ impl<'a, In: 'a> ArrayWrapperAccess<'a, In> {
             bytes_size: size
                 .checked_mul(4)
                 .ok_or_else(|| Error::bespoke("nbt array too large".to_string()))?,
+            element_size: 4,
             token: INT_ARRAY_TOKEN,
             state: State::Unread,
         })
@@ -807,6 +898,7 @@ impl<'a, In: 'a> ArrayWrapperAccess<'a, In> {
             bytes_size: size
                 .checked_mul(8)
                 .ok_or_else(|| Error::bespoke("nbt array too large".to_string()))?,
+            element_size: 8,
             token: LONG_ARRAY_TOKEN,
             state: State::Unread,
         })
@@ -838,6 +930,12 @@ impl<'de, 'a, In: Input<'de> + 'a> de::MapAccess<'de> for ArrayWrapperAccess<'a,
             .input
             .consume_bytes(self.bytes_size, &mut self.de.scratch)?;
 
+        if self.de.opts.little_endian && self.element_size > 1 {
+            // Array data is always given big endian, so reverse each element.
+            let data = to_big_endian(data.as_ref(), self.element_size);
+            return seed.deserialize(BytesDeserializer::new(&data));
+        }
+
         match data {
             Reference::Borrowed(bs) => seed.deserialize(BorrowedBytesDeserializer::new(bs)),
             Reference::Copied(bs) => seed.deserialize(BytesDeserializer::new(bs)),
@@ -860,6 +958,11 @@ where
                 .ok_or_else(|| Error::bespoke("nbt array too large".to_string()))?;
 
             let bs = de.de.input.consume_bytes(size, &mut de.de.scratch)?;
+            let bs = if de.de.opts.little_endian {
+                Cow::Owned(to_big_endian(bs.as_ref(), 4))
+            } else {
+                Cow::Borrowed(bs.as_ref())
+            };
             let bs = bs.as_ref();
 
             match bs.try_into() {
@@ -875,3 +978,11 @@ where
         )),
     }
 }
+
+/// Reverse the bytes of each little endian element of an array.
+fn to_big_endian(data: &[u8], element_size: usize) -> Vec<u8> {
+    data.chunks(element_size)
+        .flat_map(|element| element.iter().rev())
+        .copied()
+        .collect()
+}
@@ -3,7 +3,11 @@ use std::fmt::Display;
 
 /// Various errors that can occur during deserialization.
 #[derive(Debug, Clone, PartialEq, Eq)]
-pub struct Error(String);
+pub struct Error {
+    msg: String,
+    offset: Option<usize>,
+    fatal: bool,
+}
 
 /// Convenience type for Result.
 pub type Result<T> = std::result::Result<T, Error>;
@@ -12,13 +16,17 @@ impl std::error::Error for Error {}
 
 impl std::fmt::Display for Error {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.write_str(&self.0)
+        f.write_str(&self.msg)?;
+        if let Some(offset) = self.offset {
+            write!(f, " (at byte offset {})", offset)?;
+        }
+        Ok(())
     }
 }
 
 impl serde::de::Error for Error {
     fn custom<T: Display>(msg: T) -> Self {
-        Error(msg.to_string())
+        Error::new(msg.to_string())
     }
 }
 
@@ -28,45 +36,83 @@ impl serde::ser::Error for Error {
     where
         T: Display,
     {
-        Error(msg.to_string())
+        Error::new(msg.to_string())
     }
 }
 
 impl From<std::io::Error> for Error {
     fn from(e: std::io::Error) -> Self {
-        Error(format!("io error: {}", e))
+        Error::new(format!("io error: {}", e)).fatal()
     }
 }
 
 impl Error {
+    fn new(msg: String) -> Error {
+        Error {
+            msg,
+            offset: None,
+            fatal: false,
+        }
+    }
+
+    /// Mark the error as one that no amount of leniency can recover from,
+    /// such as running out of input.
+    fn fatal(mut self) -> Error {
+        self.fatal = true;
+        self
+    }
+
+    /// Whether the error is from running out of input, an IO error, or
+    /// exceeding the max depth, rather than from malformed data.
+    pub(crate) fn is_fatal(&self) -> bool {
+        self.fatal
+    }
+
+    /// The byte offset into the input where the error was found, if known.
+    /// This is only known when deserializing from a slice with
+    /// [`from_bytes`][`crate::from_bytes`] or
+    /// [`from_bytes_with_opts`][`crate::from_bytes_with_opts`].
+    pub fn offset(&self) -> Option<usize> {
+        self.offset
+    }
+
+    pub(crate) fn with_offset(mut self, offset: usize) -> Error {
+        self.offset.get_or_insert(offset);
+        self
+    }
+
     pub(crate) fn invalid_tag(tag: u8) -> Error {
-        Error(format!("invalid nbt tag value: {}", tag))
+        Error::new(format!("invalid nbt tag value: {}", tag))
     }
 
     pub(crate) fn no_root_compound() -> Error {
-        Error("invalid nbt: no root compound".to_owned())
+        Error::new("invalid nbt: no root compound".to_owned())
     }
 
     pub(crate) fn nonunicode_string(data: &[u8]) -> Error {
-        Error(format!(
+        Error::new(format!(
             "invalid nbt string: nonunicode: {}",
             String::from_utf8_lossy(data)
         ))
     }
 
     pub(crate) fn unexpected_eof() -> Error {
-        Error("eof: unexpectedly ran out of input".to_owned())
+        Error::new("eof: unexpectedly ran out of input".to_owned()).fatal()
     }
 
     pub(crate) fn array_as_seq() -> Error {
-        Error("expected NBT Array, found seq: use ByteArray, IntArray or LongArray types".into())
+        Error::new("expected NBT Array, found seq: use ByteArray, IntArray or LongArray types".into())
     }
 
     pub(crate) fn array_as_other() -> Error {
-        Error("expected NBT Array: use ByteArray, IntArray or LongArray types".into())
+        Error::new("expected NBT Array: use ByteArray, IntArray or LongArray types".into())
+    }
+
+    pub(crate) fn max_depth_exceeded() -> Error {
+        Error::new("max depth exceeded: compounds and lists nested too deeply".to_owned()).fatal()
     }
 
     pub(crate) fn bespoke(msg: String) -> Error {
-        Error(msg)
+        Error::new(msg)
     }
 }
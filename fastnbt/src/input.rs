@@ -1,18 +1,128 @@
+//! The sources of NBT data a [`Deserializer`][`crate::de::Deserializer`] can
+//! read from. These are [`Slice`], made by
+//! [`Deserializer::from_bytes`][`crate::de::Deserializer::from_bytes`], and
+//! [`Reader`], made by
+//! [`Deserializer::from_reader`][`crate::de::Deserializer::from_reader`].
+//!
+//! Other sources can implement [`Input`] and be deserialized with
+//! [`Deserializer::new`][`crate::de::Deserializer::new`]. Anything that can
+//! implement [`Read`] is simpler to use through
+//! [`from_reader`][`crate::from_reader`]. Implementing [`Input`] is worth it
+//! when the data can be borrowed, as the [`borrow`][`crate::borrow`] types do.
+//! Here the data is split across several buffers:
+//!
+//! ```
+//! # use fastnbt::{nbt, DeOpts, Value};
+//! use fastnbt::{de::Deserializer, error::{Error, Result}, input::{Input, Reference}};
+//! use serde::{de::Error as _, Deserialize};
+//!
+//! struct Segments<'de>(Vec<&'de [u8]>);
+//!
+//! impl<'de> Segments<'de> {
+//!     fn take(&mut self, mut n: usize, out: &mut Vec<u8>) -> Result<()> {
+//!         while n > 0 {
+//!             let seg = self.0.first_mut().ok_or_else(|| {
+//!                 std::io::Error::from(std::io::ErrorKind::UnexpectedEof)
+//!             })?;
+//!             let len = n.min(seg.len());
+//!             out.extend_from_slice(&seg[..len]);
+//!             *seg = &seg[len..];
+//!             if seg.is_empty() {
+//!                 self.0.remove(0);
+//!             }
+//!             n -= len;
+//!         }
+//!         Ok(())
+//!     }
+//!
+//!     fn number<const N: usize>(&mut self) -> Result<[u8; N]> {
+//!         let mut buf = Vec::with_capacity(N);
+//!         self.take(N, &mut buf)?;
+//!         Ok(buf.try_into().unwrap())
+//!     }
+//! }
+//!
+//! impl<'de> Input<'de> for Segments<'de> {
+//!     fn consume_byte(&mut self) -> Result<u8> {
+//!         Ok(self.number::<1>()?[0])
+//!     }
+//!
+//!     fn ignore_str(&mut self) -> Result<()> {
+//!         let len = u16::from_be_bytes(self.number()?) as usize;
+//!         self.ignore_bytes(len)
+//!     }
+//!
+//!     fn ignore_bytes(&mut self, size: usize) -> Result<()> {
+//!         self.take(size, &mut Vec::new())
+//!     }
+//!
+//!     fn consume_str<'s>(&'s mut self, scratch: &'s mut Vec<u8>) -> Result<Reference<'de, 's, str>> {
+//!         let len = u16::from_be_bytes(self.number()?) as usize;
+//!         scratch.clear();
+//!         self.take(len, scratch)?;
+//!         // Real NBT strings are modified UTF-8, this example only handles UTF-8.
+//!         let s = std::str::from_utf8(scratch).map_err(Error::custom)?;
+//!         Ok(Reference::Copied(s))
+//!     }
+//!
+//!     fn consume_bytes<'s>(&'s mut self, n: usize, scratch: &'s mut Vec<u8>) -> Result<Reference<'de, 's, [u8]>> {
+//!         scratch.clear();
+//!         self.take(n, scratch)?;
+//!         Ok(Reference::Copied(scratch))
+//!     }
+//!
+//!     fn consume_i16(&mut self) -> Result<i16> {
+//!         Ok(i16::from_be_bytes(self.number()?))
+//!     }
+//!
+//!     fn consume_i32(&mut self) -> Result<i32> {
+//!         Ok(i32::from_be_bytes(self.number()?))
+//!     }
+//!
+//!     fn consume_i64(&mut self) -> Result<i64> {
+//!         Ok(i64::from_be_bytes(self.number()?))
+//!     }
+//!
+//!     fn consume_f32(&mut self) -> Result<f32> {
+//!         Ok(f32::from_be_bytes(self.number()?))
+//!     }
+//!
+//!     fn consume_f64(&mut self) -> Result<f64> {
+//!         Ok(f64::from_be_bytes(self.number()?))
+//!     }
+//! }
+//!
+//! let bytes = fastnbt::to_bytes(&nbt!({"a": 1, "b": "hello"})).unwrap();
+//! let (first, second) = bytes.split_at(5);
+//!
+//! let mut de = Deserializer::new(Segments(vec![first, second]), DeOpts::new());
+//! let val = Value::deserialize(&mut de).unwrap();
+//! assert_eq!(val, nbt!({"a": 1, "b": "hello"}));
+//! ```
+//!
+//! The input types can be named, for example to store a deserializer:
+//!
+//! ```
+//! # use fastnbt::{nbt, DeOpts, Value};
+//! use fastnbt::{de::Deserializer, input::Slice};
+//! use serde::Deserialize;
+//!
+//! let bytes = fastnbt::to_bytes(&nbt!({"a": 1})).unwrap();
+//! let mut de: Deserializer<Slice> = Deserializer::from_bytes(&bytes, DeOpts::new());
+//!
+//! let val = Value::deserialize(&mut de).unwrap();
+//! assert_eq!(val, nbt!({"a": 1}));
+//! ```
+
 use std::{borrow::Cow, io::Read, ops::Range};
 
-use byteorder::{BigEndian, ReadBytesExt};
+use byteorder::ReadBytesExt;
 
 use crate::{
     error::{Error, Result},
     Tag,
 };
 
-mod private {
-    // Only this crate can implement this trait. Other traits can inherit from
-    // Sealed in order to prevent other crates from creating implementations.
-    pub trait Sealed {}
-}
-
 fn try_size(size: i32, multiplier: usize) -> Result<usize> {
     let size: usize = size
         .try_into()
@@ -21,11 +131,17 @@ fn try_size(size: i32, multiplier: usize) -> Result<usize> {
     size.checked_mul(multiplier)
         .ok_or_else(|| Error::bespoke("size too large".to_string()))
 }
+
+/// Data read from an [`Input`], either borrowed from the input itself or
+/// copied into a scratch buffer.
 pub enum Reference<'b, 'c, T>
 where
     T: ?Sized + 'static,
 {
+    /// Borrowed from the input for the `'de` lifetime. Only borrowed data can
+    /// be deserialized into borrowing types like `&'de str`.
     Borrowed(&'b T),
+    /// Copied into the scratch buffer, valid until the next read.
     Copied(&'c T),
 }
 
@@ -38,116 +154,174 @@ impl<'b, 'c> AsRef<[u8]> for Reference<'b, 'c, [u8]> {
     }
 }
 
-pub trait Input<'de>: private::Sealed {
-    #[doc(hidden)]
+/// A source of NBT data for a [`Deserializer`][`crate::de::Deserializer`]. See
+/// the [module documentation][`crate::input`] for an example implementation.
+///
+/// The required methods are the stable interface: their signatures and
+/// contracts will only change in a breaking release. The provided methods
+/// build on them and do not need to be overridden.
+///
+/// The input decodes numbers itself, so it is responsible for their byte
+/// order. [`DeOpts::little_endian`][`crate::DeOpts::little_endian`] only
+/// applies to the built in inputs. When the data runs out, return an error
+/// converted from a [`std::io::Error`] so that lenient deserialization knows
+/// not to recover from it.
+pub trait Input<'de> {
+    /// Consume a single byte.
     fn consume_byte(&mut self) -> Result<u8>;
 
-    // #[doc(hidden)]
-    // fn discard(&mut self);
-
-    #[doc(hidden)]
+    /// Skip over a string: a `u16` length then that many bytes.
     fn ignore_str(&mut self) -> Result<()>;
 
-    #[doc(hidden)]
+    /// Skip over `size` bytes.
     fn ignore_bytes(&mut self, size: usize) -> Result<()>;
 
+    /// Consume a tag byte, failing if it is not a valid tag.
     fn consume_tag(&mut self) -> Result<Tag> {
         let tag = self.consume_byte()?;
         Tag::try_from(tag).map_err(|_| Error::invalid_tag(tag))
     }
 
+    /// Consume a string: a `u16` length then that many bytes of Java's
+    /// modified UTF-8, which must be decoded to UTF-8. The `scratch` buffer can
+    /// be used to hold a copy.
     fn consume_str<'s>(&'s mut self, scratch: &'s mut Vec<u8>) -> Result<Reference<'de, 's, str>>;
 
+    /// Consume exactly `n` bytes. The `scratch` buffer can be used to hold a
+    /// copy.
     fn consume_bytes<'s>(
         &'s mut self,
         n: usize,
         scratch: &'s mut Vec<u8>,
     ) -> Result<Reference<'de, 's, [u8]>>;
 
+    /// Consume a short.
     fn consume_i16(&mut self) -> Result<i16>;
+    /// Consume an int.
     fn consume_i32(&mut self) -> Result<i32>;
+    /// Consume a long.
     fn consume_i64(&mut self) -> Result<i64>;
+    /// Consume a float.
     fn consume_f32(&mut self) -> Result<f32>;
+    /// Consume a double.
     fn consume_f64(&mut self) -> Result<f64>;
 
-    fn ignore_value(&mut self, tag: Tag) -> Result<()> {
-        match tag {
-            Tag::Byte => {
-                self.consume_byte()?;
-            }
-            Tag::Short => {
-                self.consume_i16()?;
-            }
-            Tag::Int => {
-                self.consume_i32()?;
-            }
-            Tag::Long => {
-                self.consume_i64()?;
-            }
-            Tag::Float => {
-                self.consume_f32()?;
-            }
-            Tag::Double => {
-                self.consume_f64()?;
-            }
-            Tag::String => {
-                self.ignore_str()?;
-            }
-            Tag::ByteArray => {
-                let size = self.consume_i32()? as usize;
-                self.ignore_bytes(size)?;
-            }
-            Tag::IntArray => {
-                let size = self.consume_i32()?;
-                self.ignore_bytes(try_size(size, std::mem::size_of::<i32>())?)?;
-            }
-            Tag::LongArray => {
-                let size = self.consume_i32()?;
-                self.ignore_bytes(try_size(size, std::mem::size_of::<i64>())?)?;
-            }
-            Tag::Compound => {
-                // Need to loop and ignore each value until we reach an end tag.
-
-                // we need to enter the compound, then ignore it's value.
-                loop {
-                    let tag = self.consume_tag()?;
-                    if tag == Tag::End {
-                        break;
+    /// Skip over a value with the given tag. Compounds and lists may be nested
+    /// at most `max_depth` deep.
+    fn ignore_value(&mut self, tag: Tag, max_depth: usize) -> Result<()> {
+        // Open compounds and lists are kept here rather than recursing, so
+        // skipping deeply nested data uses no extra stack.
+        let mut open = Vec::new();
+        let mut tag = tag;
+
+        loop {
+            match tag {
+                Tag::Byte => {
+                    self.consume_byte()?;
+                }
+                Tag::Short => {
+                    self.consume_i16()?;
+                }
+                Tag::Int => {
+                    self.consume_i32()?;
+                }
+                Tag::Long => {
+                    self.consume_i64()?;
+                }
+                Tag::Float => {
+                    self.consume_f32()?;
+                }
+                Tag::Double => {
+                    self.consume_f64()?;
+                }
+                Tag::String => {
+                    self.ignore_str()?;
+                }
+                Tag::ByteArray | Tag::IntArray | Tag::LongArray => {
+                    let size = self.consume_i32()?;
+                    let element_size = tag.element_size().expect("arrays have an element size");
+                    self.ignore_bytes(try_size(size, element_size)?)?;
+                }
+                Tag::Compound => {
+                    if open.len() >= max_depth {
+                        return Err(Error::max_depth_exceeded());
+                    }
+                    open.push(Open::Compound);
+                }
+                Tag::List => {
+                    if open.len() >= max_depth {
+                        return Err(Error::max_depth_exceeded());
                     }
 
-                    // consume the name.
-                    self.ignore_str()?;
-                    self.ignore_value(tag)?;
+                    let element_tag = self.consume_tag()?;
+                    let size = self.consume_i32()?;
+                    if element_tag == Tag::End && size > 0 {
+                        return Err(Error::bespoke(
+                            "unexpected list of type 'end', which is not supported".into(),
+                        ));
+                    }
+                    open.push(Open::List {
+                        tag: element_tag,
+                        remaining: size,
+                    });
                 }
-            }
-            Tag::List => {
-                let element_tag = self.consume_tag()?;
-                let size = self.consume_i32()?;
-                for _ in 0..size {
-                    self.ignore_value(element_tag)?;
+                Tag::End => {
+                    // If we are trying to ignore a list of empty compounds, that
+                    // list might be indicated by a series of End tags. If this
+                    // occurs then we should end the Compound branch of this match
+                    // statement, where the end tag will be consumed. So we should
+                    // never reach here.
+                    //
+                    // TODO: Write an explicit test for ignored list of compound.
+                    unreachable!()
                 }
             }
-            Tag::End => {
-                // If we are trying to ignore a list of empty compounds, that
-                // list might be indicated by a series of End tags. If this
-                // occurs then we should end the Compound branch of this match
-                // statement, where the end tag will be consumed. So we should
-                // never reach here.
-                //
-                // TODO: Write an explicit test for ignored list of compound.
-                unreachable!()
+
+            // Find the next value to skip, closing anything that has ended.
+            loop {
+                match open.last_mut() {
+                    None => return Ok(()),
+                    Some(Open::Compound) => {
+                        let next = self.consume_tag()?;
+                        if next == Tag::End {
+                            open.pop();
+                            continue;
+                        }
+
+                        // consume the name.
+                        self.ignore_str()?;
+                        tag = next;
+                        break;
+                    }
+                    Some(Open::List {
+                        tag: element_tag,
+                        remaining,
+                    }) => {
+                        if *remaining > 0 {
+                            *remaining -= 1;
+                            tag = *element_tag;
+                            break;
+                        }
+                        open.pop();
+                    }
+                }
             }
         }
-
-        Ok(())
     }
 }
 
+/// A compound or list being skipped by [`Input::ignore_value`].
+enum Open {
+    Compound,
+    List { tag: Tag, remaining: i32 },
+}
+
+/// Input from a byte slice. Strings and arrays can be borrowed from it.
 pub struct Slice<'de> {
     pub(crate) data: &'de [u8],
+    pub(crate) little_endian: bool,
 }
 
-impl<'de> private::Sealed for Slice<'de> {}
 impl<'de> Slice<'de> {
     fn consume(&mut self, r: Range<usize>) -> Result<&'de [u8]> {
         if r.end <= self.data.len() {
@@ -158,6 +332,15 @@ impl<'de> Slice<'de> {
             Err(Error::unexpected_eof())
         }
     }
+
+    /// Consume a number, returning its bytes in big endian order.
+    fn consume_number<const N: usize>(&mut self) -> Result<[u8; N]> {
+        let mut bs: [u8; N] = self.consume(0..N)?.try_into().expect("consumed N bytes");
+        if self.little_endian {
+            bs.reverse();
+        }
+        Ok(bs)
+    }
 }
 
 impl<'de> Input<'de> for Slice<'de> {
@@ -166,12 +349,12 @@ impl<'de> Input<'de> for Slice<'de> {
     }
 
     fn ignore_str(&mut self) -> Result<()> {
-        let len = self.consume(0..2)?.read_u16::<BigEndian>()? as usize;
+        let len = u16::from_be_bytes(self.consume_number()?) as usize;
         self.consume(0..len).map(|_| ())
     }
 
     fn consume_str<'s>(&'s mut self, scratch: &'s mut Vec<u8>) -> Result<Reference<'de, 's, str>> {
-        let len = self.consume(0..2)?.read_u16::<BigEndian>()? as usize;
+        let len = u16::from_be_bytes(self.consume_number()?) as usize;
         let str = self.consume(0..len)?;
         let str = cesu8::from_java_cesu8(str).map_err(|_| Error::nonunicode_string(str))?;
 
@@ -196,28 +379,23 @@ impl<'de> Input<'de> for Slice<'de> {
     }
 
     fn consume_i16(&mut self) -> Result<i16> {
-        let mut bs = self.consume(0..std::mem::size_of::<i16>())?;
-        Ok(bs.read_i16::<BigEndian>()?)
+        Ok(i16::from_be_bytes(self.consume_number()?))
     }
 
     fn consume_i32(&mut self) -> Result<i32> {
-        let mut bs = self.consume(0..std::mem::size_of::<i32>())?;
-        Ok(bs.read_i32::<BigEndian>()?)
+        Ok(i32::from_be_bytes(self.consume_number()?))
     }
 
     fn consume_i64(&mut self) -> Result<i64> {
-        let mut bs = self.consume(0..std::mem::size_of::<i64>())?;
-        Ok(bs.read_i64::<BigEndian>()?)
+        Ok(i64::from_be_bytes(self.consume_number()?))
     }
 
     fn consume_f32(&mut self) -> Result<f32> {
-        let mut bs = self.consume(0..std::mem::size_of::<f32>())?;
-        Ok(bs.read_f32::<BigEndian>()?)
+        Ok(f32::from_be_bytes(self.consume_number()?))
     }
 
     fn consume_f64(&mut self) -> Result<f64> {
-        let mut bs = self.consume(0..std::mem::size_of::<f64>())?;
-        Ok(bs.read_f64::<BigEndian>()?)
+        Ok(f64::from_be_bytes(self.consume_number()?))
     }
 
     fn ignore_bytes(&mut self, size: usize) -> Result<()> {
@@ -226,11 +404,23 @@ impl<'de> Input<'de> for Slice<'de> {
     }
 }
 
+/// Input from a [`Read`]. Everything read is copied.
 pub struct Reader<R: Read> {
     pub(crate) reader: R,
+    pub(crate) little_endian: bool,
 }
 
-impl<R: Read> private::Sealed for Reader<R> {}
+impl<R: Read> Reader<R> {
+    /// Read a number, returning its bytes in big endian order.
+    fn read_number<const N: usize>(&mut self) -> Result<[u8; N]> {
+        let mut bs = [0u8; N];
+        self.reader.read_exact(&mut bs)?;
+        if self.little_endian {
+            bs.reverse();
+        }
+        Ok(bs)
+    }
+}
 
 impl<'de, R: Read> Input<'de> for Reader<R> {
     fn consume_byte(&mut self) -> Result<u8> {
@@ -238,13 +428,13 @@ impl<'de, R: Read> Input<'de> for Reader<R> {
     }
 
     fn ignore_str(&mut self) -> Result<()> {
-        let len = self.reader.read_u16::<BigEndian>()? as usize;
+        let len = u16::from_be_bytes(self.read_number()?) as usize;
         let mut buf = vec![0; len]; // TODO: try a scratch space to reduce allocs?
         Ok(self.reader.read_exact(&mut buf)?)
     }
 
     fn consume_str<'s>(&'s mut self, scratch: &'s mut Vec<u8>) -> Result<Reference<'de, 's, str>> {
-        let len = self.reader.read_u16::<BigEndian>()? as usize;
+        let len = u16::from_be_bytes(self.read_number()?) as usize;
         scratch.clear();
         scratch.resize(len, 0);
         self.reader.read_exact(scratch)?;
@@ -275,23 +465,23 @@ impl<'de, R: Read> Input<'de> for Reader<R> {
     }
 
     fn consume_i16(&mut self) -> Result<i16> {
-        Ok(self.reader.read_i16::<BigEndian>()?)
+        Ok(i16::from_be_bytes(self.read_number()?))
     }
 
     fn consume_i32(&mut self) -> Result<i32> {
-        Ok(self.reader.read_i32::<BigEndian>()?)
+        Ok(i32::from_be_bytes(self.read_number()?))
     }
 
     fn consume_i64(&mut self) -> Result<i64> {
-        Ok(self.reader.read_i64::<BigEndian>()?)
+        Ok(i64::from_be_bytes(self.read_number()?))
     }
 
     fn consume_f32(&mut self) -> Result<f32> {
-        Ok(self.reader.read_f32::<BigEndian>()?)
+        Ok(f32::from_be_bytes(self.read_number()?))
     }
 
     fn consume_f64(&mut self) -> Result<f64> {
-        Ok(self.reader.read_f64::<BigEndian>()?)
+        Ok(f64::from_be_bytes(self.read_number()?))
     }
 
     fn ignore_bytes(&mut self, size: usize) -> Result<()> {
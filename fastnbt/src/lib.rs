@@ -120,6 +120,20 @@
 //! requiring deserialization into Rust objects. You can use [`from_reader`] for
 //! full deserialization.
 //!
+//! # Crate features
+//!
+//! * **indexmap** -
+//!   Use an `IndexMap` for [`Value::Compound`] so that the order of keys is
+//!   preserved. See [`value::Map`].
+//! * **arbitrary1** -
+//!   Implement `arbitrary::Arbitrary` for the NBT types, for fuzzing.
+//! * **compression** -
+//!   Add [`from_compressed_reader`], for reading gzip or zlib compressed NBT
+//!   such as `level.dat`.
+//! * **uuid** -
+//!   Add the [`uuid`][`crate::uuid`] module, for (de)serializing `uuid::Uuid`
+//!   fields stored as IntArrays.
+//!
 
 use ser::Serializer;
 use serde::{de as serde_de, Deserialize, Serialize};
@@ -127,12 +141,16 @@ use serde::{de as serde_de, Deserialize, Serialize};
 pub mod borrow;
 pub mod de;
 pub mod error;
+pub mod input;
 pub mod ser;
 pub mod stream;
 pub mod value;
+pub mod varint;
+
+#[cfg(feature = "uuid")]
+pub mod uuid;
 
 mod arrays;
-mod input;
 #[macro_use]
 mod macros;
 
@@ -153,7 +171,7 @@ use std::{
 };
 
 /// An NBT tag. This does not carry the value or the name of the data.
-#[derive(Deserialize, Debug, PartialEq, Clone, Copy)]
+#[derive(Deserialize, Debug, PartialEq, Eq, Hash, Clone, Copy)]
 #[cfg_attr(feature = "arbitrary1", derive(arbitrary::Arbitrary))]
 #[repr(u8)]
 pub enum Tag {
@@ -185,6 +203,43 @@ pub enum Tag {
     LongArray = 12,
 }
 
+impl Tag {
+    /// Whether this is one of the array tags: [`ByteArray`][`Tag::ByteArray`],
+    /// [`IntArray`][`Tag::IntArray`] or [`LongArray`][`Tag::LongArray`].
+    pub fn is_array(self) -> bool {
+        matches!(self, Tag::ByteArray | Tag::IntArray | Tag::LongArray)
+    }
+
+    /// Whether this is a numeric tag, ie a fixed size value like
+    /// [`Int`][`Tag::Int`] or [`Double`][`Tag::Double`].
+    pub fn is_scalar(self) -> bool {
+        matches!(
+            self,
+            Tag::Byte | Tag::Short | Tag::Int | Tag::Long | Tag::Float | Tag::Double
+        )
+    }
+
+    /// The size in bytes of a value of this tag if it is a scalar, or of each
+    /// element if it is an array. None for other tags, whose size depends on
+    /// their contents.
+    ///
+    /// ```
+    /// # use fastnbt::Tag;
+    /// assert_eq!(Tag::Short.element_size(), Some(2));
+    /// assert_eq!(Tag::LongArray.element_size(), Some(8));
+    /// assert_eq!(Tag::String.element_size(), None);
+    /// ```
+    pub fn element_size(self) -> Option<usize> {
+        match self {
+            Tag::Byte | Tag::ByteArray => Some(1),
+            Tag::Short => Some(2),
+            Tag::Int | Tag::Float | Tag::IntArray => Some(4),
+            Tag::Long | Tag::Double | Tag::LongArray => Some(8),
+            Tag::End | Tag::String | Tag::List | Tag::Compound => None,
+        }
+    }
+}
+
 // Crates exist to generate this code for us, but would add to our compile
 // times, so we instead write it out manually, the tags will very rarely change
 // so isn't a massive burden, but saves a significant amount of compile time.
@@ -252,12 +307,35 @@ pub fn to_writer<T: Serialize, W: Write>(writer: W, v: &T) -> Result<()> {
     to_writer_with_opts(writer, v, Default::default())
 }
 
+/// Serialize some `T` into NBT data in the given buffer, replacing its
+/// contents. This reuses the buffer's allocation, which helps when serializing
+/// many values in a loop. See the [`ser`] module for more information.
+///
+/// ```
+/// # use fastnbt::{nbt, error::Result};
+/// # fn main() -> Result<()> {
+/// let mut buf = Vec::new();
+/// for i in 0..3 {
+///     fastnbt::to_bytes_into(&nbt!({ "index": i }), &mut buf)?;
+///     assert_eq!(buf, fastnbt::to_bytes(&nbt!({ "index": i }))?);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+///
+/// If serialization fails the buffer may contain partial data.
+pub fn to_bytes_into<T: Serialize>(v: &T, buf: &mut Vec<u8>) -> Result<()> {
+    to_bytes_into_with_opts(v, buf, Default::default())
+}
+
 /// Options for customizing serialization.
 #[derive(Clone)]
 pub struct SerOpts {
     root_name: String,
     /// Whether to include the root compound name.
     serialize_root_name: bool,
+    sorted_keys: bool,
+    little_endian: bool,
 }
 
 impl Default for SerOpts {
@@ -265,6 +343,8 @@ impl Default for SerOpts {
         Self {
             root_name: Default::default(),
             serialize_root_name: true,
+            sorted_keys: false,
+            little_endian: false,
         }
     }
 }
@@ -275,6 +355,8 @@ impl SerOpts {
         Default::default()
     }
 
+    /// Creates options for "network NBT" mode, where the root compound is
+    /// written without a name. See [`DeOpts::network_nbt`].
     pub fn network_nbt() -> Self {
         Self::new().serialize_root_compound_name(false)
     }
@@ -292,6 +374,24 @@ impl SerOpts {
         self.serialize_root_name = true;
         self
     }
+
+    /// Write the entries of every compound sorted by key, rather than in the
+    /// order they are serialized. This gives the same bytes for the same data
+    /// regardless of map type, which helps when diffing or hashing NBT.
+    ///
+    /// Keys are compared by their encoded bytes. Entries are buffered until the
+    /// end of each compound, so this is slower than the default.
+    pub fn sorted_keys(mut self, sorted_keys: bool) -> Self {
+        self.sorted_keys = sorted_keys;
+        self
+    }
+
+    /// Write numbers little endian, as Bedrock Edition does, rather than big
+    /// endian. See [`DeOpts::little_endian`].
+    pub fn little_endian(mut self, little_endian: bool) -> Self {
+        self.little_endian = little_endian;
+        self
+    }
 }
 
 /// Serialize some `T` into NBT data. See the [`ser`] module for more
@@ -303,11 +403,25 @@ pub fn to_bytes_with_opts<T: Serialize>(v: &T, opts: SerOpts) -> Result<Vec<u8>>
         writer: &mut result,
         root_name: opts.root_name,
         serialize_root_name: opts.serialize_root_name,
+        sorted_keys: opts.sorted_keys,
+        little_endian: opts.little_endian,
     };
     v.serialize(&mut serializer)?;
     Ok(result)
 }
 
+/// Serialize some `T` into NBT data in the given buffer, replacing its
+/// contents. See [`to_bytes_into`]. The options allow you to set things like
+/// the root name of the compound when serialized.
+pub fn to_bytes_into_with_opts<T: Serialize>(
+    v: &T,
+    buf: &mut Vec<u8>,
+    opts: SerOpts,
+) -> Result<()> {
+    buf.clear();
+    to_writer_with_opts(buf, v, opts)
+}
+
 /// Serialize some `T` into NBT data. See the [`ser`] module for more
 /// information. The options allow you to set things like the root name of the
 /// compound when serialized.
@@ -316,6 +430,8 @@ pub fn to_writer_with_opts<T: Serialize, W: Write>(writer: W, v: &T, opts: SerOp
         writer,
         root_name: opts.root_name,
         serialize_root_name: opts.serialize_root_name,
+        sorted_keys: opts.sorted_keys,
+        little_endian: opts.little_endian,
     };
     v.serialize(&mut serializer)?;
     Ok(())
@@ -398,6 +514,73 @@ pub fn from_reader<'de, R, T>(reader: R) -> Result<T>
     from_reader_with_opts(reader, Default::default())
 }
 
+/// Deserialize into a `T` from NBT data that may be compressed, such as the
+/// contents of a `level.dat` file. See the [`de`] module for more information.
+///
+/// The first bytes of the data are checked for a gzip or zlib header, and the
+/// data is decompressed if one is found. Otherwise it is read as raw NBT.
+///
+/// ```no_run
+/// # use fastnbt::Value;
+/// # use fastnbt::error::Result;
+/// # fn main() -> Result<()> {
+/// let file = std::fs::File::open("level.dat")?;
+/// let val: Value = fastnbt::from_compressed_reader(file)?;
+/// # Ok(())
+/// # }
+/// ```
+#[cfg(feature = "compression")]
+pub fn from_compressed_reader<'de, R, T>(reader: R) -> Result<T>
+where
+    T: serde_de::Deserialize<'de>,
+    R: Read,
+{
+    from_compressed_reader_with_opts(reader, Default::default())
+}
+
+/// Similar to [`from_compressed_reader`] but with options.
+#[cfg(feature = "compression")]
+pub fn from_compressed_reader_with_opts<'de, R, T>(mut reader: R, opts: DeOpts) -> Result<T>
+where
+    T: serde_de::Deserialize<'de>,
+    R: Read,
+{
+    // Read the first two bytes to check for a header, then put them back in
+    // front of the rest of the data.
+    let mut magic = [0u8; 2];
+    let mut len = 0;
+    while len < magic.len() {
+        match reader.read(&mut magic[len..])? {
+            0 => break,
+            n => len += n,
+        }
+    }
+    let reader = Read::chain(&magic[..len], reader);
+
+    if magic[..len] == GZIP_MAGIC_BYTES {
+        from_reader_with_opts(flate2::read::MultiGzDecoder::new(reader), opts)
+    } else if len == 2 && is_zlib_header(magic) {
+        from_reader_with_opts(flate2::read::ZlibDecoder::new(reader), opts)
+    } else {
+        from_reader_with_opts(reader, opts)
+    }
+}
+
+/// Default maximum depth of nested compounds and lists, for both
+/// [`DeOpts`] and the [`stream::Parser`]. This is the same limit Minecraft
+/// uses, and deserializing to [`Value`] this deep fits in the 2 MiB stack Rust
+/// gives spawned threads, even in debug builds.
+pub const DEFAULT_MAX_DEPTH: usize = 512;
+
+const GZIP_MAGIC_BYTES: [u8; 2] = [0x1f, 0x8b];
+
+/// Whether the bytes are a zlib header using deflate, see RFC 1950. Raw NBT
+/// starts with the compound tag `0x0a`, which is never a valid first byte here.
+#[cfg(feature = "compression")]
+fn is_zlib_header(header: [u8; 2]) -> bool {
+    header[0] & 0x0f == 8 && header[0] >> 4 <= 7 && u16::from_be_bytes(header).is_multiple_of(31)
+}
+
 /// Options for customizing deserialization.
 #[derive(Clone)]
 pub struct DeOpts {
@@ -405,6 +588,12 @@ pub struct DeOpts {
     max_seq_len: usize,
     /// Whether compound tag names are expected to exist or not.
     expect_coumpound_names: bool,
+    /// Maximum depth of nested compounds and lists.
+    max_depth: usize,
+    /// Whether to recover from malformed lists rather than failing.
+    lenient_lists: bool,
+    /// Whether numbers are little endian.
+    little_endian: bool,
 }
 
 impl DeOpts {
@@ -413,8 +602,9 @@ impl DeOpts {
         Default::default()
     }
 
-    /// Creates a decoder for "network NBT" mode.
-    /// At the moment that consists compound tags not having names (or their length).
+    /// Creates a decoder for "network NBT" mode, as used by the Java Edition
+    /// protocol since 1.20.2. The root compound has no name (or name length).
+    /// Nested compounds still have names, like any other tag in a compound.
     pub fn network_nbt() -> Self {
         Self::new().expect_coumpound_names(false)
     }
@@ -427,6 +617,72 @@ impl DeOpts {
         self
     }
 
+    /// Set the maximum depth compounds and lists can be nested within the
+    /// root compound. Deeper data is an error rather than risking a stack
+    /// overflow on malicious or corrupt input. Defaults to
+    /// [`DEFAULT_MAX_DEPTH`].
+    ///
+    /// Each level of nesting uses stack, a few KiB per level in debug builds.
+    /// Lower this when deserializing on threads with small stacks.
+    pub fn max_depth(mut self, value: usize) -> Self {
+        self.max_depth = value;
+        self
+    }
+
+    /// Recover what data we can from malformed lists rather than failing.
+    /// This is meant for decoding into [`Value`] to salvage data from
+    /// corrupted chunks. Defaults to false.
+    ///
+    /// * A list of type 'end' with elements is read as an empty list. Empty
+    ///   lists of type 'end' are always allowed.
+    /// * If an element of a list is malformed, eg has an invalid tag, the list
+    ///   ends with the elements before it. The rest of the input cannot be
+    ///   trusted, so every compound and list still open ends there too.
+    ///
+    /// This only applies to lists read as [`Value`], or other types that
+    /// deserialize without a type hint. Running out of input, IO errors and
+    /// exceeding the [max depth][`DeOpts::max_depth`] are always errors.
+    ///
+    /// ```
+    /// # use fastnbt::{DeOpts, Value, nbt};
+    /// let mut bytes = fastnbt::to_bytes(&nbt!({"list": [{"a": 1}, {"a": 2}]})).unwrap();
+    /// // Corrupt the tag of the entry in the second compound, which is 9
+    /// // bytes, followed by the end of the root compound.
+    /// let i = bytes.len() - 10;
+    /// bytes[i] = 99;
+    ///
+    /// let v: Value =
+    ///     fastnbt::from_bytes_with_opts(&bytes, DeOpts::new().lenient_lists(true)).unwrap();
+    /// assert_eq!(v, nbt!({"list": [{"a": 1}]}));
+    /// ```
+    pub fn lenient_lists(mut self, value: bool) -> Self {
+        self.lenient_lists = value;
+        self
+    }
+
+    /// Read numbers as little endian rather than big endian. Bedrock Edition
+    /// stores NBT this way, for example in its `level.dat`. Defaults to false.
+    ///
+    /// This only changes the byte order of the NBT itself. Bedrock's LevelDB
+    /// world storage is not supported, and the 8 byte header at the start of
+    /// Bedrock's `level.dat` must be skipped before deserializing. Borrowed
+    /// int and long arrays from the [`borrow`] module cannot be used, as the
+    /// data has to be copied to change its byte order.
+    ///
+    /// ```
+    /// # use fastnbt::{DeOpts, SerOpts, Value, nbt};
+    /// let v = nbt!({"a": 1, "longs": [L; 2, 3]});
+    /// let bytes = fastnbt::to_bytes_with_opts(&v, SerOpts::new().little_endian(true)).unwrap();
+    ///
+    /// let opts = DeOpts::new().little_endian(true);
+    /// let back: Value = fastnbt::from_bytes_with_opts(&bytes, opts).unwrap();
+    /// assert_eq!(back, v);
+    /// ```
+    pub fn little_endian(mut self, value: bool) -> Self {
+        self.little_endian = value;
+        self
+    }
+
     /// Sets wheather the deserializer should expect compound tags to have names.
     pub fn expect_coumpound_names(mut self, value: bool) -> Self {
         self.expect_coumpound_names = value;
@@ -439,6 +695,9 @@ impl Default for DeOpts {
         Self {
             max_seq_len: 10_000_000, // arbitrary high limit.
             expect_coumpound_names: true,
+            max_depth: DEFAULT_MAX_DEPTH,
+            lenient_lists: false,
+            little_endian: false,
         }
     }
 }
@@ -448,8 +707,85 @@ pub fn from_bytes_with_opts<'a, T>(input: &'a [u8], opts: DeOpts) -> Result<T>
 where
     T: serde_de::Deserialize<'a>,
 {
-    const GZIP_MAGIC_BYTES: [u8; 2] = [0x1f, 0x8b];
+    from_bytes_tracked_with_opts(input, opts).map(|(t, _)| t)
+}
 
+/// Similar to [`from_bytes`] but also returns the number of bytes of `input`
+/// that were consumed. Anything after that is not part of the NBT, for
+/// example trailing garbage or another NBT document.
+///
+/// ```
+/// # use fastnbt::{Value, nbt};
+/// let mut input = fastnbt::to_bytes(&nbt!({"a": 1})).unwrap();
+/// let len = input.len();
+/// input.extend_from_slice(&[1, 2, 3]);
+///
+/// let (val, consumed): (Value, usize) = fastnbt::from_bytes_tracked(&input).unwrap();
+/// assert_eq!(val, nbt!({"a": 1}));
+/// assert_eq!(consumed, len);
+/// ```
+pub fn from_bytes_tracked<'a, T>(input: &'a [u8]) -> Result<(T, usize)>
+where
+    T: serde_de::Deserialize<'a>,
+{
+    from_bytes_tracked_with_opts(input, Default::default())
+}
+
+/// Deserialize a `T` from each of several NBT documents stored back to back,
+/// such as in some network captures. Each item is one root compound, and the
+/// iterator ends when the input is used up.
+///
+/// The iterator stops after the first error, as where the next document
+/// starts is unknown.
+///
+/// ```
+/// # use fastnbt::{Value, nbt};
+/// let mut input = fastnbt::to_bytes(&nbt!({"a": 1})).unwrap();
+/// input.extend(fastnbt::to_bytes(&nbt!({"b": 2})).unwrap());
+///
+/// let vals: Vec<Value> = fastnbt::from_bytes_iter(&input)
+///     .collect::<fastnbt::error::Result<_>>()
+///     .unwrap();
+/// assert_eq!(vals, [nbt!({"a": 1}), nbt!({"b": 2})]);
+/// ```
+pub fn from_bytes_iter<'a, T>(input: &'a [u8]) -> impl Iterator<Item = Result<T>> + 'a
+where
+    T: serde_de::Deserialize<'a> + 'a,
+{
+    from_bytes_iter_with_opts(input, Default::default())
+}
+
+/// Similar to [`from_bytes_iter`] but with options.
+pub fn from_bytes_iter_with_opts<'a, T>(
+    mut input: &'a [u8],
+    opts: DeOpts,
+) -> impl Iterator<Item = Result<T>> + 'a
+where
+    T: serde_de::Deserialize<'a> + 'a,
+{
+    std::iter::from_fn(move || {
+        if input.is_empty() {
+            return None;
+        }
+
+        match from_bytes_tracked_with_opts(input, opts.clone()) {
+            Ok((t, consumed)) => {
+                input = &input[consumed..];
+                Some(Ok(t))
+            }
+            Err(e) => {
+                input = &[];
+                Some(Err(e))
+            }
+        }
+    })
+}
+
+/// Similar to [`from_bytes_tracked`] but with options.
+pub fn from_bytes_tracked_with_opts<'a, T>(input: &'a [u8], opts: DeOpts) -> Result<(T, usize)>
+where
+    T: serde_de::Deserialize<'a>,
+{
     // Provide freindly error for the common case of passing GZip data to
     // `from_bytes`. This would be invalid starting data for NBT anyway.
     if input.starts_with(&GZIP_MAGIC_BYTES) {
@@ -459,6 +795,6 @@ where
     }
 
     let mut des = Deserializer::from_bytes(input, opts);
-    let t = T::deserialize(&mut des)?;
-    Ok(t)
+    let t = T::deserialize(&mut des).map_err(|e| e.with_offset(input.len() - des.remaining()))?;
+    Ok((t, input.len() - des.remaining()))
 }
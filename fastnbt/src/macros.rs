@@ -56,6 +56,54 @@
 /// });
 /// ```
 ///
+/// Numbers become the NBT type matching their Rust type, so a literal suffix
+/// can be used to pick the tag. Unsuffixed integers are `Int` and unsuffixed
+/// floats are `Double`, as in Rust. `bool` becomes a `Byte`.
+///
+/// ```rust
+/// # use fastnbt::{nbt, Value};
+/// let v = nbt!({
+///     "byte": 1_i8,
+///     "short": 1_i16,
+///     "int": 1,
+///     "long": 1_i64,
+///     "float": 1.0_f32,
+///     "double": 1.0,
+///     "bool": true,
+/// });
+///
+/// assert_eq!(v["byte"], Value::Byte(1));
+/// assert_eq!(v["short"], Value::Short(1));
+/// assert_eq!(v["int"], Value::Int(1));
+/// assert_eq!(v["long"], Value::Long(1));
+/// assert_eq!(v["float"], Value::Float(1.0));
+/// assert_eq!(v["double"], Value::Double(1.0));
+/// assert_eq!(v["bool"], Value::Byte(1));
+/// ```
+///
+/// Any other expression is converted with [`to_value`][`crate::to_value`], so
+/// variables, function calls and existing [`Value`][`crate::Value`]s can be
+/// spliced in. Expressions that are more than a single token can be wrapped
+/// in parentheses, and a variable can also be written `#name`. Keys can be
+/// any expression that converts into a `String` if it is parenthesized.
+///
+/// ```rust
+/// # use fastnbt::{nbt, Value};
+/// let pos = nbt!([I; 1, 64, -3]);
+/// let name = "stone";
+/// let key = String::from("count");
+///
+/// let v = nbt!({
+///     "pos": (pos.clone()),
+///     "name": #name,
+///     (key): [#name, name.to_uppercase()],
+/// });
+///
+/// assert_eq!(v["pos"], pos);
+/// assert_eq!(v["name"], Value::String("stone".to_owned()));
+/// assert_eq!(v["count"], nbt!(["stone", "STONE"]));
+/// ```
+///
 #[macro_export(local_inner_macros)]
 macro_rules! nbt {
     // Hide distracting implementation details from the generated rustdoc.
@@ -87,6 +135,16 @@ macro_rules! nbt_internal {
         nbt_internal!(@array [$($elems,)* nbt_internal!({$($map)*})] $($rest)*)
     };
 
+    // Next element is an interpolated variable followed by comma.
+    (@array [$($elems:expr,)*] # $next:ident, $($rest:tt)*) => {
+        nbt_internal!(@array [$($elems,)* nbt_internal!($next),] $($rest)*)
+    };
+
+    // Last element is an interpolated variable with no trailing comma.
+    (@array [$($elems:expr,)*] # $last:ident) => {
+        nbt_internal!(@array [$($elems,)* nbt_internal!($last)])
+    };
+
     // Next element is an expression followed by comma.
     (@array [$($elems:expr,)*] $next:expr, $($rest:tt)*) => {
         nbt_internal!(@array [$($elems,)* nbt_internal!($next),] $($rest)*)
@@ -178,6 +236,16 @@ macro_rules! nbt_internal {
         nbt_internal!(@object $object [$($key)+] (nbt_internal!({$($map)*})) $($rest)*);
     };
 
+    // Next value is an interpolated variable followed by comma.
+    (@object $object:ident ($($key:tt)+) (: # $value:ident , $($rest:tt)*) $copy:tt) => {
+        nbt_internal!(@object $object [$($key)+] (nbt_internal!($value)) , $($rest)*);
+    };
+
+    // Last value is an interpolated variable with no trailing comma.
+    (@object $object:ident ($($key:tt)+) (: # $value:ident) $copy:tt) => {
+        nbt_internal!(@object $object [$($key)+] (nbt_internal!($value)));
+    };
+
     // Next value is an expression followed by comma.
     (@object $object:ident ($($key:tt)+) (: $value:expr , $($rest:tt)*) $copy:tt) => {
         nbt_internal!(@object $object [$($key)+] (nbt_internal!($value)) , $($rest)*);
@@ -268,17 +336,22 @@ macro_rules! nbt_internal {
     };
 
     ({}) => {
-        $crate::Value::Compound(std::collections::HashMap::new())
+        $crate::Value::Compound($crate::value::Map::new())
     };
 
     ({ $($tt:tt)+ }) => {
         $crate::Value::Compound({
-            let mut object = std::collections::HashMap::new();
+            let mut object = $crate::value::Map::new();
             nbt_internal!(@object object () ($($tt)+) ($($tt)+));
             object
         })
     };
 
+    // An interpolated variable.
+    (# $value:ident) => {
+        nbt_internal!($value)
+    };
+
     // Any Serialize type: numbers, strings, struct literals, variables etc.
     ($other:expr) => {
         $crate::to_value(&$other).unwrap()
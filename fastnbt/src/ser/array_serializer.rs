@@ -50,8 +50,20 @@ impl<'a, W: Write> serde::Serializer for ArraySerializer<'a, W> {
             _ => panic!(),
         };
         let len = v.len() / stride;
-        self.ser.writer.write_len(len)?;
-        self.ser.writer.write_all(v)?;
+        self.ser.writer.write_len(len, self.ser.little_endian)?;
+
+        if self.ser.little_endian && stride > 1 {
+            // Array data is always big endian, so reverse each element.
+            let mut buf = [0u8; 8];
+            let buf = &mut buf[..stride];
+            for element in v.chunks(stride) {
+                buf.copy_from_slice(element);
+                buf.reverse();
+                self.ser.writer.write_all(buf)?;
+            }
+        } else {
+            self.ser.writer.write_all(v)?;
+        }
         Ok(())
     }
 
@@ -16,6 +16,12 @@
 //! `i128` or `u128`, an IntArray of length 4 will be produced. This is stored
 //! as big endian i.e. the most significant bit (and int) is first.
 //!
+//! # Booleans
+//!
+//! NBT has no boolean type. Like Minecraft, `bool` is serialized as a Byte of
+//! 0 or 1. Deserializing a `bool` accepts any integer, with non-zero being
+//! true.
+//!
 //! # Root compound name
 //!
 //! A valid NBT compound must have a name, including the root compound. For most
@@ -46,9 +52,38 @@
 //! let structure = todo!(); // make schematic
 //! let bytes = fastnbt::to_bytes_with_opts(&structure, SerOpts::new().root_name("Schematic")).unwrap();
 //! ```
+//!
+//! # Streaming
+//!
+//! [`to_writer`][`crate::to_writer`] does not build up the NBT in memory
+//! before writing it. Each value is written to the writer as it is visited,
+//! so very large structures can be written without holding a second copy of
+//! them in memory. Only the name of the field currently being written is
+//! buffered.
+//!
+//! The exception is [`SerOpts::sorted_keys(true)`][`crate::SerOpts::sorted_keys`].
+//! Every entry of each compound is serialized into a buffer so the entries can
+//! be written in key order once the compound ends, so memory grows with the
+//! size of the compound. As the root is a compound, the whole serialized value
+//! is held in memory before anything is written.
+//!
+//! Writing directly to a [`File`][`std::fs::File`] results in many small
+//! writes, so you will usually want to wrap it in a
+//! [`BufWriter`][`std::io::BufWriter`]:
+//!
+//! ```no_run
+//! use std::fs::File;
+//! use std::io::{BufWriter, Write};
+//! use fastnbt::Value;
+//!
+//! let world: Value = todo!(); // a very large value
+//! let mut writer = BufWriter::new(File::create("export.nbt").unwrap());
+//! fastnbt::to_writer(&mut writer, &world).unwrap();
+//! writer.flush().unwrap();
+//! ```
 mod array_serializer;
 mod name_serializer;
 mod serializer;
-mod write_nbt;
+pub(crate) mod write_nbt;
 
 pub use serializer::*;
@@ -1,6 +1,6 @@
 use std::{io::Write, mem};
 
-use byteorder::{BigEndian, WriteBytesExt};
+use byteorder::WriteBytesExt;
 use serde::{
     ser::{self, Impossible, SerializeTuple},
     Serialize,
@@ -28,6 +28,20 @@ pub struct Serializer<W: Write> {
     // NOTE: This is `mem:take`en, so is only valid at the start of serialization!
     pub(crate) root_name: String,
     pub(crate) serialize_root_name: bool,
+
+    // Whether compound entries are written sorted by key rather than in the
+    // order they are serialized.
+    pub(crate) sorted_keys: bool,
+
+    // Whether numbers are written little endian, as Bedrock Edition does.
+    pub(crate) little_endian: bool,
+}
+
+impl<W: Write> Serializer<W> {
+    /// Write the big endian bytes of a number in the byte order being used.
+    fn write_number<const N: usize>(&mut self, be_bytes: [u8; N]) -> Result<()> {
+        self.writer.write_number(be_bytes, self.little_endian)
+    }
 }
 
 macro_rules! no_root {
@@ -71,6 +85,7 @@ impl<'a, W: 'a + Write> serde::ser::Serializer for &'a mut Serializer<W> {
         // self.root_name elsewhere.
         let root_name = mem::take(&mut self.root_name);
         let serialize_root_name = mem::take(&mut self.serialize_root_name);
+        let entries = self.sorted_keys.then(Vec::new);
         Ok(SerializerMap {
             ser: self,
             key: None,
@@ -78,6 +93,7 @@ impl<'a, W: 'a + Write> serde::ser::Serializer for &'a mut Serializer<W> {
                 root_name: serialize_root_name.then_some(root_name),
             }),
             trailer: Some(Tag::End),
+            entries,
         })
     }
 
@@ -135,9 +151,17 @@ pub struct SerializerMap<'a, W: Write> {
     key: Option<Vec<u8>>,
     header: Option<DelayedHeader>,
     trailer: Option<Tag>,
+    // Serialized entries held back until the end of the map so that they can
+    // be sorted by key. None if keys are not being sorted.
+    entries: Option<Vec<(Vec<u8>, Vec<u8>)>>,
 }
 
-fn write_header(writer: &mut impl Write, header: DelayedHeader, actual_tag: Tag) -> Result<()> {
+fn write_header(
+    writer: &mut impl Write,
+    header: DelayedHeader,
+    actual_tag: Tag,
+    little_endian: bool,
+) -> Result<()> {
     match header {
         DelayedHeader::Root {
             root_name: outer_name,
@@ -148,17 +172,17 @@ fn write_header(writer: &mut impl Write, header: DelayedHeader, actual_tag: Tag)
             }
             writer.write_tag(Tag::Compound)?;
             if let Some(outer_name) = &outer_name {
-                writer.write_size_prefixed_str(&outer_name)?;
+                writer.write_size_prefixed_str(outer_name, little_endian)?;
             }
         }
         DelayedHeader::MapEntry { ref outer_name } => {
             writer.write_tag(actual_tag)?;
-            writer.write_u16::<BigEndian>(outer_name.len() as u16)?;
+            writer.write_str_len(outer_name.len(), little_endian)?;
             writer.write_all(outer_name)?;
         }
         DelayedHeader::List { len } => {
             writer.write_tag(actual_tag)?;
-            writer.write_len(len)?;
+            writer.write_len(len, little_endian)?;
         }
     };
     Ok(())
@@ -196,7 +220,12 @@ impl<'a, W: Write> serde::ser::SerializeMap for SerializerMap<'a, W> {
         };
 
         if let Some(header) = self.header.take() {
-            write_header(&mut self.ser.writer, header, outer_tag)?;
+            write_header(
+                &mut self.ser.writer,
+                header,
+                outer_tag,
+                self.ser.little_endian,
+            )?;
         }
 
         match std::str::from_utf8(&name) {
@@ -221,11 +250,33 @@ impl<'a, W: Write> serde::ser::SerializeMap for SerializerMap<'a, W> {
                     tag: Tag::LongArray,
                 })
             }
-            _ => value.serialize(&mut Delayed {
-                ser: &mut *self.ser,
-                header: Some(DelayedHeader::MapEntry { outer_name: name }),
-                is_list: false,
-            }),
+            _ => match &mut self.entries {
+                Some(entries) => {
+                    // Serialize the entry on the side, it gets written in
+                    // key order once the whole map has been seen.
+                    let mut entry = Serializer {
+                        writer: Vec::new(),
+                        root_name: String::new(),
+                        serialize_root_name: false,
+                        sorted_keys: true,
+                        little_endian: self.ser.little_endian,
+                    };
+                    value.serialize(&mut Delayed {
+                        ser: &mut entry,
+                        header: Some(DelayedHeader::MapEntry {
+                            outer_name: name.clone(),
+                        }),
+                        is_list: false,
+                    })?;
+                    entries.push((name, entry.writer));
+                    Ok(())
+                }
+                None => value.serialize(&mut Delayed {
+                    ser: &mut *self.ser,
+                    header: Some(DelayedHeader::MapEntry { outer_name: name }),
+                    is_list: false,
+                }),
+            },
         }
     }
 
@@ -235,7 +286,18 @@ impl<'a, W: Write> serde::ser::SerializeMap for SerializerMap<'a, W> {
                 // if we still have a header, that means that we haven't seen a
                 // single key, so it must be an empty compound, we need to write
                 // the bytes we have delayed then close off the compound.
-                write_header(&mut self.ser.writer, header, Tag::Compound)?;
+                write_header(
+                    &mut self.ser.writer,
+                    header,
+                    Tag::Compound,
+                    self.ser.little_endian,
+                )?;
+            }
+            if let Some(mut entries) = self.entries.take() {
+                entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+                for (_, entry) in entries {
+                    self.ser.writer.write_all(&entry)?;
+                }
             }
             self.ser.writer.write_tag(tag)?;
         }
@@ -348,7 +410,7 @@ struct Delayed<'a, W: Write + 'a> {
 impl<'a, W: Write + 'a> Delayed<'a, W> {
     fn write_header(&mut self, tag: Tag) -> Result<()> {
         if let Some(header) = self.header.take() {
-            write_header(&mut self.ser.writer, header, tag)?;
+            write_header(&mut self.ser.writer, header, tag, self.ser.little_endian)?;
         }
         Ok(())
     }
@@ -366,8 +428,7 @@ impl<'a, W: 'a + Write> serde::ser::Serializer for &'a mut Delayed<'a, W> {
     type SerializeStructVariant = Impossible<(), Error>;
 
     fn serialize_bool(self, v: bool) -> Result<()> {
-        self.write_header(Tag::Byte)?;
-        self.ser.serialize_bool(v)
+        self.serialize_i8(v as i8)
     }
 
     fn serialize_i8(self, v: i8) -> Result<()> {
@@ -378,19 +439,19 @@ impl<'a, W: 'a + Write> serde::ser::Serializer for &'a mut Delayed<'a, W> {
 
     fn serialize_i16(self, v: i16) -> Result<()> {
         self.write_header(Tag::Short)?;
-        self.ser.writer.write_i16::<BigEndian>(v)?;
+        self.ser.write_number(v.to_be_bytes())?;
         Ok(())
     }
 
     fn serialize_i32(self, v: i32) -> Result<()> {
         self.write_header(Tag::Int)?;
-        self.ser.writer.write_i32::<BigEndian>(v)?;
+        self.ser.write_number(v.to_be_bytes())?;
         Ok(())
     }
 
     fn serialize_i64(self, v: i64) -> Result<()> {
         self.write_header(Tag::Long)?;
-        self.ser.writer.write_i64::<BigEndian>(v)?;
+        self.ser.write_number(v.to_be_bytes())?;
         Ok(())
     }
 
@@ -400,11 +461,11 @@ impl<'a, W: 'a + Write> serde::ser::Serializer for &'a mut Delayed<'a, W> {
 
     fn serialize_u128(self, v: u128) -> Result<()> {
         self.write_header(Tag::IntArray)?;
-        self.ser.writer.write_len(4)?;
-        self.ser.writer.write_u32::<BigEndian>((v >> 96) as u32)?;
-        self.ser.writer.write_u32::<BigEndian>((v >> 64) as u32)?;
-        self.ser.writer.write_u32::<BigEndian>((v >> 32) as u32)?;
-        self.ser.writer.write_u32::<BigEndian>(v as u32)?;
+        self.ser.writer.write_len(4, self.ser.little_endian)?;
+        for shift in [96, 64, 32, 0] {
+            let int = (v >> shift) as u32;
+            self.ser.write_number(int.to_be_bytes())?;
+        }
         Ok(())
     }
 
@@ -416,50 +477,52 @@ impl<'a, W: 'a + Write> serde::ser::Serializer for &'a mut Delayed<'a, W> {
 
     fn serialize_u16(self, v: u16) -> Result<()> {
         self.write_header(Tag::Short)?;
-        self.ser.writer.write_u16::<BigEndian>(v)?;
+        self.ser.write_number(v.to_be_bytes())?;
         Ok(())
     }
 
     fn serialize_u32(self, v: u32) -> Result<()> {
         self.write_header(Tag::Int)?;
-        self.ser.writer.write_u32::<BigEndian>(v)?;
+        self.ser.write_number(v.to_be_bytes())?;
         Ok(())
     }
 
     fn serialize_u64(self, v: u64) -> Result<()> {
         self.write_header(Tag::Long)?;
-        self.ser.writer.write_u64::<BigEndian>(v)?;
+        self.ser.write_number(v.to_be_bytes())?;
         Ok(())
     }
 
     fn serialize_f32(self, v: f32) -> Result<()> {
         self.write_header(Tag::Float)?;
-        self.ser.writer.write_f32::<BigEndian>(v)?;
+        self.ser.write_number(v.to_be_bytes())?;
         Ok(())
     }
 
     fn serialize_f64(self, v: f64) -> Result<()> {
         self.write_header(Tag::Double)?;
-        self.ser.writer.write_f64::<BigEndian>(v)?;
+        self.ser.write_number(v.to_be_bytes())?;
         Ok(())
     }
 
     fn serialize_char(self, v: char) -> Result<()> {
         self.write_header(Tag::Int)?;
-        self.ser.writer.write_u32::<BigEndian>(v as u32)?;
+        self.ser.write_number((v as u32).to_be_bytes())?;
         Ok(())
     }
 
     fn serialize_str(self, v: &str) -> Result<()> {
         self.write_header(Tag::String)?;
-        self.ser.writer.write_size_prefixed_str(v)?;
+        self.ser
+            .writer
+            .write_size_prefixed_str(v, self.ser.little_endian)?;
         Ok(())
     }
 
     fn serialize_bytes(self, v: &[u8]) -> Result<()> {
         self.write_header(Tag::List)?;
         self.ser.writer.write_tag(Tag::Byte)?;
-        self.ser.writer.write_len(v.len())?;
+        self.ser.writer.write_len(v.len(), self.ser.little_endian)?;
         self.ser.writer.write_all(v)?;
         Ok(())
     }
@@ -499,7 +562,9 @@ impl<'a, W: 'a + Write> serde::ser::Serializer for &'a mut Delayed<'a, W> {
         variant: &'static str,
     ) -> Result<()> {
         self.write_header(Tag::String)?;
-        self.ser.writer.write_size_prefixed_str(variant)
+        self.ser
+            .writer
+            .write_size_prefixed_str(variant, self.ser.little_endian)
     }
 
     fn serialize_newtype_struct<T: ?Sized>(self, _name: &'static str, value: &T) -> Result<()>
@@ -544,7 +609,7 @@ impl<'a, W: 'a + Write> serde::ser::Serializer for &'a mut Delayed<'a, W> {
             // A list of end tags seems to be the way to go.
 
             self.ser.writer.write_tag(Tag::End)?;
-            self.ser.writer.write_u32::<BigEndian>(0)?; // ie len
+            self.ser.writer.write_len(0, self.ser.little_endian)?;
         }
 
         Ok(SerializerTuple {
@@ -573,11 +638,13 @@ impl<'a, W: 'a + Write> serde::ser::Serializer for &'a mut Delayed<'a, W> {
     }
 
     fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap> {
+        let entries = self.ser.sorted_keys.then(Vec::new);
         Ok(SerializerMap {
             ser: self.ser,
             key: None,
             header: self.header.take(),
             trailer: Some(Tag::End),
+            entries,
         })
     }
 
@@ -1,7 +1,7 @@
 use std::convert::TryInto;
 use std::io::Write;
 
-use byteorder::{BigEndian, WriteBytesExt};
+use byteorder::WriteBytesExt;
 
 use crate::error::{Error, Result};
 use crate::Tag;
@@ -12,20 +12,41 @@ pub(crate) trait WriteNbt: Write {
         Ok(())
     }
 
-    fn write_size_prefixed_str(&mut self, key: &str) -> Result<()> {
+    fn write_size_prefixed_str(&mut self, key: &str, little_endian: bool) -> Result<()> {
         let key = cesu8::to_java_cesu8(key);
-        let len_bytes = key.len() as u16;
-        self.write_u16::<BigEndian>(len_bytes)?;
+        self.write_str_len(key.len(), little_endian)?;
         self.write_all(&key)?;
         Ok(())
     }
 
-    fn write_len(&mut self, len: usize) -> Result<()> {
-        self.write_u32::<BigEndian>(
-            len.try_into()
-                .map_err(|_| Error::bespoke("len too large".to_owned()))?,
-        )?;
+    /// Write the length prefix of an already encoded string.
+    fn write_str_len(&mut self, len: usize, little_endian: bool) -> Result<()> {
+        let len: u16 = len
+            .try_into()
+            .map_err(|_| Error::bespoke("string too long for NBT".to_owned()))?;
 
+        self.write_number(len.to_be_bytes(), little_endian)
+    }
+
+    fn write_len(&mut self, len: usize, little_endian: bool) -> Result<()> {
+        let len: u32 = len
+            .try_into()
+            .map_err(|_| Error::bespoke("len too large".to_owned()))?;
+
+        self.write_number(len.to_be_bytes(), little_endian)
+    }
+
+    /// Write the big endian bytes of a number, reversed if writing little
+    /// endian.
+    fn write_number<const N: usize>(
+        &mut self,
+        mut be_bytes: [u8; N],
+        little_endian: bool,
+    ) -> Result<()> {
+        if little_endian {
+            be_bytes.reverse();
+        }
+        self.write_all(&be_bytes)?;
         Ok(())
     }
 }
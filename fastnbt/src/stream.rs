@@ -1,8 +1,13 @@
 //! Allows streaming of NBT data without prior knowledge of the structure.
 
 use super::Tag;
-use byteorder::{BigEndian, ReadBytesExt};
-use std::{convert::TryFrom, io::Read, str};
+use crate::ser::write_nbt::WriteNbt;
+use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
+use std::{
+    convert::TryFrom,
+    io::{Read, Write},
+    str,
+};
 
 /// An optional `String`.
 pub type Name = Option<String>;
@@ -60,6 +65,10 @@ pub enum ErrorKind {
     /// Expected unicode data but was not valid. Parser remains valid if just
     /// this value was not unicode. Contained bytes are the invalid unicode data.
     Nonunicode(Vec<u8>),
+
+    /// Compounds and lists were nested deeper than the parser's maximum
+    /// depth. See [`Parser::max_depth`].
+    MaxDepth,
 }
 
 impl Error {
@@ -96,6 +105,13 @@ impl Error {
         }
     }
 
+    fn max_depth(max: usize) -> Self {
+        Self {
+            msg: format!("exceeded max depth of {}", max),
+            kind: ErrorKind::MaxDepth,
+        }
+    }
+
     fn eof() -> Self {
         Self {
             msg: "EOF".into(),
@@ -128,11 +144,33 @@ impl From<std::io::Error> for Error {
     }
 }
 
+impl From<crate::error::Error> for Error {
+    fn from(e: crate::error::Error) -> Self {
+        Self::bespoke(e.to_string())
+    }
+}
+
 /// Convenience type for Result.
 pub type Result<T> = std::result::Result<T, Error>;
 
+pub use crate::DEFAULT_MAX_DEPTH;
+
 /// Parser can take any reader and parse it as NBT data. Does not do decompression.
 ///
+/// Values are pulled from the parser one at a time with `next`, so only the
+/// current value is held in memory. This allows scanning through very large
+/// NBT, such as a whole region's worth of chunks, without building a complete
+/// [`Value`][`crate::Value`].
+///
+/// # Untrusted input
+///
+/// The parser only keeps a small record of each compound and list it is
+/// inside. To stop malicious input growing this without bound, nesting deeper
+/// than [`max_depth`][`Parser::max_depth`] is an error. Lists and arrays with
+/// negative lengths are also errors. Array lengths are not trusted for
+/// preallocating memory, so a huge length with little data behind it fails
+/// with an EOF rather than a huge allocation.
+///
 /// # Examples
 ///
 /// ## Dump NBT
@@ -211,6 +249,7 @@ pub type Result<T> = std::result::Result<T, Error>;
 pub struct Parser<R: Read> {
     reader: R,
     layers: Vec<Layer>,
+    max_depth: usize,
 }
 
 impl<R: Read> Parser<R> {
@@ -219,9 +258,23 @@ impl<R: Read> Parser<R> {
         Self {
             reader,
             layers: Vec::new(),
+            max_depth: DEFAULT_MAX_DEPTH,
         }
     }
 
+    /// Set the maximum depth of nested compounds and lists. Entering a
+    /// compound or list beyond this depth returns an error of kind
+    /// [`ErrorKind::MaxDepth`]. Defaults to [`DEFAULT_MAX_DEPTH`].
+    pub fn max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
+
+    /// The number of compounds and lists the parser is currently inside.
+    pub fn depth(&self) -> usize {
+        self.layers.len()
+    }
+
     /// Parse the next value from the input.
     #[allow(clippy::should_implement_trait)]
     pub fn next(&mut self) -> Result<Value> {
@@ -321,46 +374,69 @@ impl<R: Read> Parser<R> {
             Tag::Float => Ok(Value::Float(name, self.reader.read_f32::<BigEndian>()?)),
             Tag::Double => Ok(Value::Double(name, self.reader.read_f64::<BigEndian>()?)),
             Tag::Compound => {
-                self.layers.push(Layer::Compound);
+                self.push_layer(Layer::Compound)?;
                 Ok(Value::Compound(name))
             }
             Tag::End => panic!("end tag should have returned early"),
             Tag::List => {
                 let element_tag = self.reader.read_u8()?;
                 let element_tag = u8_to_tag(element_tag)?;
-                let size = self.reader.read_i32::<BigEndian>()?;
-                self.layers.push(Layer::List(element_tag, size));
-                Ok(Value::List(name, element_tag, size))
+                let size = self.read_len()?;
+                self.push_layer(Layer::List(element_tag, size as i32))?;
+                Ok(Value::List(name, element_tag, size as i32))
             }
             Tag::String => Ok(Value::String(name, self.read_size_prefixed_string()?)),
             Tag::ByteArray => {
-                let size = self.reader.read_i32::<BigEndian>()?;
-                let mut buf = vec![0u8; size as usize];
-                self.reader.read_exact(&mut buf[..])?;
+                let size = self.read_len()?;
+                let mut buf = Vec::with_capacity(size.min(MAX_PREALLOC));
+                (&mut self.reader).take(size as u64).read_to_end(&mut buf)?;
+                if buf.len() != size {
+                    return Err(Error::from(std::io::Error::from(
+                        std::io::ErrorKind::UnexpectedEof,
+                    )));
+                }
                 Ok(Value::ByteArray(name, vec_u8_into_i8(buf)))
             }
             Tag::IntArray => {
-                let size = self.reader.read_i32::<BigEndian>()?;
-                let mut buf = vec![0i32; size as usize];
-                for i in 0..size {
-                    buf[i as usize] = self.reader.read_i32::<BigEndian>()?;
+                let size = self.read_len()?;
+                let mut buf = Vec::with_capacity(size.min(MAX_PREALLOC));
+                for _ in 0..size {
+                    buf.push(self.reader.read_i32::<BigEndian>()?);
                 }
 
                 Ok(Value::IntArray(name, buf))
             }
             Tag::LongArray => {
-                let size = self.reader.read_i32::<BigEndian>()?;
-                let mut buf = vec![0i64; size as usize];
-                for i in 0..size {
-                    buf[i as usize] = self.reader.read_i64::<BigEndian>()?;
+                let size = self.read_len()?;
+                let mut buf = Vec::with_capacity(size.min(MAX_PREALLOC));
+                for _ in 0..size {
+                    buf.push(self.reader.read_i64::<BigEndian>()?);
                 }
 
                 Ok(Value::LongArray(name, buf))
             }
         }
     }
+
+    /// Read the length of a list or array, which must not be negative.
+    fn read_len(&mut self) -> Result<usize> {
+        let len = self.reader.read_i32::<BigEndian>()?;
+        usize::try_from(len).map_err(|_| Error::bespoke(format!("negative length: {}", len)))
+    }
+
+    fn push_layer(&mut self, layer: Layer) -> Result<()> {
+        if self.layers.len() >= self.max_depth {
+            return Err(Error::max_depth(self.max_depth));
+        }
+        self.layers.push(layer);
+        Ok(())
+    }
 }
 
+// Maximum number of elements to allocate for ahead of reading an array. Larger
+// arrays grow as they are read, so bogus lengths can't allocate much.
+const MAX_PREALLOC: usize = 16 * 1024;
+
 /// Parse the input until the compound we are currently inside is complete.
 /// Handles inner compounds by skipping those as well.
 pub fn skip_compound<R: Read>(parser: &mut Parser<R>) -> Result<()> {
@@ -401,6 +477,242 @@ pub fn find_list<R: Read>(parser: &mut Parser<R>, name: Option<&str>) -> Result<
     }
 }
 
+/// A writer of NBT values, the counterpart to [`Parser`]. This allows writing
+/// NBT where the structure is only known as it is written, without building a
+/// full [`Value`][`crate::Value`] first.
+///
+/// Values inside compounds must have a name, and elements of lists must not.
+/// Lists are started with their element tag and length, and exactly that many
+/// elements of that tag must be written before ending the list. Mistakes are
+/// reported as errors rather than writing invalid NBT, but the writer should
+/// not be used after an error.
+///
+/// ```
+/// # use fastnbt::stream::{Result, Writer};
+/// # use fastnbt::{nbt, Tag};
+/// # fn main() -> Result<()> {
+/// let mut writer = Writer::new(Vec::new());
+///
+/// writer.begin_compound(Some(""))?;
+/// writer.write_string(Some("name"), "Steve")?;
+/// writer.begin_list(Some("Pos"), Tag::Double, 3)?;
+/// for p in [1.0, 64.0, -3.5] {
+///     writer.write_double(None, p)?;
+/// }
+/// writer.end()?; // the list
+/// writer.end()?; // the compound
+///
+/// let written: fastnbt::Value = fastnbt::from_bytes(&writer.into_inner()).unwrap();
+/// assert_eq!(written, nbt!({"name": "Steve", "Pos": [1.0, 64.0, -3.5]}));
+/// # Ok(())
+/// # }
+/// ```
+///
+/// Values from a [`Parser`] can be written directly with
+/// [`write`][`Writer::write`].
+pub struct Writer<W: Write> {
+    writer: W,
+    layers: Vec<Layer>,
+}
+
+impl<W: Write> Writer<W> {
+    /// Create a new writer for the given writer.
+    pub fn new(writer: W) -> Self {
+        Self {
+            writer,
+            layers: Vec::new(),
+        }
+    }
+
+    /// The number of compounds and lists the writer is currently inside.
+    pub fn depth(&self) -> usize {
+        self.layers.len()
+    }
+
+    /// Gets a reference to the underlying writer.
+    pub fn get_ref(&self) -> &W {
+        &self.writer
+    }
+
+    /// Gets a mutable reference to the underlying writer.
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.writer
+    }
+
+    /// Consumes this writer, returning the underlying writer.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+
+    /// Write a value as produced by a [`Parser`].
+    pub fn write(&mut self, value: &Value) -> Result<()> {
+        match value {
+            Value::CompoundEnd => match self.layers.last() {
+                Some(Layer::Compound) => self.end(),
+                _ => Err(Error::bespoke("compound end outside of compound")),
+            },
+            Value::ListEnd => match self.layers.last() {
+                Some(Layer::List(..)) => self.end(),
+                _ => Err(Error::bespoke("list end outside of list")),
+            },
+            Value::Byte(n, v) => self.write_byte(n.as_deref(), *v),
+            Value::Short(n, v) => self.write_short(n.as_deref(), *v),
+            Value::Int(n, v) => self.write_int(n.as_deref(), *v),
+            Value::Long(n, v) => self.write_long(n.as_deref(), *v),
+            Value::Float(n, v) => self.write_float(n.as_deref(), *v),
+            Value::Double(n, v) => self.write_double(n.as_deref(), *v),
+            Value::ByteArray(n, v) => self.write_byte_array(n.as_deref(), v),
+            Value::String(n, v) => self.write_string(n.as_deref(), v),
+            Value::List(n, tag, len) => self.begin_list(n.as_deref(), *tag, *len),
+            Value::Compound(n) => self.begin_compound(n.as_deref()),
+            Value::IntArray(n, v) => self.write_int_array(n.as_deref(), v),
+            Value::LongArray(n, v) => self.write_long_array(n.as_deref(), v),
+        }
+    }
+
+    /// Start a compound. Values written after this are inside the compound
+    /// until [`end`][`Writer::end`] is called.
+    pub fn begin_compound(&mut self, name: Option<&str>) -> Result<()> {
+        self.header(Tag::Compound, name)?;
+        self.layers.push(Layer::Compound);
+        Ok(())
+    }
+
+    /// Start a list of `len` elements of type `tag`. Exactly `len` elements
+    /// must be written before calling [`end`][`Writer::end`]. Empty lists
+    /// usually have the element tag [`Tag::End`].
+    pub fn begin_list(&mut self, name: Option<&str>, tag: Tag, len: i32) -> Result<()> {
+        if len < 0 {
+            return Err(Error::bespoke(format!("negative list length: {len}")));
+        }
+        if tag == Tag::End && len != 0 {
+            return Err(Error::bespoke("list of End tags must be empty"));
+        }
+
+        self.header(Tag::List, name)?;
+        self.writer.write_tag(tag)?;
+        self.writer.write_i32::<BigEndian>(len)?;
+        self.layers.push(Layer::List(tag, len));
+        Ok(())
+    }
+
+    /// End the current compound or list.
+    pub fn end(&mut self) -> Result<()> {
+        match self.layers.pop() {
+            Some(Layer::Compound) => self.writer.write_tag(Tag::End)?,
+            Some(Layer::List(_, 0)) => {}
+            Some(Layer::List(_, remaining)) => {
+                return Err(Error::bespoke(format!(
+                    "list ended with {remaining} elements still to write"
+                )))
+            }
+            None => return Err(Error::bespoke("end outside of compound or list")),
+        }
+        Ok(())
+    }
+
+    /// Write a byte. The name must be `Some`, or `None` inside a list.
+    pub fn write_byte(&mut self, name: Option<&str>, v: i8) -> Result<()> {
+        self.header(Tag::Byte, name)?;
+        Ok(self.writer.write_i8(v)?)
+    }
+
+    /// Write a short. The name must be `Some`, or `None` inside a list.
+    pub fn write_short(&mut self, name: Option<&str>, v: i16) -> Result<()> {
+        self.header(Tag::Short, name)?;
+        Ok(self.writer.write_i16::<BigEndian>(v)?)
+    }
+
+    /// Write an int. The name must be `Some`, or `None` inside a list.
+    pub fn write_int(&mut self, name: Option<&str>, v: i32) -> Result<()> {
+        self.header(Tag::Int, name)?;
+        Ok(self.writer.write_i32::<BigEndian>(v)?)
+    }
+
+    /// Write a long. The name must be `Some`, or `None` inside a list.
+    pub fn write_long(&mut self, name: Option<&str>, v: i64) -> Result<()> {
+        self.header(Tag::Long, name)?;
+        Ok(self.writer.write_i64::<BigEndian>(v)?)
+    }
+
+    /// Write a float. The name must be `Some`, or `None` inside a list.
+    pub fn write_float(&mut self, name: Option<&str>, v: f32) -> Result<()> {
+        self.header(Tag::Float, name)?;
+        Ok(self.writer.write_f32::<BigEndian>(v)?)
+    }
+
+    /// Write a double. The name must be `Some`, or `None` inside a list.
+    pub fn write_double(&mut self, name: Option<&str>, v: f64) -> Result<()> {
+        self.header(Tag::Double, name)?;
+        Ok(self.writer.write_f64::<BigEndian>(v)?)
+    }
+
+    /// Write a string. The name must be `Some`, or `None` inside a list.
+    pub fn write_string(&mut self, name: Option<&str>, v: &str) -> Result<()> {
+        self.header(Tag::String, name)?;
+        Ok(self.writer.write_size_prefixed_str(v, false)?)
+    }
+
+    /// Write a byte array. The name must be `Some`, or `None` inside a list.
+    pub fn write_byte_array(&mut self, name: Option<&str>, v: &[i8]) -> Result<()> {
+        self.header(Tag::ByteArray, name)?;
+        self.writer.write_len(v.len(), false)?;
+        for b in v {
+            self.writer.write_i8(*b)?;
+        }
+        Ok(())
+    }
+
+    /// Write an int array. The name must be `Some`, or `None` inside a list.
+    pub fn write_int_array(&mut self, name: Option<&str>, v: &[i32]) -> Result<()> {
+        self.header(Tag::IntArray, name)?;
+        self.writer.write_len(v.len(), false)?;
+        for i in v {
+            self.writer.write_i32::<BigEndian>(*i)?;
+        }
+        Ok(())
+    }
+
+    /// Write a long array. The name must be `Some`, or `None` inside a list.
+    pub fn write_long_array(&mut self, name: Option<&str>, v: &[i64]) -> Result<()> {
+        self.header(Tag::LongArray, name)?;
+        self.writer.write_len(v.len(), false)?;
+        for l in v {
+            self.writer.write_i64::<BigEndian>(*l)?;
+        }
+        Ok(())
+    }
+
+    /// Write the tag and name of a value, or check it is the right tag if we
+    /// are in a list. Lists elements have no header.
+    fn header(&mut self, tag: Tag, name: Option<&str>) -> Result<()> {
+        match self.layers.last_mut() {
+            Some(Layer::List(element_tag, remaining)) => {
+                if name.is_some() {
+                    return Err(Error::bespoke("list elements cannot have a name"));
+                }
+                if *element_tag != tag {
+                    return Err(Error::bespoke(format!(
+                        "wrote {tag:?} to a list of {element_tag:?}"
+                    )));
+                }
+                if *remaining == 0 {
+                    return Err(Error::bespoke("too many elements written to list"));
+                }
+                *remaining -= 1;
+                Ok(())
+            }
+            Some(Layer::Compound) | None => {
+                let name =
+                    name.ok_or_else(|| Error::bespoke(format!("{tag:?} value needs a name")))?;
+                self.writer.write_tag(tag)?;
+                self.writer.write_size_prefixed_str(name, false)?;
+                Ok(())
+            }
+        }
+    }
+}
+
 // Thanks to https://stackoverflow.com/a/59707887
 fn vec_u8_into_i8(v: Vec<u8>) -> Vec<i8> {
     // ideally we'd use Vec::into_raw_parts, but it's unstable,
@@ -0,0 +1,104 @@
+use crate::{ByteArray, IntArray, LongArray};
+
+#[test]
+fn arrays_collect_from_iterator() {
+    let bytes: ByteArray = (1..=3).collect();
+    let ints: IntArray = (1..=3).collect();
+    let longs: LongArray = (1..=3).collect();
+
+    assert_eq!(bytes, ByteArray::new(vec![1, 2, 3]));
+    assert_eq!(ints, IntArray::new(vec![1, 2, 3]));
+    assert_eq!(longs, LongArray::new(vec![1, 2, 3]));
+}
+
+#[test]
+fn arrays_into_iterator() {
+    let mut longs = LongArray::new(vec![1, 2, 3]);
+
+    for v in &mut longs {
+        *v *= 2;
+    }
+
+    let mut by_ref = vec![];
+    for v in &longs {
+        by_ref.push(*v);
+    }
+    assert_eq!(by_ref, [2, 4, 6]);
+
+    let by_value: Vec<i64> = longs.into_iter().collect();
+    assert_eq!(by_value, [2, 4, 6]);
+
+    let remapped: IntArray = IntArray::new(vec![0, 1, 0])
+        .into_iter()
+        .map(|i| i + 10)
+        .collect();
+    assert_eq!(remapped, IntArray::new(vec![10, 11, 10]));
+
+    let sum: i32 = ByteArray::new(vec![1, 2, 3])
+        .into_iter()
+        .map(i32::from)
+        .sum();
+    assert_eq!(sum, 6);
+}
+
+#[test]
+fn byte_array_conversions() {
+    let arr = ByteArray::from_bytes(&[0, 1, 128, 255]);
+    assert_eq!(*arr, [0, 1, -128, -1]);
+    assert_eq!(arr.to_bytes(), [0, 1, 128, 255]);
+    assert_eq!(ByteArray::from_buf(arr.to_bytes()), arr);
+    assert_eq!(arr.as_bool_vec(), [false, true, true, true]);
+
+    let flags = ByteArray::from(&[true, false][..]);
+    assert_eq!(flags, ByteArray::new(vec![1, 0]));
+
+    let slice: &[i8] = flags.as_ref();
+    assert_eq!(slice, [1, 0]);
+}
+
+#[test]
+fn bit_reader_padded() {
+    // 9-bit values, 7 to a long with a bit of padding.
+    let values: Vec<u64> = (0..10).map(|i| i * 50).collect();
+    let mut longs = vec![0i64; 2];
+    for (i, v) in values.iter().enumerate() {
+        longs[i / 7] |= (*v as i64) << (i % 7 * 9);
+    }
+    longs[0] |= 1 << 63; // padding bit, should be ignored.
+
+    let arr = LongArray::new(longs);
+    assert_eq!(arr.bit_reader(9, true).count(), 14);
+    assert_eq!(arr.bit_reader(9, true).take(10).collect::<Vec<_>>(), values);
+}
+
+#[test]
+fn bit_reader_straddling() {
+    // three 30-bit values, the third straddles both longs.
+    let a = 0x2aaa_aaaa_u64;
+    let b = 0x1555_5555_u64;
+    let c = 0x3fff_0001_u64;
+    let arr = LongArray::new(vec![(a | b << 30 | c << 60) as i64, (c >> 4) as i64]);
+
+    assert_eq!(arr.bit_reader(30, false).collect::<Vec<_>>(), [a, b, c, 0]);
+    assert_eq!(
+        LongArray::new(vec![-1])
+            .bit_reader(64, false)
+            .collect::<Vec<_>>(),
+        [u64::MAX]
+    );
+}
+
+#[test]
+fn unsigned_views() {
+    let bytes = ByteArray::new(vec![0, 127, -128, -1]);
+    assert_eq!(bytes.as_u8_slice(), [0, 127, 128, 255]);
+    assert_eq!(bytes.as_u8_slice(), bytes.to_bytes());
+
+    let ints = IntArray::new(vec![0, i32::MAX, i32::MIN, -1]);
+    assert_eq!(ints.as_u32_slice(), [0, 0x7fff_ffff, 0x8000_0000, u32::MAX]);
+
+    let longs = LongArray::new(vec![i64::MIN, -1]);
+    assert_eq!(longs.as_u64_slice(), [1 << 63, u64::MAX]);
+
+    assert!(ByteArray::new(vec![]).as_u8_slice().is_empty());
+}
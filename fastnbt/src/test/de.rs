@@ -5,9 +5,10 @@ use serde::{Deserialize, Serialize};
 use crate::{
     borrow,
     error::{Error, Result},
-    from_bytes, from_bytes_with_opts, from_reader, nbt,
+    from_bytes, from_bytes_iter, from_bytes_tracked, from_bytes_with_opts, from_reader,
+    from_reader_with_opts, nbt,
     test::builder::Builder,
-    to_bytes, ByteArray, DeOpts, IntArray, LongArray, Tag, Value,
+    to_bytes, to_bytes_with_opts, ByteArray, DeOpts, IntArray, LongArray, SerOpts, Tag, Value,
 };
 
 #[derive(Debug, Serialize, Deserialize, PartialEq)]
@@ -39,6 +40,67 @@ fn descriptive_error_on_gzip_magic() {
     assert!(e.to_string().to_lowercase().contains("gzip"));
 }
 
+#[test]
+fn error_has_offset_when_from_bytes() {
+    let input = Builder::new()
+        .start_compound("")
+        .int("a", 1)
+        .tag(Tag::Byte)
+        .name("b")
+        .build(); // missing the byte payload.
+
+    let err = from_bytes::<Value>(&input).unwrap_err();
+    assert_eq!(err.offset(), Some(input.len()));
+    assert!(err.to_string().contains(&format!("offset {}", input.len())));
+
+    // Invalid tag is found once the tag byte has been read.
+    let mut input = Builder::new().start_compound("").int("a", 1).build();
+    let tag_at = input.len();
+    input.push(42);
+    let err = from_bytes::<Value>(&input).unwrap_err();
+    assert_eq!(err.offset(), Some(tag_at + 1));
+
+    // Readers do not track their position.
+    let err = from_reader::<_, Value>(input.as_slice()).unwrap_err();
+    assert_eq!(err.offset(), None);
+}
+
+#[cfg(feature = "compression")]
+#[test]
+fn compressed_reader_gzip_zlib_and_raw() {
+    use crate::from_compressed_reader;
+    use flate2::{
+        write::{GzEncoder, ZlibEncoder},
+        Compression,
+    };
+    use std::io::Write;
+
+    let expected = nbt!({"abc": 1_i8, "def": "hello"});
+    let raw = to_bytes(&expected).unwrap();
+
+    let mut gz = GzEncoder::new(Vec::new(), Compression::default());
+    gz.write_all(&raw).unwrap();
+    let gz = gz.finish().unwrap();
+
+    let mut zlib = ZlibEncoder::new(Vec::new(), Compression::default());
+    zlib.write_all(&raw).unwrap();
+    let zlib = zlib.finish().unwrap();
+
+    for input in [&raw, &gz, &zlib] {
+        let actual: Value = from_compressed_reader(input.as_slice()).unwrap();
+        assert_eq!(actual, expected);
+    }
+}
+
+#[cfg(feature = "compression")]
+#[test]
+fn compressed_reader_short_input() {
+    use crate::from_compressed_reader;
+
+    assert!(from_compressed_reader::<_, Value>(&[][..]).is_err());
+    assert!(from_compressed_reader::<_, Value>(&[0x1f][..]).is_err());
+}
+
 #[test]
 fn simple_byte() {
     #[derive(Debug, Deserialize, PartialEq)]
@@ -1003,11 +1065,14 @@ fn int_array_in_fullvalue() {
 
 #[test]
 fn trailing_bytes() {
-    // Can't really see a way to assert that there are no trailing bytes. We
-    // don't return how far in to the input we got.
+    // from_bytes ignores trailing bytes, from_bytes_tracked tells us where the
+    // NBT ended.
     let mut input = Builder::new().start_compound("").end_compound().build();
     input.push(1);
     let _v: Value = from_bytes(&input).unwrap();
+
+    let (_v, consumed): (Value, usize) = from_bytes_tracked(&input).unwrap();
+    assert_eq!(consumed, input.len() - 1);
 }
 
 #[test]
@@ -1807,3 +1872,244 @@ fn error_nested_non_named_compounds() {
     let v: Result<Value> = from_bytes_with_opts(data, DeOpts::network_nbt());
     assert!(v.is_err())
 }
+
+#[test]
+fn lenient_list_of_end() {
+    let payload = Builder::new()
+        .start_compound("object")
+        .start_list("list", Tag::End, 2)
+        .int("after", 1)
+        .end_compound()
+        .build();
+
+    assert!(from_bytes::<Value>(&payload).is_err());
+
+    let v: Value = from_bytes_with_opts(&payload, DeOpts::new().lenient_lists(true)).unwrap();
+    assert_eq!(v, nbt!({"list": [], "after": 1}));
+}
+
+#[test]
+fn lenient_list_keeps_elements_before_malformed_one() {
+    let payload = Builder::new()
+        .start_compound("object")
+        .int("before", 1)
+        .start_list("list", Tag::Compound, 3)
+        .int("a", 1)
+        .tag(Tag::End)
+        .int("a", 2)
+        .tag(Tag::End)
+        .raw_bytes(&[99]) // not a valid tag.
+        .end_compound()
+        .build();
+
+    assert!(from_bytes::<Value>(&payload).is_err());
+
+    let opts = DeOpts::new().lenient_lists(true);
+    let v: Value = from_bytes_with_opts(&payload, opts.clone()).unwrap();
+    assert_eq!(v, nbt!({"before": 1, "list": [{"a": 1}, {"a": 2}]}));
+
+    let v: Value = from_reader_with_opts(payload.as_slice(), opts).unwrap();
+    assert_eq!(v, nbt!({"before": 1, "list": [{"a": 1}, {"a": 2}]}));
+}
+
+#[test]
+fn lenient_list_still_fails_on_eof_and_depth() {
+    let opts = DeOpts::new().lenient_lists(true);
+
+    let mut truncated = Builder::new()
+        .start_compound("object")
+        .start_list("list", Tag::Int, 3)
+        .int_payload(1)
+        .int_payload(2)
+        .int_payload(3)
+        .end_compound()
+        .build();
+    truncated.truncate(truncated.len() - 3);
+
+    let err = from_bytes_with_opts::<Value>(&truncated, opts.clone()).unwrap_err();
+    assert!(err.to_string().contains("eof"), "{err}");
+    assert!(from_reader_with_opts::<_, Value>(truncated.as_slice(), opts.clone()).is_err());
+
+    let nested = Builder::new()
+        .start_compound("object")
+        .start_list("list", Tag::List, 1)
+        .start_anon_list(Tag::List, 1)
+        .start_anon_list(Tag::Int, 0)
+        .end_compound()
+        .build();
+
+    let err = from_bytes_with_opts::<Value>(&nested, opts.max_depth(2)).unwrap_err();
+    assert!(err.to_string().contains("max depth"), "{err}");
+}
+
+#[test]
+fn lenient_list_only_applies_to_untyped_lists() {
+    #[derive(Deserialize, Debug)]
+    struct V {
+        #[allow(dead_code)]
+        list: Vec<Value>,
+    }
+
+    let payload = Builder::new()
+        .start_compound("object")
+        .start_list("list", Tag::Compound, 2)
+        .int("a", 1)
+        .tag(Tag::End)
+        .raw_bytes(&[99]) // not a valid tag.
+        .end_compound()
+        .build();
+
+    let opts = DeOpts::new().lenient_lists(true);
+    assert!(from_bytes_with_opts::<V>(&payload, opts.clone()).is_err());
+
+    let v: Value = from_bytes_with_opts(&payload, opts).unwrap();
+    assert_eq!(v, nbt!({"list": [{"a": 1}]}));
+}
+
+#[test]
+fn concatenated_documents() {
+    let mut input = Builder::new()
+        .start_compound("")
+        .int("a", 1)
+        .end_compound()
+        .build();
+    input.extend(Builder::new().start_compound("").end_compound().build());
+
+    let vals: Vec<Value> = from_bytes_iter(&input).collect::<Result<_>>().unwrap();
+    assert_eq!(vals, [nbt!({"a": 1}), nbt!({})]);
+
+    // Stops after the first error.
+    input.push(99);
+    input.extend(Builder::new().start_compound("").end_compound().build());
+    let vals: Vec<Result<Value>> = from_bytes_iter(&input).collect();
+    assert_eq!(vals.len(), 3);
+    assert!(vals[2].is_err());
+
+    assert_eq!(from_bytes_iter::<Value>(&[]).count(), 0);
+}
+
+#[test]
+fn little_endian() {
+    #[rustfmt::skip]
+    let input = [
+        10, 0, 0, // root compound, unnamed
+        2, 1, 0, b's', 1, 2, // short
+        3, 1, 0, b'i', 4, 3, 2, 1, // int
+        6, 1, 0, b'd', 0, 0, 0, 0, 0, 0, 0xf0, 0x3f, // double 1.0
+        8, 1, 0, b't', 2, 0, b'h', b'i', // string
+        11, 1, 0, b'a', 2, 0, 0, 0, 1, 0, 0, 0, 2, 0, 0, 0, // int array
+        9, 1, 0, b'l', 4, 1, 0, 0, 0, 3, 0, 0, 0, 0, 0, 0, 0, // list of long
+        0,
+    ];
+
+    #[derive(Deserialize, Serialize, Debug, PartialEq)]
+    struct V {
+        s: i16,
+        i: i32,
+        d: f64,
+        t: String,
+        a: IntArray,
+        l: Vec<i64>,
+    }
+
+    let expected = V {
+        s: 0x0201,
+        i: 0x01020304,
+        d: 1.0,
+        t: "hi".to_owned(),
+        a: IntArray::new(vec![1, 2]),
+        l: vec![3],
+    };
+
+    let opts = DeOpts::new().little_endian(true);
+    let v: V = from_bytes_with_opts(&input, opts.clone()).unwrap();
+    assert_eq!(v, expected);
+
+    let v: V = from_reader_with_opts(Cursor::new(&input), opts.clone()).unwrap();
+    assert_eq!(v, expected);
+
+    let value: Value = from_bytes_with_opts(&input, opts).unwrap();
+    assert_eq!(value["a"], Value::IntArray(IntArray::new(vec![1, 2])));
+
+    let ser_opts = SerOpts::new().little_endian(true);
+    let bytes = to_bytes_with_opts(&expected, ser_opts).unwrap();
+    assert_eq!(bytes, input);
+}
+
+#[test]
+fn little_endian_round_trip() {
+    #[derive(Deserialize, Serialize, Debug, PartialEq)]
+    struct V {
+        uuid: u128,
+        longs: LongArray,
+        bytes: ByteArray,
+        float: f32,
+        nested: Vec<HashMap<String, i64>>,
+    }
+
+    let v = V {
+        uuid: 0x0102030405060708090a0b0c0d0e0f10,
+        longs: LongArray::new(vec![-1, 1 << 40]),
+        bytes: ByteArray::new(vec![1, 2, 3]),
+        float: 0.5,
+        nested: vec![HashMap::from([("a".to_owned(), 7)])],
+    };
+
+    let bytes = to_bytes_with_opts(&v, SerOpts::new().little_endian(true)).unwrap();
+    assert_ne!(bytes, to_bytes(&v).unwrap());
+
+    let back: V = from_bytes_with_opts(&bytes, DeOpts::new().little_endian(true)).unwrap();
+    assert_eq!(back, v);
+}
+
+#[test]
+fn flatten_extra_fields_into_map() {
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct V {
+        known: i32,
+        #[serde(flatten)]
+        rest: HashMap<String, Value>,
+    }
+
+    let payload = Builder::new()
+        .start_compound("")
+        .int("known", 1)
+        .byte("b", 2)
+        .short("s", 3)
+        .long("l", 4)
+        .double("d", 5.0)
+        .string("str", "hello")
+        .byte_array("ba", &[1, 2])
+        .int_array("ia", &[3, 4])
+        .long_array("la", &[5, 6])
+        .start_list("list", Tag::Int, 2)
+        .int_payload(7)
+        .int_payload(8)
+        .start_compound("nested")
+        .int("inner", 9)
+        .end_compound()
+        .end_compound()
+        .build();
+
+    let v: V = from_bytes(payload.as_slice()).unwrap();
+    let from_reader: V = from_reader(payload.as_slice()).unwrap();
+    assert_eq!(v, from_reader);
+
+    assert_eq!(v.known, 1);
+    assert_eq!(v.rest.len(), 10);
+    assert_eq!(v.rest["b"], Value::Byte(2));
+    assert_eq!(v.rest["s"], Value::Short(3));
+    assert_eq!(v.rest["l"], Value::Long(4));
+    assert_eq!(v.rest["d"], Value::Double(5.0));
+    assert_eq!(v.rest["str"], Value::String("hello".to_owned()));
+    assert_eq!(v.rest["ba"], Value::ByteArray(ByteArray::new(vec![1, 2])));
+    assert_eq!(v.rest["ia"], Value::IntArray(IntArray::new(vec![3, 4])));
+    assert_eq!(v.rest["la"], Value::LongArray(LongArray::new(vec![5, 6])));
+    assert_eq!(v.rest["list"], Value::List(vec![Value::Int(7), Value::Int(8)]));
+    assert_eq!(v.rest["nested"]["inner"], Value::Int(9));
+
+    // serializing writes the extra fields back alongside the known ones.
+    let bs = to_bytes(&v).unwrap();
+    let round_trip: V = from_bytes(&bs).unwrap();
+    assert_eq!(round_trip, v);
+}
@@ -1,6 +1,9 @@
-use std::{collections::HashMap, iter::FromIterator};
+use std::iter::FromIterator;
 
-use crate::{error::Result, from_bytes, test::builder::Builder, Tag, Value};
+use crate::{
+    error::Result, from_bytes, from_bytes_with_opts, test::builder::Builder, value::Map, DeOpts,
+    Tag, Value,
+};
 
 /// Bugs found via cargo-fuzz.
 
@@ -29,10 +32,7 @@ fn float_double() {
     //           C   name  f  name  ............ end compound
     let input = [10, 0, 0, 5, 0, 0, 0, 0, 0, 10, 0];
     let v: Value = from_bytes(&input).unwrap();
-    let expected = Value::Compound(HashMap::from_iter([(
-        "".to_string(),
-        Value::Float(1.4e-44),
-    )]));
+    let expected = Value::Compound(Map::from_iter([("".to_string(), Value::Float(1.4e-44))]));
 
     assert_eq!(expected, v);
 }
@@ -60,3 +60,81 @@ fn compound_using_nbt_token_key() {
     ];
     assert!(from_bytes::<Value>(data).is_err());
 }
+
+#[test]
+fn deeply_nested_lists_error_instead_of_overflow() {
+    let mut builder = Builder::new()
+        .start_compound("")
+        .start_list("a", Tag::List, 1);
+    for _ in 0..100_000 {
+        builder = builder.start_anon_list(Tag::List, 1);
+    }
+    let input = builder.build();
+
+    let v: Result<Value> = from_bytes(&input);
+    assert!(v.unwrap_err().to_string().contains("max depth"));
+
+    // Ignored values must not overflow either.
+    #[derive(Debug, serde::Deserialize)]
+    struct Ignore {}
+    let v: Result<Ignore> = from_bytes(&input);
+    assert!(v.unwrap_err().to_string().contains("max depth"));
+
+    #[derive(Debug, serde::Deserialize)]
+    struct Unit {
+        #[allow(dead_code)]
+        a: (),
+    }
+    let v: Result<Unit> = from_bytes(&input);
+    assert!(v.unwrap_err().to_string().contains("max depth"));
+}
+
+#[test]
+fn deeply_nested_compounds_error_instead_of_overflow() {
+    let mut builder = Builder::new().start_compound("");
+    for _ in 0..100_000 {
+        builder = builder.start_compound("a");
+    }
+    let input = builder.build();
+
+    let v: Result<Value> = from_bytes(&input);
+    assert!(v.unwrap_err().to_string().contains("max depth"));
+}
+
+#[test]
+fn default_max_depth_allows_vanilla_nesting() {
+    let nested = |depth: usize| {
+        let mut builder = Builder::new().start_compound("");
+        for _ in 0..depth {
+            builder = builder.start_compound("a");
+        }
+        for _ in 0..depth {
+            builder = builder.end_compound();
+        }
+        builder.end_compound().build()
+    };
+
+    let v: Result<Value> = from_bytes(&nested(crate::DEFAULT_MAX_DEPTH));
+    assert!(v.is_ok());
+
+    let v: Result<Value> = from_bytes(&nested(crate::DEFAULT_MAX_DEPTH + 1));
+    assert!(v.unwrap_err().to_string().contains("max depth"));
+}
+
+#[test]
+fn max_depth_can_be_set() {
+    let input = Builder::new()
+        .start_compound("")
+        .start_compound("a")
+        .start_compound("b")
+        .end_compound()
+        .end_compound()
+        .end_compound()
+        .build();
+
+    let v: Result<Value> = from_bytes_with_opts(&input, DeOpts::new().max_depth(2));
+    assert!(v.is_ok());
+
+    let v: Result<Value> = from_bytes_with_opts(&input, DeOpts::new().max_depth(1));
+    assert!(v.unwrap_err().to_string().contains("max depth"));
+}
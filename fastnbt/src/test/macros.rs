@@ -1,6 +1,4 @@
-use std::collections::HashMap;
-
-use crate::{ByteArray, IntArray, LongArray, Value};
+use crate::{value::Map, ByteArray, IntArray, LongArray, Value};
 
 #[test]
 fn nbt() {
@@ -43,10 +41,10 @@ fn nbt() {
         ])
     );
 
-    assert_eq!(nbt!({}), Value::Compound(HashMap::new()));
+    assert_eq!(nbt!({}), Value::Compound(Map::new()));
     assert_eq!(
         nbt!({ "key": "value" }),
-        Value::Compound(HashMap::from([(
+        Value::Compound(Map::from([(
             "key".to_owned(),
             Value::String("value".to_owned())
         ),]))
@@ -57,7 +55,7 @@ fn nbt() {
             "key2": 42,
             "key3": [4, 2],
         }),
-        Value::Compound(HashMap::from([
+        Value::Compound(Map::from([
             ("key1".to_owned(), Value::String("value1".to_owned())),
             ("key2".to_owned(), Value::Int(42)),
             (
@@ -83,3 +81,80 @@ fn nbt() {
         Value::LongArray(LongArray::new(vec![1, 2, 3]))
     );
 }
+
+#[test]
+fn nbt_typed_values_in_compound() {
+    assert_eq!(
+        nbt!({
+            "b": 1_i8,
+            "s": 2_i16,
+            "l": 3_i64,
+            "bytes": [B; 1_i8, -1_i8],
+            "list": [1_i16, 2_i16],
+        }),
+        Value::Compound(Map::from([
+            ("b".to_owned(), Value::Byte(1)),
+            ("s".to_owned(), Value::Short(2)),
+            ("l".to_owned(), Value::Long(3)),
+            (
+                "bytes".to_owned(),
+                Value::ByteArray(ByteArray::new(vec![1, -1]))
+            ),
+            (
+                "list".to_owned(),
+                Value::List(vec![Value::Short(1), Value::Short(2)])
+            ),
+        ]))
+    );
+}
+
+#[test]
+fn nbt_interpolates_runtime_values() {
+    let pos = nbt!([I; 1, 64, -3]);
+    let inventory = nbt!([{"id": "stone", "Count": 1_i8}]);
+    let name = "steve";
+    let health = 20.0_f32;
+    let key = "dynamic".to_owned();
+
+    assert_eq!(
+        nbt!({
+            "pos": (pos.clone()),
+            "inventory": inventory,
+            "name": #name,
+            "health": #health,
+            "tags": [#name, name.to_uppercase()],
+            (key): { "nested": #pos },
+        }),
+        Value::Compound(Map::from([
+            (
+                "pos".to_owned(),
+                Value::IntArray(IntArray::new(vec![1, 64, -3]))
+            ),
+            (
+                "inventory".to_owned(),
+                Value::List(vec![Value::Compound(Map::from([
+                    ("id".to_owned(), Value::String("stone".to_owned())),
+                    ("Count".to_owned(), Value::Byte(1)),
+                ]))])
+            ),
+            ("name".to_owned(), Value::String("steve".to_owned())),
+            ("health".to_owned(), Value::Float(20.0)),
+            (
+                "tags".to_owned(),
+                Value::List(vec![
+                    Value::String("steve".to_owned()),
+                    Value::String("STEVE".to_owned()),
+                ])
+            ),
+            (
+                "dynamic".to_owned(),
+                Value::Compound(Map::from([(
+                    "nested".to_owned(),
+                    Value::IntArray(IntArray::new(vec![1, 64, -3]))
+                )]))
+            ),
+        ]))
+    );
+
+    assert_eq!(nbt!(#name), Value::String("steve".to_owned()));
+}
@@ -10,6 +10,7 @@ mod de;
 #[allow(clippy::float_cmp)]
 mod value;
 
+mod arrays;
 pub mod builder;
 mod fuzz;
 mod macros;
@@ -17,6 +18,9 @@ mod minecraft_chunk;
 mod resources;
 mod ser;
 mod stream;
+#[cfg(feature = "uuid")]
+mod uuid;
+mod varint;
 
 #[derive(Debug, Serialize, Deserialize, PartialEq)]
 struct Single<T: Serialize> {
@@ -56,3 +60,25 @@ fn exhaustive_tag_check() {
         assert!(Tag::try_from(value).is_err())
     }
 }
+
+#[test]
+fn tag_properties() {
+    for value in 0..=12 {
+        let tag = Tag::try_from(value).unwrap();
+        assert!(!(tag.is_array() && tag.is_scalar()));
+        assert_eq!(
+            tag.element_size().is_some(),
+            tag.is_array() || tag.is_scalar(),
+            "{tag}"
+        );
+    }
+
+    assert!(Tag::IntArray.is_array());
+    assert!(!Tag::List.is_array());
+    assert!(Tag::Float.is_scalar());
+    assert!(!Tag::String.is_scalar());
+    assert_eq!(Tag::Int.element_size(), Some(4));
+    assert_eq!(Tag::IntArray.element_size(), Some(4));
+    assert_eq!(Tag::Double.element_size(), Some(8));
+    assert_eq!(Tag::Compound.element_size(), None);
+}
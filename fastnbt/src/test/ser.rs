@@ -1,10 +1,11 @@
-use std::{collections::HashMap, io::Cursor, iter::FromIterator};
+use std::{cell::RefCell, collections::HashMap, io::Cursor, iter::FromIterator, rc::Rc};
 
 use crate::{
-    borrow, from_bytes, from_bytes_with_opts,
+    borrow, from_bytes, from_bytes_with_opts, from_reader_with_opts,
     test::{resources::CHUNK_RAW_WITH_ENTITIES, Single, Wrap},
-    to_bytes, to_bytes_with_opts, to_writer_with_opts, ByteArray, DeOpts, IntArray, LongArray,
-    SerOpts, Tag, Value,
+    to_bytes, to_bytes_into, to_bytes_into_with_opts, to_bytes_with_opts, to_writer_with_opts,
+    value::Map,
+    ByteArray, DeOpts, IntArray, LongArray, SerOpts, Tag, Value,
 };
 use serde::{ser::SerializeMap, Deserialize, Serialize};
 use serde_bytes::{ByteBuf, Bytes};
@@ -454,7 +455,7 @@ fn nbt_long_array() {
 #[test]
 fn value_hashmap() {
     // let v = Value::Unit;
-    let v = Value::Compound(HashMap::from_iter([
+    let v = Value::Compound(Map::from_iter([
         ("a".to_string(), Value::Int(123)),
         ("b".to_string(), Value::Byte(123)),
     ]));
@@ -930,7 +931,7 @@ fn serialize_root_with_name() {
     to_writer_with_opts(&mut actual_via_writer, &Empty {}, opts.clone()).unwrap();
 
     let actual_via_bytes = to_bytes_with_opts(&Empty {}, opts.clone()).unwrap();
-    let actual_value = to_bytes_with_opts(&Value::Compound(HashMap::new()), opts.clone()).unwrap();
+    let actual_value = to_bytes_with_opts(&Value::Compound(Map::new()), opts.clone()).unwrap();
 
     assert_eq!(actual_via_bytes, expected);
     assert_eq!(actual_via_writer.into_inner(), expected);
@@ -959,3 +960,327 @@ fn serialize_networked_compound() {
         \x00"
     );
 }
+
+#[test]
+fn network_nbt_only_root_is_unnamed() {
+    // Shaped like a text component in a 1.20.2+ play packet. Only the root
+    // compound loses its name, nested compounds keep theirs.
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct HoverEvent {
+        action: String,
+        contents: String,
+    }
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct Text {
+        text: String,
+        #[serde(rename = "hoverEvent")]
+        hover_event: HoverEvent,
+        extra: Vec<Text>,
+    }
+
+    let text = Text {
+        text: "hi".to_owned(),
+        hover_event: HoverEvent {
+            action: "show_text".to_owned(),
+            contents: "there".to_owned(),
+        },
+        extra: vec![],
+    };
+
+    let expected = Builder::new()
+        .tag(Tag::Compound)
+        .string("text", "hi")
+        .start_compound("hoverEvent")
+        .string("action", "show_text")
+        .string("contents", "there")
+        .end_compound()
+        .start_list("extra", Tag::End, 0)
+        .end_compound()
+        .build();
+
+    for opts in [
+        SerOpts::network_nbt(),
+        SerOpts::network_nbt().sorted_keys(true),
+    ] {
+        let bytes = to_bytes_with_opts(&text, opts.clone()).unwrap();
+        let round_trip: Text = from_bytes_with_opts(&bytes, DeOpts::network_nbt()).unwrap();
+        assert_eq!(round_trip, text);
+
+        let value: Value = from_bytes_with_opts(&bytes, DeOpts::network_nbt()).unwrap();
+        assert_eq!(to_bytes_with_opts(&value, opts).unwrap().len(), bytes.len());
+    }
+
+    let bytes = to_bytes_with_opts(&text, SerOpts::network_nbt()).unwrap();
+    assert_eq!(bytes, expected);
+}
+
+#[test]
+fn network_nbt_value_round_trip() {
+    let values = [
+        nbt!({}),
+        nbt!({
+            "a": {"b": {"c": [1, 2]}},
+            "list": [{"x": 1}, {}],
+            "arr": [I; 1, 2],
+            "s": "x",
+        }),
+    ];
+
+    for v in values {
+        for opts in [
+            SerOpts::network_nbt(),
+            SerOpts::network_nbt().sorted_keys(true),
+        ] {
+            let bytes = to_bytes_with_opts(&v, opts.clone()).unwrap();
+            assert_eq!(bytes[0], Tag::Compound as u8);
+
+            let mut writer = vec![];
+            to_writer_with_opts(&mut writer, &v, opts.clone()).unwrap();
+            assert_eq!(writer, bytes);
+
+            let mut buf = vec![];
+            to_bytes_into_with_opts(&v, &mut buf, opts).unwrap();
+            assert_eq!(buf, bytes);
+
+            let from_slice: Value = from_bytes_with_opts(&bytes, DeOpts::network_nbt()).unwrap();
+            let from_reader: Value =
+                from_reader_with_opts(bytes.as_slice(), DeOpts::network_nbt()).unwrap();
+            assert_eq!(from_slice, v);
+            assert_eq!(from_reader, v);
+        }
+    }
+
+    // Only compounds can be the root, with or without a name.
+    assert!(to_bytes_with_opts(&nbt!(1), SerOpts::network_nbt()).is_err());
+}
+
+#[test]
+fn to_writer_writes_before_finishing() {
+    // A writer sharing its buffer, so that we can check how much has been
+    // written part way through serialization.
+    #[derive(Clone)]
+    struct SharedWriter(Rc<RefCell<Vec<u8>>>);
+
+    impl std::io::Write for SharedWriter {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.borrow_mut().write(buf)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    // Serializes as an int, but checks the first field has already made it to
+    // the writer.
+    struct Check(SharedWriter);
+
+    impl Serialize for Check {
+        fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            assert!(self.0 .0.borrow().len() > 1000);
+            serializer.serialize_i32(1)
+        }
+    }
+
+    #[derive(Serialize)]
+    struct V {
+        first: Vec<i64>,
+        second: Check,
+    }
+
+    let writer = SharedWriter(Default::default());
+    let v = V {
+        first: vec![0; 1000],
+        second: Check(writer.clone()),
+    };
+
+    crate::to_writer(writer.clone(), &v).unwrap();
+    assert_eq!(*writer.0.borrow(), to_bytes(&v).unwrap());
+}
+
+#[test]
+fn serialize_sorted_keys() {
+    #[derive(Serialize)]
+    struct Inner {
+        b: i32,
+        a: i32,
+    }
+
+    #[derive(Serialize)]
+    struct Outer {
+        z: i8,
+        missing: Option<i8>,
+        list: Vec<Inner>,
+        inner: Inner,
+        arr: LongArray,
+    }
+
+    let data = Outer {
+        z: 1,
+        missing: None,
+        list: vec![Inner { b: 2, a: 3 }],
+        inner: Inner { b: 4, a: 5 },
+        arr: LongArray::new(vec![6]),
+    };
+
+    let expected = Builder::new()
+        .start_compound("")
+        .long_array("arr", &[6])
+        .start_compound("inner")
+        .int("a", 5)
+        .int("b", 4)
+        .end_compound()
+        .start_list("list", Tag::Compound, 1)
+        .int("a", 3)
+        .int("b", 2)
+        .end_anon_compound()
+        .byte("z", 1)
+        .end_compound()
+        .build();
+
+    let opts = SerOpts::new().sorted_keys(true);
+    let actual = to_bytes_with_opts(&data, opts.clone()).unwrap();
+    assert_eq!(actual, expected);
+
+    // A Value gives the same bytes as the struct it came from.
+    let value: Value = from_bytes(&to_bytes(&data).unwrap()).unwrap();
+    let actual = to_bytes_with_opts(&value, opts).unwrap();
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn serialize_sorted_keys_only_when_asked() {
+    let data = nbt!({"b": 1_i8, "a": 2_i8});
+
+    let sorted = to_bytes_with_opts(&data, SerOpts::new().sorted_keys(true)).unwrap();
+    let expected = Builder::new()
+        .start_compound("")
+        .byte("a", 2)
+        .byte("b", 1)
+        .end_compound()
+        .build();
+    assert_eq!(sorted, expected);
+
+    // Default keeps the order the serializer is given, a struct keeps its
+    // field order.
+    #[derive(Serialize)]
+    struct BA {
+        b: i8,
+        a: i8,
+    }
+    let actual = to_bytes(&BA { b: 1, a: 2 }).unwrap();
+    let expected = Builder::new()
+        .start_compound("")
+        .byte("b", 1)
+        .byte("a", 2)
+        .end_compound()
+        .build();
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn serialize_into_reused_buffer() {
+    let mut buf = Vec::with_capacity(1024);
+    let capacity = buf.capacity();
+
+    to_bytes_into(
+        &Single {
+            val: "a long string value",
+        },
+        &mut buf,
+    )
+    .unwrap();
+    to_bytes_into(&Single { val: 1u8 }, &mut buf).unwrap();
+
+    assert_eq!(buf, to_bytes(&Single { val: 1u8 }).unwrap());
+    assert_eq!(buf.capacity(), capacity);
+
+    let opts = SerOpts::new().root_name("root");
+    to_bytes_into_with_opts(&Single { val: 1u8 }, &mut buf, opts.clone()).unwrap();
+    assert_eq!(buf, to_bytes_with_opts(&Single { val: 1u8 }, opts).unwrap());
+}
+
+#[test]
+fn serialize_bool_as_byte() {
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct V {
+        yes: bool,
+        no: bool,
+        list: Vec<bool>,
+    }
+
+    let v = V {
+        yes: true,
+        no: false,
+        list: vec![true, false],
+    };
+    let bs = to_bytes(&v).unwrap();
+
+    let expected = Builder::new()
+        .start_compound("")
+        .byte("yes", 1)
+        .byte("no", 0)
+        .start_list("list", Tag::Byte, 2)
+        .byte_payload(1)
+        .byte_payload(0)
+        .end_compound()
+        .build();
+
+    assert_eq!(bs, expected);
+    assert_eq!(from_bytes::<V>(&bs).unwrap(), v);
+}
+
+#[test]
+fn serialize_string_too_long() {
+    let long = "a".repeat(70_000);
+
+    let err = to_bytes(&Single { val: long.as_str() }).unwrap_err();
+    assert!(err.to_string().contains("string too long"), "{err}");
+
+    let mut map = HashMap::new();
+    map.insert(long.clone(), 1);
+    assert!(to_bytes(&map).is_err());
+
+    let err = to_bytes(&Value::Compound(Map::from_iter([(
+        "key".to_owned(),
+        Value::List(vec![Value::String(long)]),
+    )])))
+    .unwrap_err();
+    assert!(err.to_string().contains("string too long"), "{err}");
+
+    // Exactly the maximum is fine. Non-ASCII characters take more than one
+    // byte, so count towards the limit more.
+    let max = "a".repeat(65535);
+    let accented = "é".repeat(32768);
+    assert!(to_bytes(&Single { val: max }).is_ok());
+    assert!(to_bytes(&Single { val: accented }).is_err());
+}
+
+#[test]
+fn astral_plane_round_trip() {
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct V {
+        #[serde(rename = "😈")]
+        devil: String,
+        list: Vec<String>,
+    }
+
+    let v = V {
+        devil: "😈".to_owned(),
+        list: vec!["a😈b".to_owned()],
+    };
+    let bs = to_bytes_with_opts(&v, SerOpts::new().root_name("😈")).unwrap();
+
+    // Written as Java's CESU-8, never as plain UTF-8.
+    let utf8 = "😈".as_bytes();
+    let cesu8 = cesu8::to_java_cesu8("😈");
+    assert_ne!(utf8, &*cesu8);
+    assert!(!bs.windows(utf8.len()).any(|w| w == utf8));
+    assert_eq!(bs.windows(cesu8.len()).filter(|w| *w == &*cesu8).count(), 4);
+
+    assert_eq!(from_bytes::<V>(&bs).unwrap(), v);
+
+    let value: Value = from_bytes(&bs).unwrap();
+    assert_eq!(from_bytes::<V>(&to_bytes(&value).unwrap()).unwrap(), v);
+}
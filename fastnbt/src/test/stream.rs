@@ -1,5 +1,6 @@
 use super::builder::Builder;
-use crate::stream::{ErrorKind, Name, Parser, Result, Value};
+use super::resources::CHUNK_RAW_WITH_ENTITIES;
+use crate::stream::{ErrorKind, Name, Parser, Result, Value, Writer};
 use crate::Tag;
 
 fn name(n: &str) -> Name {
@@ -437,3 +438,169 @@ fn list_of_compounds() -> Result<()> {
     assert!(matches!(parser.next(), Err(e) if e.is_eof()));
     Ok(())
 }
+
+#[test]
+fn deeply_nested_lists_hit_max_depth() {
+    // A list of lists of lists... far deeper than the default max depth.
+    let mut builder = Builder::new().tag(Tag::List).name("deep");
+    for _ in 0..100_000 {
+        builder = builder.tag(Tag::List).int_payload(1);
+    }
+    let payload = builder.tag(Tag::End).int_payload(0).build();
+
+    let mut parser = Parser::new(payload.as_slice());
+    let err = loop {
+        match parser.next() {
+            Ok(_) => {}
+            Err(e) => break e,
+        }
+    };
+
+    assert!(matches!(err.kind(), ErrorKind::MaxDepth));
+    assert_eq!(parser.depth(), crate::stream::DEFAULT_MAX_DEPTH);
+}
+
+#[test]
+fn max_depth_can_be_set() -> Result<()> {
+    let payload = Builder::new()
+        .start_compound("a")
+        .start_compound("b")
+        .end_compound()
+        .end_compound()
+        .build();
+
+    let mut parser = Parser::new(payload.as_slice()).max_depth(2);
+    assert_eq!(parser.next()?, Value::Compound(name("a")));
+    assert_eq!(parser.next()?, Value::Compound(name("b")));
+    assert_eq!(parser.depth(), 2);
+
+    let mut parser = Parser::new(payload.as_slice()).max_depth(1);
+    assert_eq!(parser.next()?, Value::Compound(name("a")));
+    assert!(matches!(
+        parser.next().unwrap_err().kind(),
+        ErrorKind::MaxDepth
+    ));
+    Ok(())
+}
+
+#[test]
+fn negative_lengths_are_errors() {
+    for tag in [Tag::ByteArray, Tag::IntArray, Tag::LongArray] {
+        let payload = Builder::new().tag(tag).name("a").int_payload(-1).build();
+        let mut parser = Parser::new(payload.as_slice());
+        assert!(parser.next().is_err());
+    }
+
+    let payload = Builder::new()
+        .tag(Tag::List)
+        .name("a")
+        .tag(Tag::Byte)
+        .int_payload(-1)
+        .build();
+    let mut parser = Parser::new(payload.as_slice());
+    assert!(parser.next().is_err());
+}
+
+#[test]
+fn huge_array_length_without_data_is_eof() {
+    for tag in [Tag::ByteArray, Tag::IntArray, Tag::LongArray] {
+        let payload = Builder::new()
+            .tag(tag)
+            .name("a")
+            .int_payload(i32::MAX)
+            .byte_array_payload(&[1, 2, 3])
+            .build();
+        let mut parser = Parser::new(payload.as_slice());
+        assert!(matches!(
+            parser.next().unwrap_err().kind(),
+            ErrorKind::UnexpectedEof
+        ));
+    }
+}
+
+#[test]
+fn writer_builds_known_bytes() -> Result<()> {
+    let expected = Builder::new()
+        .start_compound("root")
+        .byte("b", 1)
+        .short("s", 2)
+        .int("i", 3)
+        .long("l", 4)
+        .float("f", 5.0)
+        .double("d", 6.0)
+        .string("str", "hello")
+        .byte_array("ba", &[1, 2, 3])
+        .int_array("ia", &[4, 5])
+        .long_array("la", &[6])
+        .start_list("list", Tag::Compound, 2)
+        .int("a", 1)
+        .end_anon_compound()
+        .end_anon_compound()
+        .start_list("empty", Tag::End, 0)
+        .end_compound()
+        .build();
+
+    let mut w = Writer::new(Vec::new());
+    w.begin_compound(Some("root"))?;
+    w.write_byte(Some("b"), 1)?;
+    w.write_short(Some("s"), 2)?;
+    w.write_int(Some("i"), 3)?;
+    w.write_long(Some("l"), 4)?;
+    w.write_float(Some("f"), 5.0)?;
+    w.write_double(Some("d"), 6.0)?;
+    w.write_string(Some("str"), "hello")?;
+    w.write_byte_array(Some("ba"), &[1, 2, 3])?;
+    w.write_int_array(Some("ia"), &[4, 5])?;
+    w.write_long_array(Some("la"), &[6])?;
+    w.begin_list(Some("list"), Tag::Compound, 2)?;
+    w.begin_compound(None)?;
+    w.write_int(Some("a"), 1)?;
+    w.end()?;
+    w.begin_compound(None)?;
+    w.end()?;
+    w.end()?;
+    w.begin_list(Some("empty"), Tag::End, 0)?;
+    w.end()?;
+    w.end()?;
+
+    assert_eq!(w.depth(), 0);
+    assert_eq!(w.into_inner(), expected);
+    Ok(())
+}
+
+#[test]
+fn writer_reproduces_parsed_values() -> Result<()> {
+    let mut parser = Parser::new(CHUNK_RAW_WITH_ENTITIES);
+    let mut writer = Writer::new(Vec::new());
+
+    loop {
+        match parser.next() {
+            Ok(value) => writer.write(&value)?,
+            Err(e) if e.is_eof() => break,
+            Err(e) => return Err(e),
+        }
+    }
+
+    assert_eq!(writer.into_inner(), CHUNK_RAW_WITH_ENTITIES);
+    Ok(())
+}
+
+#[test]
+fn writer_rejects_invalid_structure() {
+    let mut w = Writer::new(Vec::new());
+    assert!(w.write_int(None, 1).is_err());
+    assert!(w.end().is_err());
+
+    let mut w = Writer::new(Vec::new());
+    w.begin_list(Some("list"), Tag::Int, 2).unwrap();
+    assert!(w.write_int(Some("named"), 1).is_err());
+    assert!(w.write_long(None, 1).is_err());
+    w.write_int(None, 1).unwrap();
+    assert!(w.end().is_err());
+
+    let mut w = Writer::new(Vec::new());
+    w.begin_list(Some("list"), Tag::Int, 0).unwrap();
+    assert!(w.write_int(None, 1).is_err());
+    assert!(w.begin_list(None, Tag::End, 1).is_err());
+    assert!(w.begin_list(None, Tag::Int, -1).is_err());
+}
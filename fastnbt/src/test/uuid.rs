@@ -0,0 +1,59 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{from_bytes, to_bytes, IntArray, Value};
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Entity {
+    #[serde(rename = "UUID", with = "crate::uuid")]
+    uuid: Uuid,
+}
+
+#[test]
+fn uuid_round_trips_through_bytes() {
+    let entity = Entity {
+        uuid: Uuid::from_u128(0xf1e2d3c4_b5a69788_796a5b4c_3d2e1f00),
+    };
+
+    let bytes = to_bytes(&entity).unwrap();
+    let value: Value = from_bytes(&bytes).unwrap();
+    assert_eq!(
+        value,
+        nbt!({
+            "UUID": IntArray::new(vec![
+                0xf1e2d3c4_u32 as i32,
+                0xb5a69788_u32 as i32,
+                0x796a5b4c,
+                0x3d2e1f00,
+            ]),
+        })
+    );
+
+    let back: Entity = from_bytes(&bytes).unwrap();
+    assert_eq!(back, entity);
+}
+
+#[test]
+fn uuid_matches_u128_layout() {
+    #[derive(Serialize)]
+    struct Raw {
+        #[serde(rename = "UUID")]
+        uuid: u128,
+    }
+
+    let v = 0x0123_4567_89ab_cdef_fedc_ba98_7654_3210;
+    let entity = Entity {
+        uuid: Uuid::from_u128(v),
+    };
+
+    assert_eq!(
+        to_bytes(&entity).unwrap(),
+        to_bytes(&Raw { uuid: v }).unwrap()
+    );
+}
+
+#[test]
+fn uuid_wrong_length_errors() {
+    let bytes = to_bytes(&nbt!({"UUID": [I; 1, 2, 3]})).unwrap();
+    assert!(from_bytes::<Entity>(&bytes).is_err());
+}
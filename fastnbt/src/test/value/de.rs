@@ -1,8 +1,8 @@
-use std::borrow::Cow;
+use std::{borrow::Cow, collections::HashMap};
 
 use serde::Deserialize;
 
-use crate::{value::from_value, ByteArray, IntArray, LongArray};
+use crate::{value::from_value, ByteArray, IntArray, LongArray, Value};
 
 #[test]
 fn simple_types() {
@@ -60,10 +60,46 @@ fn simple_types() {
     };
 
     assert_eq!(expected, val);
-    // A borrowed string cannot be deserialized from Value
+    // Cow is only borrowed with #[serde(borrow)], see borrowed_strings.
     assert!(matches!(val.str, Cow::Owned(_)));
 }
 
+#[test]
+fn borrowed_strings() {
+    #[derive(Deserialize, PartialEq, Debug)]
+    struct V<'a> {
+        name: &'a str,
+        #[serde(borrow)]
+        cow: Cow<'a, str>,
+        #[serde(borrow)]
+        tags: Vec<&'a str>,
+        #[serde(borrow)]
+        counts: HashMap<&'a str, i32>,
+    }
+
+    let value = nbt!({
+        "name": "minecraft:stone",
+        "cow": "cow",
+        "tags": ["a", "b"],
+        "counts": {"stone": 1},
+    });
+    let val: V = from_value(&value).unwrap();
+
+    assert_eq!(val.name, "minecraft:stone");
+    assert!(matches!(val.cow, Cow::Borrowed("cow")));
+    assert_eq!(val.tags, ["a", "b"]);
+    assert_eq!(val.counts["stone"], 1);
+
+    // the strings point into the value rather than being copies.
+    let Some(Value::String(name)) = value.get("name") else {
+        panic!("name is not a string");
+    };
+    assert_eq!(val.name.as_ptr(), name.as_ptr());
+
+    let key = value["counts"].as_compound().unwrap().keys().next().unwrap();
+    assert_eq!(val.counts.keys().next().unwrap().as_ptr(), key.as_ptr());
+}
+
 #[test]
 fn int_array_types() {
     #[derive(Deserialize, PartialEq, Debug)]
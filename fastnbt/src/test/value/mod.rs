@@ -1,9 +1,7 @@
 mod ser;
 mod de;
 
-use std::collections::HashMap;
-
-use crate::{from_bytes, to_bytes, Tag, Value};
+use crate::{from_bytes, from_value, to_bytes, value::Map, ByteArray, IntArray, Tag, Value};
 
 use super::builder::Builder;
 
@@ -118,7 +116,7 @@ fn distinguish_floats() {
 #[test]
 fn fuzz_float() {
     let v = Value::Float(1.4e-44);
-    let mut inner = HashMap::new();
+    let mut inner = Map::new();
     inner.insert("".to_string(), v);
 
     let v = Value::Compound(inner);
@@ -216,3 +214,301 @@ fn distinguish_compound() {
     let v: Value = from_bytes(&input).unwrap();
     assert_contains!(v, "a", Value::Compound(_));
 }
+
+#[test]
+fn get_and_index_compound() {
+    let mut v = nbt!({
+        "Data": {
+            "version": 19133,
+            "name": "world",
+        },
+        "list": [1, 2],
+    });
+
+    assert_eq!(v["Data"]["version"].as_i64(), Some(19133));
+    assert_eq!(
+        v.get("Data").and_then(|d| d.get("name")),
+        Some(&nbt!("world"))
+    );
+    assert_eq!(v.get("missing"), None);
+    assert_eq!(v["list"].get("key"), None);
+    assert_eq!(v["list"].as_list(), Some([nbt!(1), nbt!(2)].as_slice()));
+    assert_eq!(v["Data"].as_compound().map(|c| c.len()), Some(2));
+    assert_eq!(v["list"].as_compound(), None);
+
+    *v.get_mut("Data").unwrap().get_mut("version").unwrap() = nbt!(1);
+    assert_eq!(v["Data"]["version"], 1);
+}
+
+#[test]
+#[should_panic]
+fn index_missing_key_panics() {
+    let v = nbt!({ "a": 1 });
+    let _ = &v["b"];
+}
+
+#[test]
+fn pointer() {
+    let mut v = nbt!({
+        "Data": {
+            "WorldGenSettings": { "seed": 1234_i64 },
+            "ServerBrands": ["vanilla", "fabric"],
+            "a/b": 1,
+            "c~d": 2,
+        },
+    });
+
+    assert_eq!(v.pointer(""), Some(&v));
+    assert_eq!(
+        v.pointer("/Data/WorldGenSettings/seed"),
+        Some(&nbt!(1234_i64))
+    );
+    assert_eq!(v.pointer("/Data/ServerBrands/1"), Some(&nbt!("fabric")));
+    assert_eq!(v.pointer("/Data/a~1b"), Some(&nbt!(1)));
+    assert_eq!(v.pointer("/Data/c~0d"), Some(&nbt!(2)));
+
+    assert_eq!(v.pointer("Data"), None);
+    assert_eq!(v.pointer("/Data/ServerBrands/2"), None);
+    assert_eq!(v.pointer("/Data/ServerBrands/01"), None);
+    assert_eq!(v.pointer("/Data/ServerBrands/+1"), None);
+    assert_eq!(v.pointer("/Data/WorldGenSettings/seed/0"), None);
+
+    *v.pointer_mut("/Data/ServerBrands/0").unwrap() = nbt!("forge");
+    assert_eq!(v.pointer("/Data/ServerBrands/0"), Some(&nbt!("forge")));
+}
+
+#[cfg(feature = "indexmap")]
+#[test]
+fn compound_preserves_key_order() {
+    let input = Builder::new()
+        .start_compound("")
+        .int("z", 1)
+        .int("a", 2)
+        .int("m", 3)
+        .end_compound()
+        .build();
+
+    let v: Value = from_bytes(&input).unwrap();
+    let keys: Vec<_> = v.as_compound().unwrap().keys().collect();
+    assert_eq!(keys, ["z", "a", "m"]);
+
+    assert_eq!(to_bytes(&v).unwrap(), input);
+}
+
+#[test]
+fn build_compound_and_list() {
+    let mut list = Value::list();
+    list.push(1_i32);
+    list.push(2_i32);
+
+    let mut v = Value::compound();
+    assert_eq!(v.insert("name", "pig"), None);
+    assert_eq!(
+        v.insert("name", "cow"),
+        Some(Value::String("pig".to_owned()))
+    );
+    v.insert(String::from("list"), list);
+    v.insert("nested", Value::compound());
+
+    assert_eq!(
+        v,
+        crate::nbt!({
+            "name": "cow",
+            "list": [1, 2],
+            "nested": {},
+        })
+    );
+}
+
+#[test]
+fn insert_and_push_on_wrong_type_do_nothing() {
+    let mut v = Value::Int(1);
+    assert_eq!(v.insert("a", 1_i8), None);
+    v.push(1_i8);
+    assert_eq!(v, Value::Int(1));
+
+    let mut list = Value::list();
+    assert_eq!(list.insert("a", 1_i8), None);
+    assert_eq!(list, Value::List(vec![]));
+
+    let mut compound = Value::compound();
+    compound.push(1_i8);
+    assert_eq!(compound, Value::Compound(Map::new()));
+}
+
+#[test]
+fn merge_compounds() {
+    let mut v = nbt!({
+        "keep": 1,
+        "replace": 2,
+        "list": [1, 2, 3],
+        "array": [L; 1, 2],
+        "nested": {"a": 1, "b": {"c": 2}},
+        "compound_to_scalar": {"a": 1},
+    });
+
+    v.merge(nbt!({
+        "replace": "two",
+        "list": [4],
+        "array": [L; 3],
+        "nested": {"b": {"d": 3}, "e": 4},
+        "compound_to_scalar": 5,
+        "new": {"x": 1},
+    }));
+
+    assert_eq!(
+        v,
+        nbt!({
+            "keep": 1,
+            "replace": "two",
+            "list": [4],
+            "array": [L; 3],
+            "nested": {"a": 1, "b": {"c": 2, "d": 3}, "e": 4},
+            "compound_to_scalar": 5,
+            "new": {"x": 1},
+        })
+    );
+}
+
+#[test]
+fn merge_non_compounds_does_nothing() {
+    let mut v = Value::Int(1);
+    v.merge(nbt!({"a": 1}));
+    assert_eq!(v, Value::Int(1));
+
+    let mut compound = nbt!({"a": 1});
+    compound.merge(Value::list());
+    assert_eq!(compound, nbt!({"a": 1}));
+}
+
+#[test]
+fn try_from_scalars() {
+    assert_eq!(i32::try_from(&Value::Short(-5)).unwrap(), -5);
+    assert_eq!(i64::try_from(&Value::Int(7)).unwrap(), 7);
+    assert_eq!(i8::try_from(&Value::Long(100)).unwrap(), 100);
+    assert_eq!(f64::try_from(&Value::Float(0.5)).unwrap(), 0.5);
+    assert_eq!(f32::try_from(&Value::Double(0.25)).unwrap(), 0.25);
+    assert!(bool::try_from(&Value::Byte(1)).unwrap());
+    assert!(!bool::try_from(&Value::Byte(0)).unwrap());
+    assert_eq!(
+        String::try_from(&Value::String("pig".to_owned())).unwrap(),
+        "pig"
+    );
+}
+
+#[test]
+fn try_from_wrong_type_names_variant() {
+    let err = i32::try_from(&Value::String("1".to_owned())).unwrap_err();
+    assert_eq!(err.to_string(), "expected i32, found string");
+
+    let err = String::try_from(&Value::IntArray(IntArray::new(vec![]))).unwrap_err();
+    assert_eq!(err.to_string(), "expected string, found int-array");
+
+    assert!(f64::try_from(&Value::Int(1)).is_err());
+    assert!(bool::try_from(&Value::Float(1.0)).is_err());
+}
+
+#[test]
+fn try_from_out_of_range() {
+    let err = i8::try_from(&Value::Int(300)).unwrap_err();
+    assert_eq!(err.to_string(), "300 out of range for i8");
+    assert!(i32::try_from(&Value::Long(i64::MAX)).is_err());
+}
+
+#[test]
+fn value_tag() {
+    assert_eq!(Value::Byte(1).tag(), Tag::Byte);
+    assert_eq!(Value::list().tag(), Tag::List);
+    assert_eq!(Value::compound().tag(), Tag::Compound);
+}
+
+#[test]
+fn display_as_snbt() {
+    assert_eq!(nbt!(1_i8).to_string(), "1b");
+    assert_eq!(nbt!(-2_i16).to_string(), "-2s");
+    assert_eq!(nbt!(3).to_string(), "3");
+    assert_eq!(nbt!(4_i64).to_string(), "4L");
+    assert_eq!(nbt!(1.5_f32).to_string(), "1.5f");
+    assert_eq!(nbt!(2.0).to_string(), "2.0d");
+    assert_eq!(nbt!("a \"b\" \\c").to_string(), r#""a \"b\" \\c""#);
+
+    assert_eq!(nbt!([B; 1, -2]).to_string(), "[B;1b,-2b]");
+    assert_eq!(nbt!([I; 1, 2]).to_string(), "[I;1,2]");
+    assert_eq!(nbt!([L;]).to_string(), "[L;]");
+    assert_eq!(nbt!([1_i16, 2_i16]).to_string(), "[1s,2s]");
+
+    // Keys are in sorted order so the output is the same with `indexmap`.
+    let v = nbt!({
+        "Count": 1_i8,
+        "Name": "minecraft:stone",
+        "needs quotes": {},
+        "tags": [{"a": 1}],
+    });
+    assert_eq!(
+        v.to_string(),
+        r#"{Count:1b,Name:"minecraft:stone","needs quotes":{},tags:[{a:1}]}"#
+    );
+}
+
+#[test]
+fn display_non_finite_floats_as_rust_does() {
+    // There is no valid SNBT for these, so this pins what we write instead.
+    assert_eq!(Value::Float(f32::NAN).to_string(), "NaNf");
+    assert_eq!(Value::Float(f32::INFINITY).to_string(), "inff");
+    assert_eq!(Value::Double(f64::NEG_INFINITY).to_string(), "-infd");
+}
+
+#[test]
+fn tag_histogram() {
+    let v = nbt!({
+        "DataVersion": 3465,
+        "Heightmaps": {"WORLD_SURFACE": [L; 1, 2, 3]},
+        "Entities": [
+            {"id": "minecraft:cow", "Pos": [1.0, 2.0, 3.0]},
+            {"id": "minecraft:pig", "Pos": [4.0, 5.0, 6.0]},
+        ],
+        "empty": [],
+    });
+
+    let histogram = v.tag_histogram();
+    assert_eq!(histogram.get(&Tag::Compound), Some(&4));
+    assert_eq!(histogram.get(&Tag::List), Some(&4));
+    assert_eq!(histogram.get(&Tag::Double), Some(&6));
+    assert_eq!(histogram.get(&Tag::String), Some(&2));
+    assert_eq!(histogram.get(&Tag::Int), Some(&1));
+    assert_eq!(histogram.get(&Tag::LongArray), Some(&1));
+    assert_eq!(histogram.get(&Tag::Long), None);
+    assert_eq!(histogram.values().sum::<usize>(), 18);
+
+    assert_eq!(nbt!(1_i8).tag_histogram(), [(Tag::Byte, 1)].into());
+}
+
+#[test]
+fn array_tokens_as_keys_are_rejected() {
+    for token in [
+        crate::BYTE_ARRAY_TOKEN,
+        crate::INT_ARRAY_TOKEN,
+        crate::LONG_ARRAY_TOKEN,
+    ] {
+        // a compound from untrusted NBT using the token as a key.
+        let input = Builder::new()
+            .start_compound("")
+            .byte_array(token, &[0, 0, 0, 1, 0, 0, 0, 2])
+            .end_compound()
+            .build();
+        assert!(from_bytes::<Value>(&input).is_err());
+
+        // the same compound built directly as a value.
+        let mut v = Value::compound();
+        v.insert(token, ByteArray::new(vec![0, 0, 0, 1, 0, 0, 0, 2]));
+        assert!(from_value::<Value>(&v).is_err());
+
+        // a list of bytes could otherwise be read as the array's data.
+        let v = nbt!({ token: [0_i8, 0_i8, 0_i8, 1_i8, 0_i8, 0_i8, 0_i8, 2_i8] });
+        assert!(from_value::<Value>(&v).is_err());
+    }
+
+    // keys that only look like tokens are fine.
+    let v = nbt!({"__fastnbt_other": 1});
+    assert_eq!(from_value::<Value>(&v).unwrap(), v);
+}
@@ -0,0 +1,51 @@
+use crate::varint::decode_varints;
+
+#[test]
+fn palette_index_stream() {
+    let data = [
+        0x00, // 0
+        0x05, // 5
+        0x7f, // 127
+        0x80, 0x01, // 128
+        0xac, 0x02, // 300
+        0xff, 0xff, 0x03, // 65535
+        0x00, // 0
+    ];
+
+    let ids: Vec<_> = decode_varints(&data).collect();
+    assert_eq!(ids, [0, 5, 127, 128, 300, 65535, 0]);
+}
+
+#[test]
+fn five_byte_values() {
+    let data = [
+        0xff, 0xff, 0xff, 0xff, 0x07, // i32::MAX
+        0xff, 0xff, 0xff, 0xff, 0x0f, // -1
+        0x80, 0x80, 0x80, 0x80, 0x08, // i32::MIN
+    ];
+
+    let ids: Vec<_> = decode_varints(&data).collect();
+    assert_eq!(ids, [i32::MAX, -1, i32::MIN]);
+}
+
+#[test]
+fn overlong_value_stops_decoding() {
+    let mut data = vec![0x01];
+    data.extend([0x80; 6]);
+    data.extend([0x02, 0x03]);
+
+    let ids: Vec<_> = decode_varints(&data).collect();
+    assert_eq!(ids, [1]);
+
+    // Long enough to overflow a shift if it were not bounded.
+    let ids: Vec<_> = decode_varints(&[0xff; 64]).collect();
+    assert!(ids.is_empty());
+}
+
+#[test]
+fn truncated_value_is_dropped() {
+    let ids: Vec<_> = decode_varints(&[0x01, 0x80]).collect();
+    assert_eq!(ids, [1]);
+
+    assert_eq!(decode_varints(&[]).count(), 0);
+}
@@ -0,0 +1,62 @@
+//! (De)serialize `uuid::Uuid` as an IntArray of length 4, the way Minecraft
+//! stores UUIDs such as an entity's `UUID`. Use with serde's `with` attribute:
+//!
+//! ```
+//! # use fastnbt::nbt;
+//! use serde::{Deserialize, Serialize};
+//! use uuid::Uuid;
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct Entity {
+//!     #[serde(rename = "UUID", with = "fastnbt::uuid")]
+//!     uuid: Uuid,
+//! }
+//!
+//! let nbt = nbt!({ "UUID": [I; 1, 2, 3, 4] });
+//! let entity: Entity = fastnbt::from_value(&nbt).unwrap();
+//!
+//! assert_eq!(entity.uuid, Uuid::from_u128(0x00000001_00000002_00000003_00000004));
+//! assert_eq!(fastnbt::to_value(&entity).unwrap(), nbt);
+//! ```
+//!
+//! The ints are most significant first, each big endian. This is the same
+//! layout used for `u128` fields, see the [`de`][`crate::de`] module.
+
+use ::uuid::Uuid;
+use serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::IntArray;
+
+/// Serialize a UUID as an IntArray of length 4.
+pub fn serialize<S>(uuid: &Uuid, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    let v = uuid.as_u128();
+    let ints = (0..4)
+        .rev()
+        .map(|i| (v >> (32 * i)) as u32 as i32)
+        .collect();
+
+    IntArray::new(ints).serialize(serializer)
+}
+
+/// Deserialize a UUID from an IntArray of length 4.
+pub fn deserialize<'de, D>(deserializer: D) -> Result<Uuid, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let ints = IntArray::deserialize(deserializer)?;
+    if ints.len() != 4 {
+        return Err(D::Error::invalid_length(
+            ints.len(),
+            &"an IntArray of length 4",
+        ));
+    }
+
+    let v = ints
+        .iter()
+        .fold(0u128, |acc, i| (acc << 32) | *i as u32 as u128);
+
+    Ok(Uuid::from_u128(v))
+}
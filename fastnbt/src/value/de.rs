@@ -1,4 +1,4 @@
-use std::{borrow::Cow, collections::HashMap};
+use std::borrow::Cow;
 
 use serde::{
     de::{
@@ -12,6 +12,8 @@ use serde_bytes::ByteBuf;
 
 use crate::{error::Error, ByteArray, IntArray, LongArray, Value};
 
+use super::Map;
+
 impl<'de> Deserialize<'de> for Value {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
@@ -100,7 +102,7 @@ impl<'de> Deserialize<'de> for Value {
             {
                 match map.next_key_seed(KeyClassifier)? {
                     Some(KeyClass::Compound(first_key)) => {
-                        let mut compound = HashMap::new();
+                        let mut compound = Map::new();
 
                         compound.insert(first_key, map.next_value()?);
                         while let Some((key, value)) = map.next_entry()? {
@@ -109,22 +111,9 @@ impl<'de> Deserialize<'de> for Value {
 
                         Ok(Value::Compound(compound))
                     }
-                    Some(KeyClass::ByteArray) => {
-                        let data = map.next_value::<ByteBuf>()?;
-                        Ok(Value::ByteArray(ByteArray::from_buf(data.into_vec())))
-                    }
-                    Some(KeyClass::IntArray) => {
-                        let data = map.next_value::<ByteBuf>()?;
-                        IntArray::from_bytes(&data)
-                            .map(Value::IntArray)
-                            .map_err(|_| serde::de::Error::custom("could not read int array"))
-                    }
-                    Some(KeyClass::LongArray) => {
-                        let data = map.next_value::<ByteBuf>()?;
-                        LongArray::from_bytes(&data)
-                            .map(Value::LongArray)
-                            .map_err(|_| serde::de::Error::custom("could not read long array"))
-                    }
+                    // Arrays are read out of line to keep this frame small,
+                    // since it is on the stack once per level of nesting.
+                    Some(class) => visit_array(class, map),
                     // No keys just means an empty compound.
                     None => Ok(Value::Compound(Default::default())),
                 }
@@ -135,6 +124,31 @@ impl<'de> Deserialize<'de> for Value {
     }
 }
 
+fn visit_array<'de, A>(class: KeyClass, mut map: A) -> Result<Value, A::Error>
+where
+    A: MapAccess<'de>,
+{
+    match class {
+        KeyClass::ByteArray => {
+            let data = map.next_value::<ByteBuf>()?;
+            Ok(Value::ByteArray(ByteArray::from_buf(data.into_vec())))
+        }
+        KeyClass::IntArray => {
+            let data = map.next_value::<ByteBuf>()?;
+            IntArray::from_bytes(&data)
+                .map(Value::IntArray)
+                .map_err(|_| serde::de::Error::custom("could not read int array"))
+        }
+        KeyClass::LongArray => {
+            let data = map.next_value::<ByteBuf>()?;
+            LongArray::from_bytes(&data)
+                .map(Value::LongArray)
+                .map_err(|_| serde::de::Error::custom("could not read long array"))
+        }
+        KeyClass::Compound(_) => unreachable!("compounds are not arrays"),
+    }
+}
+
 struct KeyClassifier;
 
 enum KeyClass {
@@ -252,10 +266,7 @@ where
     }
 }
 
-fn visit_compound<'de, V>(
-    compound: &'de HashMap<String, Value>,
-    visitor: V,
-) -> Result<V::Value, Error>
+fn visit_compound<'de, V>(compound: &'de Map<String, Value>, visitor: V) -> Result<V::Value, Error>
 where
     V: Visitor<'de>,
 {
@@ -735,12 +746,12 @@ impl<'de> SeqAccess<'de> for SeqDeserializer<'de> {
 }
 
 struct MapDeserializer<'de> {
-    iter: <&'de HashMap<String, Value> as IntoIterator>::IntoIter,
+    iter: <&'de Map<String, Value> as IntoIterator>::IntoIter,
     value: Option<&'de Value>,
 }
 
 impl<'de> MapDeserializer<'de> {
-    fn new(map: &'de HashMap<String, Value>) -> Self {
+    fn new(map: &'de Map<String, Value>) -> Self {
         MapDeserializer {
             iter: map.iter(),
             value: None,
@@ -758,8 +769,10 @@ impl<'de> MapAccess<'de> for MapDeserializer<'de> {
         match self.iter.next() {
             Some((key, value)) => {
                 self.value = Some(value);
+                // A compound with an array token as a key would otherwise be
+                // read back as an array, as with the main deserializer.
                 let key_de = MapKeyDeserializer {
-                    key: Cow::Borrowed(&**key),
+                    key: Cow::Borrowed(crate::de::arr_check(key)?),
                 };
                 seed.deserialize(key_de).map(Some)
             }
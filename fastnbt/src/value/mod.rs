@@ -1,15 +1,38 @@
 mod array_serializer;
 mod de;
 mod ser;
+mod snbt;
 
 use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::ops::Index;
 
 use serde::{serde_if_integer128, Deserialize, Serialize};
 
-use crate::{error::Error, ByteArray, IntArray, LongArray};
+use crate::{error::Error, ByteArray, IntArray, LongArray, Tag};
 
 pub use self::ser::Serializer;
 
+/// The map type used for [`Value::Compound`].
+///
+/// This is a [`HashMap`][`std::collections::HashMap`] by default. With the
+/// `indexmap` feature enabled this is an [`IndexMap`][`indexmap::IndexMap`]
+/// instead, which preserves the order keys were inserted or deserialized in.
+/// This means NBT can be round-tripped through [`Value`] with the original
+/// key order.
+#[cfg(not(feature = "indexmap"))]
+pub type Map<K, V> = std::collections::HashMap<K, V>;
+
+/// The map type used for [`Value::Compound`].
+///
+/// This is a [`HashMap`][`std::collections::HashMap`] by default. With the
+/// `indexmap` feature enabled this is an [`IndexMap`][`indexmap::IndexMap`]
+/// instead, which preserves the order keys were inserted or deserialized in.
+/// This means NBT can be round-tripped through [`Value`] with the original
+/// key order.
+#[cfg(feature = "indexmap")]
+pub type Map<K, V> = indexmap::IndexMap<K, V>;
+
 /// Value is a complete NBT value. It owns its data. Compounds and Lists are
 /// resursively deserialized. This type takes care to preserve all the
 /// information from the original NBT, with the exception of the name of the
@@ -18,11 +41,11 @@ pub use self::ser::Serializer;
 /// ```no_run
 /// # use fastnbt::Value;
 /// # use fastnbt::error::Result;
-/// # use std::collections::HashMap;
+/// # use fastnbt::value::Map;
 /// #
 /// # fn main() -> Result<()> {
 /// #   let mut buf = vec![];
-///     let compound: HashMap<String, Value> = fastnbt::from_bytes(buf.as_slice())?;
+///     let compound: Map<String, Value> = fastnbt::from_bytes(buf.as_slice())?;
 ///     match compound["DataVersion"] {
 ///         Value::Int(ver) => println!("Version: {}", ver),
 ///         _ => {},
@@ -44,7 +67,7 @@ pub enum Value {
     IntArray(IntArray),
     LongArray(LongArray),
     List(Vec<Value>),
-    Compound(HashMap<String, Value>),
+    Compound(Map<String, Value>),
 }
 
 #[cfg(feature = "arbitrary1")]
@@ -117,6 +140,72 @@ impl<'a> arbitrary::Arbitrary<'a> for Value {
 }
 
 impl Value {
+    /// Create an empty compound. Entries can be added with
+    /// [`insert`][`Value::insert`]. This is useful when keys are not known
+    /// ahead of time, otherwise the [`nbt!`][`crate::nbt`] macro is simpler.
+    ///
+    /// ```
+    /// # use fastnbt::{nbt, Value};
+    /// let mut pos = Value::list();
+    /// for p in [1.0, 64.0, -3.5] {
+    ///     pos.push(p);
+    /// }
+    ///
+    /// let mut entity = Value::compound();
+    /// entity.insert("id", "minecraft:pig");
+    /// entity.insert("Pos", pos);
+    ///
+    /// assert_eq!(entity, nbt!({"id": "minecraft:pig", "Pos": [1.0, 64.0, -3.5]}));
+    /// ```
+    pub fn compound() -> Value {
+        Value::Compound(Map::new())
+    }
+
+    /// Create an empty list. Elements can be added with
+    /// [`push`][`Value::push`].
+    pub fn list() -> Value {
+        Value::List(Vec::new())
+    }
+
+    /// Insert an entry into this compound, returning the value previously
+    /// stored for the key if there was one.
+    ///
+    /// Does nothing and returns None if this is not a compound.
+    pub fn insert(&mut self, key: impl Into<String>, value: impl Into<Value>) -> Option<Value> {
+        match self {
+            Value::Compound(map) => map.insert(key.into(), value.into()),
+            _ => None,
+        }
+    }
+
+    /// Append an element to this list. Does nothing if this is not a list.
+    ///
+    /// NBT lists can only hold a single type of element. This is not checked
+    /// here, but serializing a list of mixed types will fail.
+    pub fn push(&mut self, value: impl Into<Value>) {
+        if let Value::List(list) = self {
+            list.push(value.into());
+        }
+    }
+
+    /// The NBT tag of this value.
+    pub fn tag(&self) -> Tag {
+        match self {
+            Value::Byte(_) => Tag::Byte,
+            Value::Short(_) => Tag::Short,
+            Value::Int(_) => Tag::Int,
+            Value::Long(_) => Tag::Long,
+            Value::Float(_) => Tag::Float,
+            Value::Double(_) => Tag::Double,
+            Value::String(_) => Tag::String,
+            Value::ByteArray(_) => Tag::ByteArray,
+            Value::IntArray(_) => Tag::IntArray,
+            Value::LongArray(_) => Tag::LongArray,
+            Value::List(_) => Tag::List,
+            Value::Compound(_) => Tag::Compound,
+        }
+    }
+
     pub fn as_i64(&self) -> Option<i64> {
         match *self {
             Value::Byte(v) => Some(v as i64),
@@ -159,6 +248,182 @@ impl Value {
             _ => None,
         }
     }
+
+    pub fn as_compound(&self) -> Option<&Map<String, Value>> {
+        match self {
+            Value::Compound(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    pub fn as_list(&self) -> Option<&[Value]> {
+        match self {
+            Value::List(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    /// Get the value of the given key if this is a compound. Returns None if
+    /// this is not a compound or the key is not present.
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        self.as_compound()?.get(key)
+    }
+
+    /// Mutable version of [`get`][`Value::get`].
+    pub fn get_mut(&mut self, key: &str) -> Option<&mut Value> {
+        match self {
+            Value::Compound(v) => v.get_mut(key),
+            _ => None,
+        }
+    }
+
+    /// Look up a value by a JSON Pointer ([RFC
+    /// 6901](https://tools.ietf.org/html/rfc6901)) style path. Compounds are
+    /// walked by key and lists by index. Returns None if any part of the path
+    /// is not found.
+    ///
+    /// ```
+    /// # use fastnbt::nbt;
+    /// let level = nbt!({
+    ///     "Data": {
+    ///         "WorldGenSettings": { "seed": 1234_i64 },
+    ///         "ServerBrands": ["vanilla"],
+    ///     },
+    /// });
+    ///
+    /// assert_eq!(level.pointer("/Data/WorldGenSettings/seed"), Some(&nbt!(1234_i64)));
+    /// assert_eq!(level.pointer("/Data/ServerBrands/0"), Some(&nbt!("vanilla")));
+    /// assert_eq!(level.pointer("/Data/missing"), None);
+    /// ```
+    ///
+    /// `~1` and `~0` in keys are unescaped to `/` and `~` respectively.
+    pub fn pointer(&self, pointer: &str) -> Option<&Value> {
+        if pointer.is_empty() {
+            return Some(self);
+        }
+        if !pointer.starts_with('/') {
+            return None;
+        }
+        pointer
+            .split('/')
+            .skip(1)
+            .map(|x| x.replace("~1", "/").replace("~0", "~"))
+            .try_fold(self, |target, token| match target {
+                Value::Compound(map) => map.get(&token),
+                Value::List(list) => parse_index(&token).and_then(|x| list.get(x)),
+                _ => None,
+            })
+    }
+
+    /// Mutable version of [`pointer`][`Value::pointer`].
+    pub fn pointer_mut(&mut self, pointer: &str) -> Option<&mut Value> {
+        if pointer.is_empty() {
+            return Some(self);
+        }
+        if !pointer.starts_with('/') {
+            return None;
+        }
+        pointer
+            .split('/')
+            .skip(1)
+            .map(|x| x.replace("~1", "/").replace("~0", "~"))
+            .try_fold(self, |target, token| match target {
+                Value::Compound(map) => map.get_mut(&token),
+                Value::List(list) => parse_index(&token).and_then(move |x| list.get_mut(x)),
+                _ => None,
+            })
+    }
+
+    /// Deep merge another compound into this one. Keys of `other` are added to
+    /// this compound, with compounds present on both sides merged recursively.
+    /// For any other conflict, including lists and arrays, the value from
+    /// `other` replaces the existing one.
+    ///
+    /// ```
+    /// # use fastnbt::nbt;
+    /// let mut level = nbt!({
+    ///     "Data": {
+    ///         "LevelName": "world",
+    ///         "GameRules": { "doDaylightCycle": "true", "keepInventory": "false" },
+    ///     },
+    /// });
+    ///
+    /// level.merge(nbt!({ "Data": { "GameRules": { "keepInventory": "true" } } }));
+    ///
+    /// assert_eq!(level.pointer("/Data/GameRules/keepInventory"), Some(&nbt!("true")));
+    /// assert_eq!(level.pointer("/Data/GameRules/doDaylightCycle"), Some(&nbt!("true")));
+    /// assert_eq!(level.pointer("/Data/LevelName"), Some(&nbt!("world")));
+    /// ```
+    ///
+    /// Does nothing if either value is not a compound.
+    pub fn merge(&mut self, other: Value) {
+        let (Value::Compound(this), Value::Compound(other)) = (self, other) else {
+            return;
+        };
+
+        for (key, value) in other {
+            match (this.get_mut(&key), value) {
+                (Some(existing @ Value::Compound(_)), value @ Value::Compound(_)) => {
+                    existing.merge(value)
+                }
+                (_, value) => {
+                    this.insert(key, value);
+                }
+            }
+        }
+    }
+
+    /// Count how many of each tag appear in this value, including the value
+    /// itself and everything nested in its lists and compounds. Arrays count
+    /// as a single tag.
+    ///
+    /// ```
+    /// # use fastnbt::{nbt, Tag};
+    /// let chunk = nbt!({
+    ///     "Entities": [{"id": "minecraft:cow"}, {"id": "minecraft:pig"}],
+    /// });
+    ///
+    /// let histogram = chunk.tag_histogram();
+    /// assert_eq!(histogram[&Tag::Compound], 3);
+    /// assert_eq!(histogram[&Tag::String], 2);
+    /// assert_eq!(histogram[&Tag::List], 1);
+    /// ```
+    pub fn tag_histogram(&self) -> HashMap<Tag, usize> {
+        fn walk(value: &Value, counts: &mut HashMap<Tag, usize>) {
+            *counts.entry(value.tag()).or_default() += 1;
+            match value {
+                Value::List(list) => list.iter().for_each(|v| walk(v, counts)),
+                Value::Compound(map) => map.values().for_each(|v| walk(v, counts)),
+                _ => {}
+            }
+        }
+
+        let mut counts = HashMap::new();
+        walk(self, &mut counts);
+        counts
+    }
+}
+
+fn parse_index(s: &str) -> Option<usize> {
+    if s.starts_with('+') || (s.starts_with('0') && s.len() != 1) {
+        return None;
+    }
+    s.parse().ok()
+}
+
+/// Index into a compound by key, eg `level["Data"]["version"]`.
+///
+/// # Panics
+///
+/// Panics if the value is not a compound or the key is not present. Use
+/// [`Value::get`] to avoid this.
+impl Index<&str> for Value {
+    type Output = Value;
+
+    fn index(&self, key: &str) -> &Value {
+        self.get(key)
+            .unwrap_or_else(|| panic!("no compound entry found for key {key:?}"))
+    }
 }
 
 // ------------- From<T> impls -------------
@@ -204,6 +469,92 @@ impl From<&bool> for Value {
     }
 }
 
+// ------------- TryFrom<&Value> impls -------------
+
+fn integer(value: &Value) -> Option<i64> {
+    match *value {
+        Value::Byte(v) => Some(v as i64),
+        Value::Short(v) => Some(v as i64),
+        Value::Int(v) => Some(v as i64),
+        Value::Long(v) => Some(v),
+        _ => None,
+    }
+}
+
+fn wrong_type(expected: &str, value: &Value) -> Error {
+    Error::bespoke(format!("expected {}, found {}", expected, value.tag()))
+}
+
+macro_rules! try_from_integer {
+    ($($type:ty),+) => {
+        $(
+            /// Any integer value is accepted if it fits in the type. Floats
+            /// are not converted.
+            impl TryFrom<&Value> for $type {
+                type Error = Error;
+
+                fn try_from(value: &Value) -> Result<Self, Error> {
+                    let v = integer(value)
+                        .ok_or_else(|| wrong_type(stringify!($type), value))?;
+                    <$type>::try_from(v).map_err(|_| {
+                        Error::bespoke(format!("{} out of range for {}", v, stringify!($type)))
+                    })
+                }
+            }
+        )+
+    };
+}
+try_from_integer!(i8, i16, i32, i64);
+
+/// Float and double values are accepted, doubles are converted with `as`.
+impl TryFrom<&Value> for f32 {
+    type Error = Error;
+
+    fn try_from(value: &Value) -> Result<Self, Error> {
+        match *value {
+            Value::Float(v) => Ok(v),
+            Value::Double(v) => Ok(v as f32),
+            _ => Err(wrong_type("f32", value)),
+        }
+    }
+}
+
+/// Float and double values are accepted.
+impl TryFrom<&Value> for f64 {
+    type Error = Error;
+
+    fn try_from(value: &Value) -> Result<Self, Error> {
+        match *value {
+            Value::Float(v) => Ok(v as f64),
+            Value::Double(v) => Ok(v),
+            _ => Err(wrong_type("f64", value)),
+        }
+    }
+}
+
+/// Any integer value is accepted, with non-zero being true. This matches how
+/// Minecraft stores booleans as bytes.
+impl TryFrom<&Value> for bool {
+    type Error = Error;
+
+    fn try_from(value: &Value) -> Result<Self, Error> {
+        integer(value)
+            .map(|v| v != 0)
+            .ok_or_else(|| wrong_type("bool", value))
+    }
+}
+
+impl TryFrom<&Value> for String {
+    type Error = Error;
+
+    fn try_from(value: &Value) -> Result<Self, Error> {
+        match value {
+            Value::String(v) => Ok(v.clone()),
+            _ => Err(wrong_type("string", value)),
+        }
+    }
+}
+
 //
 // Everything below is copied from serde_json,
 // Partial Eq impls: https://github.com/serde-rs/json/blob/5d2cbcdd4b146e98b5aa2200de7a8ae6231bf0ba/src/value/partial_eq.rs
@@ -432,6 +783,25 @@ where
 /// println!("{:#?}", u);
 /// ```
 ///
+/// Strings, including compound keys, are borrowed from the value rather than
+/// copied, so `T` can contain `&str`. `Cow<str>` fields need
+/// `#[serde(borrow)]` to borrow.
+///
+/// ```
+/// use serde::Deserialize;
+/// use fastnbt::nbt;
+///
+/// #[derive(Deserialize)]
+/// struct Block<'a> {
+///     #[serde(rename = "Name")]
+///     name: &'a str,
+/// }
+///
+/// let value = nbt!({"Name": "minecraft:stone"});
+/// let block: Block = fastnbt::from_value(&value).unwrap();
+/// assert_eq!(block.name, "minecraft:stone");
+/// ```
+///
 /// # Errors
 ///
 /// This conversion can fail if the structure of the Value does not match the
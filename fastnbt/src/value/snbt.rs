@@ -0,0 +1,103 @@
+use std::fmt::{self, Display, Formatter, Write};
+
+use super::Value;
+
+/// Formats the value as SNBT, the text format used by Minecraft commands.
+///
+/// Numbers have a suffix for their type, except for ints, and arrays are
+/// written with their type prefix, eg `[B;1b,2b]`. Compound keys are only
+/// quoted when they need to be. Without the `indexmap` feature keys are
+/// sorted, so the output does not depend on the order of the hash map.
+///
+/// NaN and infinite floats have no SNBT form. They are written as Rust
+/// formats them, eg `NaNf` or `-infd`, which cannot be parsed back.
+///
+/// ```
+/// # use fastnbt::nbt;
+/// let v = nbt!({"Count": 1_i8, "Name": "stone"});
+/// assert_eq!(v.to_string(), r#"{Count:1b,Name:"stone"}"#);
+/// ```
+impl Display for Value {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Byte(v) => write!(f, "{v}b"),
+            Value::Short(v) => write!(f, "{v}s"),
+            Value::Int(v) => write!(f, "{v}"),
+            Value::Long(v) => write!(f, "{v}L"),
+            Value::Float(v) => write!(f, "{v:?}f"),
+            Value::Double(v) => write!(f, "{v:?}d"),
+            Value::String(v) => write_quoted(f, v),
+            Value::ByteArray(v) => write_array(f, 'B', v.iter().map(|v| Value::Byte(*v))),
+            Value::IntArray(v) => write_array(f, 'I', v.iter()),
+            Value::LongArray(v) => write_array(f, 'L', v.iter().map(|v| Value::Long(*v))),
+            Value::List(v) => {
+                f.write_char('[')?;
+                write_separated(f, v)?;
+                f.write_char(']')
+            }
+            Value::Compound(v) => {
+                #[allow(unused_mut)]
+                let mut entries: Vec<_> = v.iter().collect();
+                #[cfg(not(feature = "indexmap"))]
+                entries.sort_by_key(|(key, _)| *key);
+
+                f.write_char('{')?;
+                for (i, (key, value)) in entries.into_iter().enumerate() {
+                    if i > 0 {
+                        f.write_char(',')?;
+                    }
+                    if is_unquoted(key) {
+                        f.write_str(key)?;
+                    } else {
+                        write_quoted(f, key)?;
+                    }
+                    write!(f, ":{value}")?;
+                }
+                f.write_char('}')
+            }
+        }
+    }
+}
+
+/// Write the values separated by commas.
+fn write_separated<T: Display>(
+    f: &mut Formatter<'_>,
+    values: impl IntoIterator<Item = T>,
+) -> fmt::Result {
+    for (i, value) in values.into_iter().enumerate() {
+        if i > 0 {
+            f.write_char(',')?;
+        }
+        write!(f, "{value}")?;
+    }
+    Ok(())
+}
+
+fn write_array<T: Display>(
+    f: &mut Formatter<'_>,
+    prefix: char,
+    values: impl IntoIterator<Item = T>,
+) -> fmt::Result {
+    write!(f, "[{prefix};")?;
+    write_separated(f, values)?;
+    f.write_char(']')
+}
+
+fn write_quoted(f: &mut Formatter<'_>, s: &str) -> fmt::Result {
+    f.write_char('"')?;
+    for c in s.chars() {
+        if c == '"' || c == '\\' {
+            f.write_char('\\')?;
+        }
+        f.write_char(c)?;
+    }
+    f.write_char('"')
+}
+
+/// Whether a compound key can be written without quotes.
+fn is_unquoted(key: &str) -> bool {
+    !key.is_empty()
+        && key
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.' | '+'))
+}
@@ -0,0 +1,39 @@
+//! Decoding of varint-packed byte arrays. Sponge schematics and litematica
+//! store block data as a `ByteArray` of palette indices, each encoded as a
+//! variable length integer the same way as the Minecraft protocol's VarInt.
+//!
+//! ```
+//! use fastnbt::varint::decode_varints;
+//!
+//! // 300 does not fit in 7 bits, so takes two bytes.
+//! let ids: Vec<i32> = decode_varints(&[1, 0xac, 0x02, 0]).collect();
+//! assert_eq!(ids, [1, 300, 0]);
+//! ```
+
+/// Decode LEB128-style varints from `data`. Each byte holds 7 bits of the
+/// value, least significant group first, with the top bit set if more bytes
+/// follow. Like Minecraft's VarInt, values are 32 bit and at most 5 bytes
+/// long; bits beyond 32 are discarded.
+///
+/// If `data` ends part way through a value that value is not returned. A value
+/// still continuing after 5 bytes is malformed, so decoding stops there, as
+/// the rest of the data can't be trusted.
+pub fn decode_varints(data: &[u8]) -> impl Iterator<Item = i32> + '_ {
+    const MAX_LEN: usize = 5;
+    let mut bytes = data.iter();
+
+    std::iter::from_fn(move || {
+        let mut value = 0u32;
+
+        for i in 0..MAX_LEN {
+            let b = *bytes.next()?;
+            value |= ((b & 0x7f) as u32) << (i * 7);
+            if b & 0x80 == 0 {
+                return Some(value as i32);
+            }
+        }
+
+        bytes = [].iter();
+        None
+    })
+}
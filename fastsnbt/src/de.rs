@@ -10,6 +10,13 @@
 //! If there are escaped characters in the string, it will have
 //! to own the resulting string.
 //!
+//! ## Lenient parsing
+//! Hand-written sNBT often contains whitespace, comments and trailing commas.
+//! These are rejected by default. Use
+//! [`from_str_lenient`](crate::from_str_lenient) to skip whitespace and line
+//! comments starting with `//` or `#`, and to allow a single trailing comma
+//! before a closing `]` or `}`.
+//!
 //! ## Uuid
 //! Because [`Deserializer`] expects a human-readable format,
 //! `UUID`s are expected to be strings.
@@ -19,17 +26,31 @@ use std::{borrow::Cow, marker::PhantomData};
 use byteorder::{WriteBytesExt, BE};
 use serde::{de::{self, SeqAccess, MapAccess, IntoDeserializer, value::{BorrowedStrDeserializer, SeqAccessDeserializer, BytesDeserializer}, Visitor, DeserializeSeed}, forward_to_deserialize_any};
 
-use crate::{error::Error, parser::{parse_i8, parse_i16, parse_i32, parse_i64, parse_bool, parse_f32, parse_f64, parse_str}, LONG_ARRAY_TOKEN, INT_ARRAY_TOKEN, BYTE_ARRAY_TOKEN};
+use crate::{error::Error, parser::{parse_i8, parse_i16, parse_i32, parse_i64, parse_bool, parse_f32, parse_f64, parse_str, skip_ignored}, LONG_ARRAY_TOKEN, INT_ARRAY_TOKEN, BYTE_ARRAY_TOKEN};
 
 pub struct Deserializer<'de> {
     pub(crate) input: &'de str,
     pub(crate) pos: usize,
+    pub(crate) lenient: bool,
 }
 
 impl<'a, 'de: 'a> Deserializer<'de> {
     #[allow(clippy::should_implement_trait)]
     pub fn from_str(input: &'de str) -> Self {
-        Self { input, pos: 0, }
+        Self { input, pos: 0, lenient: false }
+    }
+
+    /// Create a deserializer that skips whitespace and comments, and allows
+    /// trailing commas. See the [module](self) docs.
+    pub fn from_str_lenient(input: &'de str) -> Self {
+        Self { input, pos: 0, lenient: true }
+    }
+
+    /// Skip whitespace and comments if parsing leniently.
+    pub(crate) fn skip_ignored(&mut self) {
+        if self.lenient {
+            self.advance(skip_ignored(self.input));
+        }
     }
 
     pub(crate) fn advance(&mut self, new_input: &'de str) {
@@ -47,7 +68,15 @@ impl<'a, 'de: 'a> Deserializer<'de> {
         }
     }
 
+    /// Check if a comma we just consumed was trailing, ie followed by the
+    /// closing `end` of the collection.
+    pub(crate) fn trailing_comma(&mut self, end: char) -> bool {
+        self.skip_ignored();
+        self.input.starts_with(end)
+    }
+
     pub(crate) fn end_delimiter(&'a mut self, end: &'de str) -> Result<&'de str, Error> {
+        self.skip_ignored();
         if !self.input.starts_with(end) {
             Err(Error::expected_collection_end())
         } else {
@@ -63,6 +92,7 @@ impl<'de, 'a> de::Deserializer<'de> for &'a mut Deserializer<'de> {
     where
         V: de::Visitor<'de>
     {
+        self.skip_ignored();
         if self.input.is_empty() {
             return Err(Error::unexpected_eof());
         }
@@ -135,6 +165,7 @@ impl<'de, 'a> de::Deserializer<'de> for &'a mut Deserializer<'de> {
     where
         V: de::Visitor<'de>
     {
+        self.skip_ignored();
         let (input, value) = if let Ok((input, v)) = parse_str(self.input) {
             visitor.visit_enum(v.as_ref().into_deserializer()).map(|v| (input, v))
         } else {
@@ -171,6 +202,7 @@ impl<'a, 'de> SeqAccess<'de> for CommaSep<'a, 'de> {
     where
         T: de::DeserializeSeed<'de>
     {
+        self.de.skip_ignored();
         if self.de.input.starts_with(']') {
             return Ok(None);
         }
@@ -178,7 +210,10 @@ impl<'a, 'de> SeqAccess<'de> for CommaSep<'a, 'de> {
         if !self.first && self.de.input.chars().next().ok_or(Error::unexpected_eof())? != ',' {
             return Err(Error::expected_comma());
         } else if !self.first {
-            self.de.advance(&self.de.input[','.len_utf8()..])
+            self.de.advance(&self.de.input[','.len_utf8()..]);
+            if self.de.lenient && self.de.trailing_comma(']') {
+                return Ok(None);
+            }
         }
         self.first = false;
         seed.deserialize(&mut *self.de).map(Some)
@@ -192,6 +227,7 @@ impl<'a, 'de> MapAccess<'de> for CommaSep<'a, 'de> {
     where
         K: de::DeserializeSeed<'de>
     {
+        self.de.skip_ignored();
         if self.de.input.starts_with('}') {
             return Ok(None);
         }
@@ -200,6 +236,9 @@ impl<'a, 'de> MapAccess<'de> for CommaSep<'a, 'de> {
             return Err(Error::expected_comma());
         } else if !self.first {
             self.de.advance(&self.de.input[','.len_utf8()..]);
+            if self.de.lenient && self.de.trailing_comma('}') {
+                return Ok(None);
+            }
         }
         self.first = false;
         seed.deserialize(&mut *self.de).map(Some)
@@ -209,6 +248,7 @@ impl<'a, 'de> MapAccess<'de> for CommaSep<'a, 'de> {
     where
         V: de::DeserializeSeed<'de>
     {
+        self.de.skip_ignored();
         if self.de.input.chars().next().ok_or(Error::unexpected_eof())? != ':' {
             return Err(Error::expected_colon());
         } else {
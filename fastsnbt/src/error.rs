@@ -37,6 +37,12 @@ impl From<std::io::Error> for Error {
     }
 }
 
+impl From<fastnbt::error::Error> for Error {
+    fn from(e: fastnbt::error::Error) -> Self {
+        Error(format!("nbt error: {}", e))
+    }
+}
+
 impl Error {
     pub(crate) fn invalid_input(pos: usize) -> Error {
         Error(format!("invalid input at {}", pos))
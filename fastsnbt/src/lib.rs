@@ -6,6 +6,8 @@
 //! - For documentation of serde (de)serialization, see [`ser`] and [`de`].
 //! - See [`fastnbt`](https://crates.io/crates/fastnbt) for most
 //!   NBT related things.
+//! - To convert between NBT and sNBT directly, see [`nbt_to_snbt`] and
+//!   [`snbt_to_nbt`].
 //!
 //! # Example
 //! ```
@@ -62,12 +64,36 @@ where
     Ok(t)
 }
 
+/// Deserialize into a `T` from some hand-written sNBT data. Unlike
+/// [`from_str`], this skips whitespace and line comments, and allows trailing
+/// commas. See the [`de`] module for more information.
+pub fn from_str_lenient<'a, T>(input: &'a str) -> Result<T>
+where
+    T: serde::de::Deserialize<'a>,
+{
+    let mut des = Deserializer::from_str_lenient(input);
+    let t = T::deserialize(&mut des)?;
+    des.skip_ignored();
+    if !des.input.is_empty() {
+        return Err(error::Error::input_not_consumed());
+    }
+    Ok(t)
+}
+
 /// Serialize some `T` into some sNBT string. This produces
 /// valid utf-8. See the [`ser`] module for more information.
 pub fn to_vec<T: ?Sized + Serialize>(value: &T) -> Result<Vec<u8>> {
-    let mut serializer = Serializer { writer: Vec::new() };
+    let mut serializer = Serializer::new(Vec::new());
+    value.serialize(&mut serializer)?;
+    Ok(serializer.into_inner())
+}
+
+/// Serialize some `T` into pretty sNBT, indenting nested entries by four
+/// spaces. Use [`ser::Serializer::pretty`] to choose the indentation.
+pub fn to_vec_pretty<T: ?Sized + Serialize>(value: &T) -> Result<Vec<u8>> {
+    let mut serializer = Serializer::pretty(Vec::new(), 4);
     value.serialize(&mut serializer)?;
-    Ok(serializer.writer)
+    Ok(serializer.into_inner())
 }
 
 /// Serialize some `T` into a sNBT string. See the [`ser`]
@@ -80,3 +106,30 @@ pub fn to_string<T: ?Sized + Serialize>(value: &T) -> Result<String> {
     };
     Ok(string)
 }
+
+/// Serialize some `T` into a pretty sNBT string. See [`to_vec_pretty`].
+pub fn to_string_pretty<T: ?Sized + Serialize>(value: &T) -> Result<String> {
+    let vec = to_vec_pretty(value)?;
+    let string = unsafe {
+        // We do not emit invalid UTF-8.
+        String::from_utf8_unchecked(vec)
+    };
+    Ok(string)
+}
+
+/// Convert some binary NBT data into the equivalent sNBT string. This goes via
+/// [`fastnbt::Value`], so NBT arrays are written as `[B;...]`, `[I;...]` and
+/// `[L;...]` and the name of the root compound is dropped.
+pub fn nbt_to_snbt(bytes: &[u8]) -> Result<String> {
+    let value: fastnbt::Value = fastnbt::from_bytes(bytes)?;
+    to_string(&value)
+}
+
+/// Convert some sNBT into the equivalent binary NBT data. This goes via
+/// [`fastnbt::Value`], so `[B;...]`, `[I;...]` and `[L;...]` become the matching
+/// NBT array tags. The root of the sNBT must be a compound, and is written with
+/// an empty name.
+pub fn snbt_to_nbt(s: &str) -> Result<Vec<u8>> {
+    let value: fastnbt::Value = from_str(s)?;
+    Ok(fastnbt::to_bytes(&value)?)
+}
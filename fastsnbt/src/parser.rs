@@ -142,3 +142,19 @@ fn decimal(input: &str) -> IResult<&str, &str> {
         ))
     )))(input)
 }
+
+/// Skip whitespace and line comments starting with `//` or `#`. Only used
+/// when parsing leniently.
+pub fn skip_ignored(mut input: &str) -> &str {
+    loop {
+        input = input.trim_start();
+        if input.starts_with("//") || input.starts_with('#') {
+            input = match input.find('\n') {
+                Some(i) => &input[i + 1..],
+                None => "",
+            };
+        } else {
+            return input;
+        }
+    }
+}
@@ -8,6 +8,18 @@
 //!
 //! The [de](crate::de) module contains more information about (de)serialization.
 //!
+//! ## Pretty printing
+//! [`to_string`](crate::to_string) writes the most compact form. Use
+//! [`to_string_pretty`](crate::to_string_pretty) or [`Serializer::pretty`] to
+//! put compound entries and list elements on their own indented lines.
+//! Pretty output can be read back with
+//! [`from_str_lenient`](crate::from_str_lenient).
+//!
+//! ## Option and unit
+//! sNBT has no null value. Compound entries that are `None`, `()` or a unit
+//! struct are left out of the output, so an `Option` field round trips as a
+//! missing key. Serializing one of these on its own is an error.
+//!
 //! ## Uuid
 //! Because sNBT is a human-readable format,
 //! `Uuid`s are represented as strings.
@@ -22,6 +34,7 @@ use self::name_serializer::NameSerializer;
 
 mod name_serializer;
 mod array_serializer;
+mod none_probe;
 
 pub(crate) fn write_escaped_str<W: Write>(mut writer: W, v: &str) -> Result<(), Error> {
     writer.write_all(b"\"")?;
@@ -47,8 +60,51 @@ pub(crate) fn write_escaped_str<W: Write>(mut writer: W, v: &str) -> Result<(),
     Ok(writer.write_all(b"\"")?)
 }
 
+/// Serializer for sNBT. By default this writes the most compact form. Use
+/// [`Serializer::pretty`] for output spread over multiple lines.
 pub struct Serializer<W> {
     pub(crate) writer: W,
+    pub(crate) indent: Option<usize>,
+    pub(crate) depth: usize,
+}
+
+impl<W: Write> Serializer<W> {
+    /// Create a serializer writing compact sNBT to `writer`.
+    pub fn new(writer: W) -> Self {
+        Self {
+            writer,
+            indent: None,
+            depth: 0,
+        }
+    }
+
+    /// Create a serializer writing pretty sNBT to `writer`. Compound entries
+    /// and list elements are put on their own line, and each level of nesting
+    /// is indented by `indent` spaces. NBT arrays such as `[B; 1b, 2b]` are
+    /// kept on a single line.
+    pub fn pretty(writer: W, indent: usize) -> Self {
+        Self {
+            writer,
+            indent: Some(indent),
+            depth: 0,
+        }
+    }
+
+    /// Get the underlying writer back.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+
+    /// Start a new line at the current depth, if pretty printing.
+    fn write_newline(&mut self) -> Result<(), Error> {
+        if let Some(indent) = self.indent {
+            self.writer.write_all(b"\n")?;
+            for _ in 0..indent * self.depth {
+                self.writer.write_all(b" ")?;
+            }
+        }
+        Ok(())
+    }
 }
 
 impl<'a, W: 'a + Write> ser::Serializer for &'a mut Serializer<W> {
@@ -261,6 +317,9 @@ impl<'a, W: 'a + Write> ser::Serializer for &'a mut Serializer<W> {
 
 pub struct ArraySerializer<'a, W> {
     first: bool,
+    // NBT arrays (with a prefix like `B;`) stay on one line when pretty
+    // printing, lists get a line per element.
+    is_list: bool,
     serializer: &'a mut Serializer<W>,
 }
 
@@ -268,7 +327,11 @@ impl<'a, W: Write> ArraySerializer<'a, W> {
     pub fn new(prefix: &'static str, serializer: &'a mut Serializer<W>) -> Result<ArraySerializer<'a, W>, Error> {
         serializer.writer.write_all(b"[")?;
         serializer.writer.write_all(prefix.as_bytes())?;
-        Ok(Self { first: false, serializer })
+        let is_list = prefix.is_empty();
+        if is_list {
+            serializer.depth += 1;
+        }
+        Ok(Self { first: false, is_list, serializer })
     }
 }
 
@@ -285,10 +348,21 @@ impl<'a, W: Write> SerializeSeq for ArraySerializer<'a, W> {
         } else {
             self.serializer.writer.write_all(b",")?;
         }
+        if self.is_list {
+            self.serializer.write_newline()?;
+        } else if self.serializer.indent.is_some() {
+            self.serializer.writer.write_all(b" ")?;
+        }
         value.serialize(&mut *self.serializer)
     }
 
     fn end(self) -> Result<Self::Ok, Self::Error> {
+        if self.is_list {
+            self.serializer.depth -= 1;
+            if self.first {
+                self.serializer.write_newline()?;
+            }
+        }
         Ok(self.serializer.writer.write_all(b"]")?)
     }
 }
@@ -380,6 +454,11 @@ impl<'a, W: Write + 'a> SerializeMap for CompoundSerializer<'a, W> {
             Error::bespoke("serialize_value called before serialize_key".to_string())
         })?;
 
+        // sNBT has no null, so entries without a value are left out.
+        if none_probe::is_none(value) {
+            return Ok(());
+        }
+
         if !self.has_first {
             self.has_first = true;
         } else {
@@ -406,9 +485,15 @@ impl<'a, W: Write + 'a> SerializeMap for CompoundSerializer<'a, W> {
                 if !self.is_compound {
                     self.is_compound = true;
                     self.serializer.writer.write_all(b"{")?;
+                    self.serializer.depth += 1;
                 }
+                self.serializer.write_newline()?;
                 self.serializer.writer.write_all(&name)?;
-                self.serializer.writer.write_all(b":")?;
+                if self.serializer.indent.is_some() {
+                    self.serializer.writer.write_all(b": ")?;
+                } else {
+                    self.serializer.writer.write_all(b":")?;
+                }
                 value.serialize(&mut *self.serializer)
             }
         }
@@ -416,7 +501,12 @@ impl<'a, W: Write + 'a> SerializeMap for CompoundSerializer<'a, W> {
 
     fn end(self) -> Result<Self::Ok, Self::Error> {
         if self.is_compound {
+            self.serializer.depth -= 1;
+            self.serializer.write_newline()?;
             self.serializer.writer.write_all(b"}")?;
+        } else if !self.has_first {
+            // Nothing was written, so this is an empty compound.
+            self.serializer.writer.write_all(b"{}")?;
         }
         Ok(())
     }
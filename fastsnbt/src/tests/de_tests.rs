@@ -94,3 +94,69 @@ fn test_longarray() {
     let data: LongArray = from_str(input).unwrap();
     assert_eq!(LongArray::new(vec![1,2,-3]), data);
 }
+
+#[test]
+fn test_lenient_comments_and_trailing_commas() {
+    #[derive(Deserialize, Debug, PartialEq)]
+    struct Data {
+        name: String,
+        count: i8,
+        tags: Vec<String>,
+        longs: LongArray,
+    }
+
+    let input = r#"
+    // A hand-written compound.
+    {
+        name: "stone", # the block
+        count: 64b,
+        tags: [
+            "a",
+            "b", // trailing comma
+        ],
+        longs: [L; 1l, 2l,],
+    }
+    "#;
+    let data: Data = crate::from_str_lenient(input).unwrap();
+    assert_eq!(
+        Data {
+            name: "stone".into(),
+            count: 64,
+            tags: vec!["a".into(), "b".into()],
+            longs: LongArray::new(vec![1, 2]),
+        },
+        data
+    );
+}
+
+#[test]
+fn test_lenient_rejects_double_trailing_comma() {
+    assert!(crate::from_str_lenient::<Vec<i32>>("[1,,]").is_err());
+    assert!(crate::from_str_lenient::<Vec<i32>>("[,]").is_err());
+}
+
+#[test]
+fn test_strict_rejects_comments_and_trailing_commas() {
+    assert!(from_str::<Vec<i32>>("[1,2,]").is_err());
+    assert!(from_str::<Vec<i32>>("[1,2]// comment").is_err());
+    assert!(from_str::<Vec<i32>>("[1, 2]").is_err());
+}
+
+#[test]
+fn test_lenient_reads_pretty_output() {
+    #[derive(serde::Serialize, Deserialize, Debug, PartialEq)]
+    struct Data {
+        a: i32,
+        b: Vec<String>,
+        bytes: ByteArray,
+    }
+
+    let data = Data {
+        a: 1,
+        b: vec!["x".into(), "y".into()],
+        bytes: ByteArray::new(vec![1, 2]),
+    };
+    let snbt = crate::to_string_pretty(&data).unwrap();
+    let de: Data = crate::from_str_lenient(&snbt).unwrap();
+    assert_eq!(data, de);
+}
@@ -28,3 +28,38 @@ fn test_mixed() {
     let deserialized: MixedStruct = from_str(&serialized).unwrap();
     assert_eq!(deserialized, data);
 }
+
+#[test]
+fn test_nbt_snbt_arrays() {
+    let nbt = fastnbt::to_bytes(&fastnbt::nbt!({
+        "bytes": [B; 1, -2],
+        "ints": [I; 3],
+        "longs": [L; 4, 5],
+    }))
+    .unwrap();
+
+    let snbt = crate::nbt_to_snbt(&nbt).unwrap();
+    assert!(snbt.contains("\"bytes\":[B;1b,-2b]"));
+    assert!(snbt.contains("\"ints\":[I;3]"));
+    assert!(snbt.contains("\"longs\":[L;4l,5l]"));
+
+    let back: fastnbt::Value = fastnbt::from_bytes(&crate::snbt_to_nbt(&snbt).unwrap()).unwrap();
+    assert_eq!(back["bytes"], fastnbt::Value::ByteArray(fastnbt::ByteArray::new(vec![1, -2])));
+    assert_eq!(back["ints"], fastnbt::Value::IntArray(IntArray::new(vec![3])));
+    assert_eq!(back["longs"], fastnbt::Value::LongArray(fastnbt::LongArray::new(vec![4, 5])));
+}
+
+#[test]
+fn test_chunk_round_trip_through_snbt() {
+    let nbt = include_bytes!("../../../fastnbt/src/test/resources/chunk.nbt");
+    let original: fastnbt::Value = fastnbt::from_bytes(nbt).unwrap();
+
+    let snbt = crate::nbt_to_snbt(nbt).unwrap();
+    let round_tripped: fastnbt::Value = fastnbt::from_bytes(&crate::snbt_to_nbt(&snbt).unwrap()).unwrap();
+    assert_eq!(original, round_tripped);
+}
+
+#[test]
+fn test_snbt_to_nbt_needs_compound() {
+    assert!(crate::snbt_to_nbt("[1,2]").is_err());
+}
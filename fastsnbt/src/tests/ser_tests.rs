@@ -127,3 +127,105 @@ fn test_struct_arrays() {
     let snbt = to_string(&data).unwrap();
     assert_eq!("{\"bytes\":[B;-20b,10b],\"longs\":[L;-40l,10000l]}", snbt);
 }
+
+#[test]
+fn test_option_some_field() {
+    #[derive(Serialize)]
+    struct OptionStruct {
+        a: Option<i32>,
+    }
+
+    let data = OptionStruct { a: Some(1) };
+    let snbt = to_string(&data).unwrap();
+    assert_eq!("{\"a\":1}", snbt);
+}
+
+#[test]
+fn test_option_none_field_is_omitted() {
+    #[derive(Serialize)]
+    struct OptionStruct {
+        a: Option<i32>,
+        b: i32,
+        c: Option<i32>,
+    }
+
+    let data = OptionStruct { a: None, b: 2, c: None };
+    let snbt = to_string(&data).unwrap();
+    assert_eq!("{\"b\":2}", snbt);
+
+    #[derive(Serialize)]
+    struct OnlyNone {
+        a: Option<i32>,
+    }
+
+    let snbt = to_string(&OnlyNone { a: None }).unwrap();
+    assert_eq!("{}", snbt);
+}
+
+#[test]
+fn test_unit_field_is_omitted() {
+    #[derive(Serialize)]
+    struct Unit;
+
+    #[derive(Serialize)]
+    struct UnitStruct {
+        a: (),
+        b: Unit,
+        c: u8,
+    }
+
+    let data = UnitStruct { a: (), b: Unit, c: 1 };
+    let snbt = to_string(&data).unwrap();
+    assert_eq!("{\"c\":1b}", snbt);
+}
+
+#[test]
+fn test_top_level_none_errors() {
+    assert!(to_string(&None::<i32>).is_err());
+    assert!(to_string(&()).is_err());
+}
+
+#[test]
+fn test_pretty() {
+    #[derive(Serialize)]
+    struct Inner {
+        a: i32,
+    }
+
+    #[derive(Serialize)]
+    struct Outer {
+        inner: Inner,
+        list: Vec<i32>,
+        empty: Vec<i32>,
+        bytes: ByteArray,
+    }
+
+    let data = Outer {
+        inner: Inner { a: 1 },
+        list: vec![1, 2],
+        empty: vec![],
+        bytes: ByteArray::new(vec![1, 2]),
+    };
+    let snbt = crate::to_string_pretty(&data).unwrap();
+    let expected = r#"{
+    "inner": {
+        "a": 1
+    },
+    "list": [
+        1,
+        2
+    ],
+    "empty": [],
+    "bytes": [B; 1b, 2b]
+}"#;
+    assert_eq!(expected, snbt);
+}
+
+#[test]
+fn test_pretty_custom_indent() {
+    let data = vec![vec![1]];
+    let mut ser = crate::ser::Serializer::pretty(Vec::new(), 2);
+    data.serialize(&mut ser).unwrap();
+    let snbt = String::from_utf8(ser.into_inner()).unwrap();
+    assert_eq!("[\n  [\n    1\n  ]\n]", snbt);
+}
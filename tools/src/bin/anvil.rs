@@ -1,10 +1,9 @@
 use clap::{App, Arg, ArgMatches, SubCommand};
 use env_logger::Env;
-use fastanvil::RenderedPalette;
-use fastanvil::{render_region, CCoord, HeightMode, RCoord, RegionLoader, Rgba, TopShadeRenderer};
+use fastanvil::{load_rendered_palette, RenderedPalette};
+use fastanvil::{render_region, CCoord, HeightMode, RCoord, RegionLoader, TopShadeRenderer};
 
 use fastanvil::RegionFileLoader;
-use flate2::read::GzDecoder;
 use log::{error, info, warn};
 use rayon::prelude::*;
 use std::path::{Path, PathBuf};
@@ -64,48 +63,7 @@ fn get_palette(path: Option<&str>) -> Result<RenderedPalette> {
     };
 
     let f = std::fs::File::open(path)?;
-    let f = GzDecoder::new(f);
-    let mut ar = tar::Archive::new(f);
-    let mut grass = Err("no grass colour map");
-    let mut foliage = Err("no foliage colour map");
-    let mut blockstates = Err("no blockstate palette");
-
-    for file in ar.entries()? {
-        let mut file = file?;
-        match file.path()?.to_str().ok_or("invalid path in TAR")? {
-            "grass-colourmap.png" => {
-                use std::io::Read;
-                let mut buf = vec![];
-                file.read_to_end(&mut buf)?;
-
-                grass = Ok(
-                    image::load(std::io::Cursor::new(buf), image::ImageFormat::Png)?.into_rgba8(),
-                );
-            }
-            "foliage-colourmap.png" => {
-                use std::io::Read;
-                let mut buf = vec![];
-                file.read_to_end(&mut buf)?;
-
-                foliage = Ok(
-                    image::load(std::io::Cursor::new(buf), image::ImageFormat::Png)?.into_rgba8(),
-                );
-            }
-            "blockstates.json" => {
-                let json: std::collections::HashMap<String, Rgba> = serde_json::from_reader(file)?;
-                blockstates = Ok(json);
-            }
-            _ => {}
-        }
-    }
-
-    let p = RenderedPalette {
-        blockstates: blockstates?,
-        grass: grass?,
-        foliage: foliage?,
-    };
-
-    Ok(p)
+    Ok(load_rendered_palette(f)?)
 }
 
 fn render(args: &ArgMatches) -> Result<()> {
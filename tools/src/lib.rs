@@ -1,6 +1,6 @@
 use fastanvil::{
-    tex::{Blockstate, Model, Render, Renderer, Texture},
-    Rgba,
+    tex::{average_colour, Blockstate, Model, Render, Renderer, Texture},
+    PaletteEntry, RenderedPalette, Tint,
 };
 use flate2::write::GzEncoder;
 use std::error::Error;
@@ -21,36 +21,37 @@ impl Display for ErrorMessage {
     }
 }
 
-fn avg_colour(rgba_data: &[u8]) -> Rgba {
-    let mut avg = [0f64; 4];
-    let mut count = 0;
-
-    for p in rgba_data.chunks(4) {
-        // alpha is reasonable.
-        avg[0] += ((p[0] as u64) * (p[0] as u64)) as f64;
-        avg[1] += ((p[1] as u64) * (p[1] as u64)) as f64;
-        avg[2] += ((p[2] as u64) * (p[2] as u64)) as f64;
-        avg[3] += ((p[3] as u64) * (p[3] as u64)) as f64;
-        count += 1;
+/// The colourmap the game tints a block with, if its model is tinted at all.
+fn tint(id: &str, tinted: bool) -> Tint {
+    if !tinted {
+        return Tint::None;
     }
 
-    [
-        (avg[0] / count as f64).sqrt() as u8,
-        (avg[1] / count as f64).sqrt() as u8,
-        (avg[2] / count as f64).sqrt() as u8,
-        (avg[3] / count as f64).sqrt() as u8,
-    ]
+    match id.trim_start_matches("minecraft:") {
+        id if id.ends_with("leaves") || id == "vine" => Tint::Foliage,
+        "water" | "bubble_column" | "water_cauldron" => Tint::Water,
+        "grass_block" | "grass" | "short_grass" | "tall_grass" | "fern" | "large_fern"
+        | "potted_fern" | "sugar_cane" | "pink_petals" | "bush" => Tint::Grass,
+        // Other tinted blocks like redstone wire, stems and lily pads are
+        // coloured by their state or a fixed colour, not the biome.
+        _ => Tint::None,
+    }
 }
 
+/// Load a texture of any size. Animated textures are a strip of square
+/// frames, so only the first frame is kept.
 fn load_texture(path: &Path) -> Result<Texture> {
     let img = image::open(path)?;
-    let img = img.to_rgba8();
+    let mut img = img.to_rgba8();
+
+    let (width, height) = img.dimensions();
+    if height > width && height % width == 0 {
+        img = image::imageops::crop(&mut img, 0, 0, width, width).to_image();
+    } else if width != height {
+        return Err(Box::new(ErrorMessage("texture was not square")));
+    }
 
-    //if img.dimensions() == (16, 16) {
     Ok(img.into_raw())
-    // } else {
-    //     Err(Box::new(ErrorMessage("texture was not 16 by 16")))
-    // }
 }
 
 fn load_blockstates(blockstates_path: &Path) -> Result<HashMap<String, Blockstate>> {
@@ -337,8 +338,10 @@ pub fn make_palette(mc_jar_path: &Path) -> Result<()> {
             if let Some(texture) = texture {
                 println!("mapped {} to {}", blockstate, tex);
                 mapped += 1;
-                let col = avg_colour(texture.as_slice());
-                return Some(col);
+                return Some(PaletteEntry {
+                    colour: average_colour(texture.as_slice()),
+                    tint: Tint::None,
+                });
             }
         }
 
@@ -365,17 +368,23 @@ pub fn make_palette(mc_jar_path: &Path) -> Result<()> {
         match bs {
             Blockstate::Variants(vars) => {
                 for props in vars.keys() {
-                    let res = renderer.get_top(name, props);
+                    let res = renderer.get_top_rgba(name, props);
                     match res {
-                        Ok(texture) => {
-                            let col = avg_colour(texture.as_slice());
+                        Ok(colour) => {
+                            let tinted = renderer.is_tinted(name, props).unwrap_or(false);
 
                             // We want to add the pipe if the props are anything
                             // but empty.
                             let description =
                                 (*name).clone() + if props.is_empty() { "" } else { "|" } + props;
 
-                            palette.insert(description, col);
+                            palette.insert(
+                                description,
+                                PaletteEntry {
+                                    colour,
+                                    tint: tint(name, tinted),
+                                },
+                            );
                             success += 1;
                         }
                         Err(_) => {
@@ -388,8 +397,26 @@ pub fn make_palette(mc_jar_path: &Path) -> Result<()> {
                 }
             }
             Blockstate::Multipart(_) => {
-                if let Some(c) = try_mappings((*name).clone()) {
-                    palette.insert((*name).clone(), c);
+                // We don't know every combination of properties a multipart
+                // block can have, so render the parts that always apply and
+                // use that for the block regardless of its properties.
+                match renderer.get_top_rgba(name, "") {
+                    Ok(colour) => {
+                        let tinted = renderer.is_tinted(name, "").unwrap_or(false);
+                        palette.insert(
+                            (*name).clone(),
+                            PaletteEntry {
+                                colour,
+                                tint: tint(name, tinted),
+                            },
+                        );
+                        success += 1;
+                    }
+                    Err(_) => {
+                        if let Some(c) = try_mappings((*name).clone()) {
+                            palette.insert((*name).clone(), c);
+                        }
+                    }
                 }
             }
         }
@@ -406,6 +433,13 @@ pub fn make_palette(mc_jar_path: &Path) -> Result<()> {
 
     let mut ar = tar::Builder::new(f);
 
+    let version = RenderedPalette::VERSION.to_string();
+    let mut header = tar::Header::new_gnu();
+    header.set_size(version.len() as u64);
+    header.set_cksum();
+    header.set_mode(0o666);
+    ar.append_data(&mut header, "version", version.as_bytes())?;
+
     let grass_colourmap = &assets.join("textures").join("colormap").join("grass.png");
     ar.append_file(
         "grass-colourmap.png",